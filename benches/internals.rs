@@ -0,0 +1,110 @@
+// Microbenchmarks of the primitives behind KeyGen/Encaps/Decaps, for attributing the top-level
+// timings in benchmark.rs. Requires the `bench-internals` feature:
+//
+// $ cargo bench --bench internals --features bench-internals
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{Rng, SeedableRng};
+
+use fips203::internals::{self, Poly};
+
+fn random_poly(rng: &mut impl Rng) -> Poly {
+    Poly::from_coeffs(&core::array::from_fn(|_| rng.gen_range(0..3329)))
+}
+
+pub fn bench_ntt(c: &mut Criterion) {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (f, g) = (random_poly(&mut rng), random_poly(&mut rng));
+
+    let mut group = c.benchmark_group("ntt");
+    group.bench_function("ntt", |b| b.iter(|| internals::ntt(black_box(&f))));
+    group.bench_function("ntt_inv", |b| b.iter(|| internals::ntt_inv(black_box(&f))));
+    group.bench_function("multiply_ntts", |b| {
+        b.iter(|| internals::multiply_ntts(black_box(&f), &g))
+    });
+    group.finish();
+}
+
+pub fn bench_sampling(c: &mut Criterion) {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let rho: [u8; 32] = rng.gen();
+    let bytes: [u8; 192] = core::array::from_fn(|_| rng.gen());
+
+    let mut group = c.benchmark_group("sampling");
+    group.bench_function("sample_ntt", |b| b.iter(|| internals::sample_ntt(black_box(&rho), 0, 1)));
+    for eta in [2, 3] {
+        let bytes = &bytes[..64 * eta as usize];
+        group.bench_function(format!("sample_poly_cbd eta={eta}"), |b| {
+            b.iter(|| internals::sample_poly_cbd(eta, black_box(bytes)))
+        });
+    }
+    group.finish();
+}
+
+pub fn bench_compress(c: &mut Criterion) {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let f = random_poly(&mut rng);
+
+    let mut group = c.benchmark_group("compress");
+    for d in [1, 4, 5, 10, 11] {
+        group.bench_function(format!("compress d={d}"), |b| {
+            b.iter(|| {
+                let mut x = f;
+                internals::compress(d, black_box(&mut x));
+                x
+            })
+        });
+    }
+    group.finish();
+}
+
+pub fn bench_byte_fns(c: &mut Criterion) {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let f = random_poly(&mut rng);
+
+    let mut group = c.benchmark_group("byte_fns");
+    for d in [1, 4, 5, 10, 11, 12] {
+        // As in K-PKE, d < 12 encodes compressed coefficients
+        let mut f_d = f;
+        if d < 12 {
+            internals::compress(d, &mut f_d);
+        }
+        let mut bytes = vec![0u8; 32 * d as usize];
+        internals::byte_encode(d, &f_d, &mut bytes).unwrap();
+        group.bench_function(format!("byte_encode d={d}"), |b| {
+            b.iter(|| internals::byte_encode(d, black_box(&f_d), &mut bytes))
+        });
+        let mut out = Poly::from_coeffs(&[0; 256]);
+        group.bench_function(format!("byte_decode d={d}"), |b| {
+            b.iter(|| internals::byte_decode(d, black_box(&bytes), &mut out))
+        });
+    }
+    group.finish();
+}
+
+pub fn bench_hash(c: &mut Criterion) {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let seed: [u8; 32] = rng.gen();
+    let ek: Vec<u8> = (0..1184).map(|_| rng.gen()).collect(); // ML-KEM-768 encaps key
+    let ct: Vec<u8> = (0..1088).map(|_| rng.gen()).collect(); // ML-KEM-768 ciphertext
+
+    let mut group = c.benchmark_group("hash");
+    group.bench_function("g", |b| b.iter(|| internals::g(&[black_box(&seed), &seed])));
+    group.bench_function("h (768 ek)", |b| b.iter(|| internals::h(black_box(&ek))));
+    group.bench_function("j (768 ct)", |b| b.iter(|| internals::j(&[black_box(&seed), &ct])));
+    for eta in [2, 3] {
+        let mut out = vec![0u8; 64 * eta];
+        group.bench_function(format!("prf eta={eta}"), |b| {
+            b.iter(|| internals::prf(black_box(&seed), 0, &mut out))
+        });
+    }
+    // SampleNTT consumes 504 bytes (three SHAKE128 blocks) or slightly more
+    let mut out = [0u8; 504];
+    group.bench_function("xof 504 bytes", |b| {
+        b.iter(|| internals::xof(black_box(&seed), 0, 1, &mut out))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ntt, bench_sampling, bench_compress, bench_byte_fns, bench_hash);
+criterion_main!(benches);
@@ -0,0 +1,329 @@
+#ifndef FIPS203_H
+#define FIPS203_H
+
+/* Generated by cbindgen from ffi/src/lib.rs; do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * Success.
+ */
+#define FIPS203_OK 0
+
+/**
+ * A required pointer was null.
+ */
+#define FIPS203_ERR_NULL_POINTER -1
+
+/**
+ * A buffer length does not match the parameter set.
+ */
+#define FIPS203_ERR_LENGTH -2
+
+/**
+ * An input failed validation, e.g. an encapsulation key coefficient not below q.
+ */
+#define FIPS203_ERR_INVALID -3
+
+/**
+ * The random number generator (or callback) failed.
+ */
+#define FIPS203_ERR_RNG -4
+
+/**
+ * Any other internal error.
+ */
+#define FIPS203_ERR_INTERNAL -5
+
+/**
+ * The length of an ML-KEM-512 encapsulation key.
+ */
+#define FIPS203_ML_KEM_512_EK_LEN 800
+
+/**
+ * The length of an ML-KEM-512 decapsulation key.
+ */
+#define FIPS203_ML_KEM_512_DK_LEN 1632
+
+/**
+ * The length of an ML-KEM-512 ciphertext.
+ */
+#define FIPS203_ML_KEM_512_CT_LEN 768
+
+/**
+ * The length of an ML-KEM-768 encapsulation key.
+ */
+#define FIPS203_ML_KEM_768_EK_LEN 1184
+
+/**
+ * The length of an ML-KEM-768 decapsulation key.
+ */
+#define FIPS203_ML_KEM_768_DK_LEN 2400
+
+/**
+ * The length of an ML-KEM-768 ciphertext.
+ */
+#define FIPS203_ML_KEM_768_CT_LEN 1088
+
+/**
+ * The length of an ML-KEM-1024 encapsulation key.
+ */
+#define FIPS203_ML_KEM_1024_EK_LEN 1568
+
+/**
+ * The length of an ML-KEM-1024 decapsulation key.
+ */
+#define FIPS203_ML_KEM_1024_DK_LEN 3168
+
+/**
+ * The length of an ML-KEM-1024 ciphertext.
+ */
+#define FIPS203_ML_KEM_1024_CT_LEN 1568
+
+/**
+ * The length of a shared secret key, for every parameter set.
+ */
+#define FIPS203_SSK_LEN 32
+
+/**
+ * The length of a key generation seed `d ∥ z`, for every parameter set.
+ */
+#define FIPS203_SEED_LEN 64
+
+/**
+ * Fills `len` bytes at `out` with randomness for the context `ctx`, returning 0 on success.
+ * Null selects the operating system generator.
+ */
+typedef int (*fips203_rng_callback)(void *ctx, uint8_t *out, size_t len);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns a static, NUL-terminated description of a `FIPS203_*` return code.
+ */
+const char *fips203_error_string(int code);
+
+/**
+ * Generates an ML-KEM-512 key pair into `ek` and `dk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_keygen(uint8_t *ek,
+                              size_t ek_len,
+                              uint8_t *dk,
+                              size_t dk_len,
+                              fips203_rng_callback rng,
+                              void *rng_ctx);
+
+/**
+ * Derives an ML-KEM-512 key pair into `ek` and `dk` from the 64-byte seed `d ∥ z`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_keygen_from_seed(const uint8_t *seed,
+                                        size_t seed_len,
+                                        uint8_t *ek,
+                                        size_t ek_len,
+                                        uint8_t *dk,
+                                        size_t dk_len);
+
+/**
+ * Encapsulates against the ML-KEM-512 `ek`, writing the ciphertext into `ct` and the shared
+ * secret into `ssk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_encaps(const uint8_t *ek,
+                              size_t ek_len,
+                              uint8_t *ct,
+                              size_t ct_len,
+                              uint8_t *ssk,
+                              size_t ssk_len,
+                              fips203_rng_callback rng,
+                              void *rng_ctx);
+
+/**
+ * Decapsulates the ML-KEM-512 `ct` with `dk`, writing the shared secret into `ssk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_decaps(const uint8_t *dk,
+                              size_t dk_len,
+                              const uint8_t *ct,
+                              size_t ct_len,
+                              uint8_t *ssk,
+                              size_t ssk_len);
+
+/**
+ * Validates an ML-KEM-512 encapsulation key.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_validate_encaps_key(const uint8_t *ek, size_t ek_len);
+
+/**
+ * Validates an ML-KEM-512 decapsulation key.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_validate_decaps_key(const uint8_t *dk, size_t dk_len);
+
+/**
+ * Validates an ML-KEM-512 ciphertext.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_512_validate_ciphertext(const uint8_t *ct, size_t ct_len);
+
+/**
+ * Generates an ML-KEM-768 key pair into `ek` and `dk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_keygen(uint8_t *ek,
+                              size_t ek_len,
+                              uint8_t *dk,
+                              size_t dk_len,
+                              fips203_rng_callback rng,
+                              void *rng_ctx);
+
+/**
+ * Derives an ML-KEM-768 key pair into `ek` and `dk` from the 64-byte seed `d ∥ z`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_keygen_from_seed(const uint8_t *seed,
+                                        size_t seed_len,
+                                        uint8_t *ek,
+                                        size_t ek_len,
+                                        uint8_t *dk,
+                                        size_t dk_len);
+
+/**
+ * Encapsulates against the ML-KEM-768 `ek`, writing the ciphertext into `ct` and the shared
+ * secret into `ssk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_encaps(const uint8_t *ek,
+                              size_t ek_len,
+                              uint8_t *ct,
+                              size_t ct_len,
+                              uint8_t *ssk,
+                              size_t ssk_len,
+                              fips203_rng_callback rng,
+                              void *rng_ctx);
+
+/**
+ * Decapsulates the ML-KEM-768 `ct` with `dk`, writing the shared secret into `ssk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_decaps(const uint8_t *dk,
+                              size_t dk_len,
+                              const uint8_t *ct,
+                              size_t ct_len,
+                              uint8_t *ssk,
+                              size_t ssk_len);
+
+/**
+ * Validates an ML-KEM-768 encapsulation key.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_validate_encaps_key(const uint8_t *ek, size_t ek_len);
+
+/**
+ * Validates an ML-KEM-768 decapsulation key.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_validate_decaps_key(const uint8_t *dk, size_t dk_len);
+
+/**
+ * Validates an ML-KEM-768 ciphertext.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_768_validate_ciphertext(const uint8_t *ct, size_t ct_len);
+
+/**
+ * Generates an ML-KEM-1024 key pair into `ek` and `dk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_keygen(uint8_t *ek,
+                               size_t ek_len,
+                               uint8_t *dk,
+                               size_t dk_len,
+                               fips203_rng_callback rng,
+                               void *rng_ctx);
+
+/**
+ * Derives an ML-KEM-1024 key pair into `ek` and `dk` from the 64-byte seed `d ∥ z`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_keygen_from_seed(const uint8_t *seed,
+                                         size_t seed_len,
+                                         uint8_t *ek,
+                                         size_t ek_len,
+                                         uint8_t *dk,
+                                         size_t dk_len);
+
+/**
+ * Encapsulates against the ML-KEM-1024 `ek`, writing the ciphertext into `ct` and the shared
+ * secret into `ssk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_encaps(const uint8_t *ek,
+                               size_t ek_len,
+                               uint8_t *ct,
+                               size_t ct_len,
+                               uint8_t *ssk,
+                               size_t ssk_len,
+                               fips203_rng_callback rng,
+                               void *rng_ctx);
+
+/**
+ * Decapsulates the ML-KEM-1024 `ct` with `dk`, writing the shared secret into `ssk`.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_decaps(const uint8_t *dk,
+                               size_t dk_len,
+                               const uint8_t *ct,
+                               size_t ct_len,
+                               uint8_t *ssk,
+                               size_t ssk_len);
+
+/**
+ * Validates an ML-KEM-1024 encapsulation key.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_validate_encaps_key(const uint8_t *ek, size_t ek_len);
+
+/**
+ * Validates an ML-KEM-1024 decapsulation key.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_validate_decaps_key(const uint8_t *dk, size_t dk_len);
+
+/**
+ * Validates an ML-KEM-1024 ciphertext.
+ * # Safety
+ * See the crate documentation.
+ */
+int fips203_ml_kem_1024_validate_ciphertext(const uint8_t *ct, size_t ct_len);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* FIPS203_H */
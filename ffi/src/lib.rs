@@ -0,0 +1,565 @@
+#![deny(clippy::pedantic)]
+#![deny(warnings)]
+#![deny(missing_docs)]
+#![allow(non_camel_case_types)]
+
+//! C bindings for the `fips203` crate, built as `libfips203_ffi.a` and `libfips203_ffi.so`
+//! with the cbindgen-generated `include/fips203.h`.
+//!
+//! Keys, ciphertexts and shared secrets cross the boundary in their FIPS 203 byte encodings, so
+//! serializing is copying the output buffer and deserializing is passing it back in, which runs
+//! the same validation as `SerDes::try_from_bytes()` (and is exposed alone as the `validate_*`
+//! functions). Every buffer is passed with its length, which must equal the corresponding
+//! `FIPS203_ML_KEM_*_LEN`, and every function returns `FIPS203_OK` or a negative
+//! `FIPS203_ERR_*` code. Outputs are only written on success.
+//!
+//! Randomness comes from the operating system unless a `fips203_rng_callback` is given, which is
+//! called with its context pointer to fill each buffer the algorithms draw (keygen draws `z` and
+//! then `d`, encapsulation draws `m`, 32 bytes each) and returns 0 on success.
+//!
+//! # Safety
+//! Each pointer must be non-null (null is reported as `FIPS203_ERR_NULL_POINTER`) and valid for
+//! reads or writes of the length passed alongside it, and output buffers must not overlap inputs.
+
+use core::ffi::{c_char, c_int, c_void};
+use core::num::NonZeroU32;
+use core::slice;
+
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768, SharedSecretKey};
+use rand_core::{CryptoRng, OsRng, RngCore};
+
+/// Success.
+pub const FIPS203_OK: c_int = 0;
+/// A required pointer was null.
+pub const FIPS203_ERR_NULL_POINTER: c_int = -1;
+/// A buffer length does not match the parameter set.
+pub const FIPS203_ERR_LENGTH: c_int = -2;
+/// An input failed validation, e.g. an encapsulation key coefficient not below q.
+pub const FIPS203_ERR_INVALID: c_int = -3;
+/// The random number generator (or callback) failed.
+pub const FIPS203_ERR_RNG: c_int = -4;
+/// Any other internal error.
+pub const FIPS203_ERR_INTERNAL: c_int = -5;
+
+/// The length of an ML-KEM-512 encapsulation key.
+pub const FIPS203_ML_KEM_512_EK_LEN: usize = 800;
+/// The length of an ML-KEM-512 decapsulation key.
+pub const FIPS203_ML_KEM_512_DK_LEN: usize = 1632;
+/// The length of an ML-KEM-512 ciphertext.
+pub const FIPS203_ML_KEM_512_CT_LEN: usize = 768;
+/// The length of an ML-KEM-768 encapsulation key.
+pub const FIPS203_ML_KEM_768_EK_LEN: usize = 1184;
+/// The length of an ML-KEM-768 decapsulation key.
+pub const FIPS203_ML_KEM_768_DK_LEN: usize = 2400;
+/// The length of an ML-KEM-768 ciphertext.
+pub const FIPS203_ML_KEM_768_CT_LEN: usize = 1088;
+/// The length of an ML-KEM-1024 encapsulation key.
+pub const FIPS203_ML_KEM_1024_EK_LEN: usize = 1568;
+/// The length of an ML-KEM-1024 decapsulation key.
+pub const FIPS203_ML_KEM_1024_DK_LEN: usize = 3168;
+/// The length of an ML-KEM-1024 ciphertext.
+pub const FIPS203_ML_KEM_1024_CT_LEN: usize = 1568;
+/// The length of a shared secret key, for every parameter set.
+pub const FIPS203_SSK_LEN: usize = 32;
+/// The length of a key generation seed `d ∥ z`, for every parameter set.
+pub const FIPS203_SEED_LEN: usize = 64;
+
+// cbindgen needs the lengths as literals; keep them in step with the crate
+const _: () = assert!(
+    FIPS203_ML_KEM_512_EK_LEN == ml_kem_512::EncapsKey::LEN
+        && FIPS203_ML_KEM_512_DK_LEN == ml_kem_512::DecapsKey::LEN
+        && FIPS203_ML_KEM_512_CT_LEN == ml_kem_512::CipherText::LEN
+        && FIPS203_ML_KEM_768_EK_LEN == ml_kem_768::EncapsKey::LEN
+        && FIPS203_ML_KEM_768_DK_LEN == ml_kem_768::DecapsKey::LEN
+        && FIPS203_ML_KEM_768_CT_LEN == ml_kem_768::CipherText::LEN
+        && FIPS203_ML_KEM_1024_EK_LEN == ml_kem_1024::EncapsKey::LEN
+        && FIPS203_ML_KEM_1024_DK_LEN == ml_kem_1024::DecapsKey::LEN
+        && FIPS203_ML_KEM_1024_CT_LEN == ml_kem_1024::CipherText::LEN
+);
+
+/// Fills `len` bytes at `out` with randomness for the context `ctx`, returning 0 on success.
+/// Null selects the operating system generator.
+pub type fips203_rng_callback =
+    Option<unsafe extern "C" fn(ctx: *mut c_void, out: *mut u8, len: usize) -> c_int>;
+
+
+/// Returns a static, NUL-terminated description of a `FIPS203_*` return code.
+#[no_mangle]
+pub extern "C" fn fips203_error_string(code: c_int) -> *const c_char {
+    let message: &'static [u8] = match code {
+        FIPS203_OK => b"success\0",
+        FIPS203_ERR_NULL_POINTER => b"null pointer\0",
+        FIPS203_ERR_LENGTH => b"buffer length does not match the parameter set\0",
+        FIPS203_ERR_INVALID => b"input failed validation\0",
+        FIPS203_ERR_RNG => b"random number generator failed\0",
+        FIPS203_ERR_INTERNAL => b"internal error\0",
+        _ => b"unknown error code\0",
+    };
+    message.as_ptr().cast()
+}
+
+
+// ----- ML-KEM-512 -----
+
+/// Generates an ML-KEM-512 key pair into `ek` and `dk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_keygen(
+    ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize, rng: fips203_rng_callback,
+    rng_ctx: *mut c_void,
+) -> c_int {
+    keygen::<MlKem512>(ek, ek_len, dk, dk_len, rng, rng_ctx)
+}
+
+/// Derives an ML-KEM-512 key pair into `ek` and `dk` from the 64-byte seed `d ∥ z`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_keygen_from_seed(
+    seed: *const u8, seed_len: usize, ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize,
+) -> c_int {
+    keygen_from_seed::<MlKem512>(seed, seed_len, ek, ek_len, dk, dk_len)
+}
+
+/// Encapsulates against the ML-KEM-512 `ek`, writing the ciphertext into `ct` and the shared
+/// secret into `ssk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_encaps(
+    ek: *const u8, ek_len: usize, ct: *mut u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+    rng: fips203_rng_callback, rng_ctx: *mut c_void,
+) -> c_int {
+    encaps::<MlKem512>(ek, ek_len, ct, ct_len, ssk, ssk_len, rng, rng_ctx)
+}
+
+/// Decapsulates the ML-KEM-512 `ct` with `dk`, writing the shared secret into `ssk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_decaps(
+    dk: *const u8, dk_len: usize, ct: *const u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+) -> c_int {
+    decaps::<MlKem512>(dk, dk_len, ct, ct_len, ssk, ssk_len)
+}
+
+/// Validates an ML-KEM-512 encapsulation key.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_validate_encaps_key(
+    ek: *const u8, ek_len: usize,
+) -> c_int {
+    validate::<<MlKem512 as ParameterSet>::EncapsKey>(ek, ek_len, MlKem512::EK_LEN)
+}
+
+/// Validates an ML-KEM-512 decapsulation key.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_validate_decaps_key(
+    dk: *const u8, dk_len: usize,
+) -> c_int {
+    validate::<<MlKem512 as ParameterSet>::DecapsKey>(dk, dk_len, MlKem512::DK_LEN)
+}
+
+/// Validates an ML-KEM-512 ciphertext.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_512_validate_ciphertext(
+    ct: *const u8, ct_len: usize,
+) -> c_int {
+    validate::<<MlKem512 as ParameterSet>::CipherText>(ct, ct_len, MlKem512::CT_LEN)
+}
+
+
+// ----- ML-KEM-768 -----
+
+/// Generates an ML-KEM-768 key pair into `ek` and `dk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_keygen(
+    ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize, rng: fips203_rng_callback,
+    rng_ctx: *mut c_void,
+) -> c_int {
+    keygen::<MlKem768>(ek, ek_len, dk, dk_len, rng, rng_ctx)
+}
+
+/// Derives an ML-KEM-768 key pair into `ek` and `dk` from the 64-byte seed `d ∥ z`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_keygen_from_seed(
+    seed: *const u8, seed_len: usize, ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize,
+) -> c_int {
+    keygen_from_seed::<MlKem768>(seed, seed_len, ek, ek_len, dk, dk_len)
+}
+
+/// Encapsulates against the ML-KEM-768 `ek`, writing the ciphertext into `ct` and the shared
+/// secret into `ssk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_encaps(
+    ek: *const u8, ek_len: usize, ct: *mut u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+    rng: fips203_rng_callback, rng_ctx: *mut c_void,
+) -> c_int {
+    encaps::<MlKem768>(ek, ek_len, ct, ct_len, ssk, ssk_len, rng, rng_ctx)
+}
+
+/// Decapsulates the ML-KEM-768 `ct` with `dk`, writing the shared secret into `ssk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_decaps(
+    dk: *const u8, dk_len: usize, ct: *const u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+) -> c_int {
+    decaps::<MlKem768>(dk, dk_len, ct, ct_len, ssk, ssk_len)
+}
+
+/// Validates an ML-KEM-768 encapsulation key.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_validate_encaps_key(
+    ek: *const u8, ek_len: usize,
+) -> c_int {
+    validate::<<MlKem768 as ParameterSet>::EncapsKey>(ek, ek_len, MlKem768::EK_LEN)
+}
+
+/// Validates an ML-KEM-768 decapsulation key.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_validate_decaps_key(
+    dk: *const u8, dk_len: usize,
+) -> c_int {
+    validate::<<MlKem768 as ParameterSet>::DecapsKey>(dk, dk_len, MlKem768::DK_LEN)
+}
+
+/// Validates an ML-KEM-768 ciphertext.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_768_validate_ciphertext(
+    ct: *const u8, ct_len: usize,
+) -> c_int {
+    validate::<<MlKem768 as ParameterSet>::CipherText>(ct, ct_len, MlKem768::CT_LEN)
+}
+
+
+// ----- ML-KEM-1024 -----
+
+/// Generates an ML-KEM-1024 key pair into `ek` and `dk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_keygen(
+    ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize, rng: fips203_rng_callback,
+    rng_ctx: *mut c_void,
+) -> c_int {
+    keygen::<MlKem1024>(ek, ek_len, dk, dk_len, rng, rng_ctx)
+}
+
+/// Derives an ML-KEM-1024 key pair into `ek` and `dk` from the 64-byte seed `d ∥ z`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_keygen_from_seed(
+    seed: *const u8, seed_len: usize, ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize,
+) -> c_int {
+    keygen_from_seed::<MlKem1024>(seed, seed_len, ek, ek_len, dk, dk_len)
+}
+
+/// Encapsulates against the ML-KEM-1024 `ek`, writing the ciphertext into `ct` and the shared
+/// secret into `ssk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_encaps(
+    ek: *const u8, ek_len: usize, ct: *mut u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+    rng: fips203_rng_callback, rng_ctx: *mut c_void,
+) -> c_int {
+    encaps::<MlKem1024>(ek, ek_len, ct, ct_len, ssk, ssk_len, rng, rng_ctx)
+}
+
+/// Decapsulates the ML-KEM-1024 `ct` with `dk`, writing the shared secret into `ssk`.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_decaps(
+    dk: *const u8, dk_len: usize, ct: *const u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+) -> c_int {
+    decaps::<MlKem1024>(dk, dk_len, ct, ct_len, ssk, ssk_len)
+}
+
+/// Validates an ML-KEM-1024 encapsulation key.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_validate_encaps_key(
+    ek: *const u8, ek_len: usize,
+) -> c_int {
+    validate::<<MlKem1024 as ParameterSet>::EncapsKey>(ek, ek_len, MlKem1024::EK_LEN)
+}
+
+/// Validates an ML-KEM-1024 decapsulation key.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_validate_decaps_key(
+    dk: *const u8, dk_len: usize,
+) -> c_int {
+    validate::<<MlKem1024 as ParameterSet>::DecapsKey>(dk, dk_len, MlKem1024::DK_LEN)
+}
+
+/// Validates an ML-KEM-1024 ciphertext.
+/// # Safety
+/// See the crate documentation.
+#[no_mangle]
+pub unsafe extern "C" fn fips203_ml_kem_1024_validate_ciphertext(
+    ct: *const u8, ct_len: usize,
+) -> c_int {
+    validate::<<MlKem1024 as ParameterSet>::CipherText>(ct, ct_len, MlKem1024::CT_LEN)
+}
+
+
+// ----- Shared implementation -----
+
+// The types and lengths of one parameter set, so the exported functions above stay one-liners
+trait ParameterSet {
+    type KG: KeyGen<EncapsKey = Self::EncapsKey, DecapsKey = Self::DecapsKey>;
+    type EncapsKey: Encaps<CipherText = Self::CipherText, SharedSecretKey = SharedSecretKey>
+        + AsRef<[u8]>
+        + for<'a> TryFrom<&'a [u8], Error = &'static str>;
+    type DecapsKey: Decaps<CipherText = Self::CipherText, SharedSecretKey = SharedSecretKey>
+        + AsRef<[u8]>
+        + for<'a> TryFrom<&'a [u8], Error = &'static str>;
+    type CipherText: AsRef<[u8]> + for<'a> TryFrom<&'a [u8], Error = &'static str>;
+    const EK_LEN: usize;
+    const DK_LEN: usize;
+    const CT_LEN: usize;
+
+    // An inherent function on each `KG`, rather than part of `KeyGen`
+    fn keygen_from_seed(
+        seed: &[u8; 64],
+    ) -> Result<(Self::EncapsKey, Self::DecapsKey), &'static str>;
+}
+
+struct MlKem512;
+struct MlKem768;
+struct MlKem1024;
+
+impl ParameterSet for MlKem512 {
+    type CipherText = ml_kem_512::CipherText;
+    type DecapsKey = ml_kem_512::DecapsKey;
+    type EncapsKey = ml_kem_512::EncapsKey;
+    type KG = ml_kem_512::KG;
+
+    const CT_LEN: usize = FIPS203_ML_KEM_512_CT_LEN;
+    const DK_LEN: usize = FIPS203_ML_KEM_512_DK_LEN;
+    const EK_LEN: usize = FIPS203_ML_KEM_512_EK_LEN;
+
+    fn keygen_from_seed(
+        seed: &[u8; 64],
+    ) -> Result<(Self::EncapsKey, Self::DecapsKey), &'static str> {
+        ml_kem_512::KG::try_keygen_from_seed_vt(seed)
+    }
+}
+
+impl ParameterSet for MlKem768 {
+    type CipherText = ml_kem_768::CipherText;
+    type DecapsKey = ml_kem_768::DecapsKey;
+    type EncapsKey = ml_kem_768::EncapsKey;
+    type KG = ml_kem_768::KG;
+
+    const CT_LEN: usize = FIPS203_ML_KEM_768_CT_LEN;
+    const DK_LEN: usize = FIPS203_ML_KEM_768_DK_LEN;
+    const EK_LEN: usize = FIPS203_ML_KEM_768_EK_LEN;
+
+    fn keygen_from_seed(
+        seed: &[u8; 64],
+    ) -> Result<(Self::EncapsKey, Self::DecapsKey), &'static str> {
+        ml_kem_768::KG::try_keygen_from_seed_vt(seed)
+    }
+}
+
+impl ParameterSet for MlKem1024 {
+    type CipherText = ml_kem_1024::CipherText;
+    type DecapsKey = ml_kem_1024::DecapsKey;
+    type EncapsKey = ml_kem_1024::EncapsKey;
+    type KG = ml_kem_1024::KG;
+
+    const CT_LEN: usize = FIPS203_ML_KEM_1024_CT_LEN;
+    const DK_LEN: usize = FIPS203_ML_KEM_1024_DK_LEN;
+    const EK_LEN: usize = FIPS203_ML_KEM_1024_EK_LEN;
+
+    fn keygen_from_seed(
+        seed: &[u8; 64],
+    ) -> Result<(Self::EncapsKey, Self::DecapsKey), &'static str> {
+        ml_kem_1024::KG::try_keygen_from_seed_vt(seed)
+    }
+}
+
+
+// Either the operating system generator or the caller's callback, which records a failure as the
+// crate draws some randomness through the infallible `fill_bytes()`
+enum Rng {
+    Os(OsRng),
+    Callback {
+        callback: unsafe extern "C" fn(*mut c_void, *mut u8, usize) -> c_int,
+        ctx: *mut c_void,
+        failed: bool,
+    },
+}
+
+impl Rng {
+    fn new(callback: fips203_rng_callback, ctx: *mut c_void) -> Self {
+        match callback {
+            None => Rng::Os(OsRng),
+            Some(callback) => Rng::Callback { callback, ctx, failed: false },
+        }
+    }
+
+    fn failed(&self) -> bool { matches!(self, Rng::Callback { failed: true, .. }) }
+}
+
+impl RngCore for Rng {
+    fn next_u32(&mut self) -> u32 { rand_core::impls::next_u32_via_fill(self) }
+
+    fn next_u64(&mut self) -> u64 { rand_core::impls::next_u64_via_fill(self) }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if self.try_fill_bytes(dest).is_err() {
+            dest.fill(0); // The operation's result is discarded
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match self {
+            Rng::Os(rng) => rng.try_fill_bytes(dest),
+            Rng::Callback { callback, ctx, failed } => {
+                // SAFETY: the caller vouches for the callback and its context
+                if *failed || unsafe { callback(*ctx, dest.as_mut_ptr(), dest.len()) } != 0 {
+                    *failed = true;
+                    return Err(NonZeroU32::new(rand_core::Error::CUSTOM_START)
+                        .unwrap()
+                        .into());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl CryptoRng for Rng {}
+
+
+unsafe fn input<'a>(ptr: *const u8, len: usize, expected: usize) -> Result<&'a [u8], c_int> {
+    if ptr.is_null() {
+        return Err(FIPS203_ERR_NULL_POINTER);
+    }
+    if len != expected {
+        return Err(FIPS203_ERR_LENGTH);
+    }
+    Ok(slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn output<'a>(ptr: *mut u8, len: usize, expected: usize) -> Result<&'a mut [u8], c_int> {
+    if ptr.is_null() {
+        return Err(FIPS203_ERR_NULL_POINTER);
+    }
+    if len != expected {
+        return Err(FIPS203_ERR_LENGTH);
+    }
+    Ok(slice::from_raw_parts_mut(ptr, len))
+}
+
+fn status(result: Result<(), c_int>) -> c_int { result.err().unwrap_or(FIPS203_OK) }
+
+
+unsafe fn keygen<P: ParameterSet>(
+    ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize, rng: fips203_rng_callback,
+    rng_ctx: *mut c_void,
+) -> c_int {
+    status((|| {
+        let ek = output(ek, ek_len, P::EK_LEN)?;
+        let dk = output(dk, dk_len, P::DK_LEN)?;
+        let mut rng = Rng::new(rng, rng_ctx);
+        let result = P::KG::try_keygen_with_rng_vt(&mut rng);
+        if rng.failed() {
+            return Err(FIPS203_ERR_RNG);
+        }
+        let (ek_out, dk_out) = result.map_err(|_| FIPS203_ERR_INTERNAL)?;
+        ek.copy_from_slice(ek_out.as_ref());
+        dk.copy_from_slice(dk_out.as_ref());
+        Ok(())
+    })())
+}
+
+unsafe fn keygen_from_seed<P: ParameterSet>(
+    seed: *const u8, seed_len: usize, ek: *mut u8, ek_len: usize, dk: *mut u8, dk_len: usize,
+) -> c_int {
+    status((|| {
+        let seed = input(seed, seed_len, FIPS203_SEED_LEN)?;
+        let ek = output(ek, ek_len, P::EK_LEN)?;
+        let dk = output(dk, dk_len, P::DK_LEN)?;
+        let seed = seed.try_into().map_err(|_| FIPS203_ERR_LENGTH)?;
+        let (ek_out, dk_out) = P::keygen_from_seed(seed).map_err(|_| FIPS203_ERR_INTERNAL)?;
+        ek.copy_from_slice(ek_out.as_ref());
+        dk.copy_from_slice(dk_out.as_ref());
+        Ok(())
+    })())
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe fn encaps<P: ParameterSet>(
+    ek: *const u8, ek_len: usize, ct: *mut u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+    rng: fips203_rng_callback, rng_ctx: *mut c_void,
+) -> c_int {
+    status((|| {
+        let ek = input(ek, ek_len, P::EK_LEN)?;
+        let ct = output(ct, ct_len, P::CT_LEN)?;
+        let ssk = output(ssk, ssk_len, FIPS203_SSK_LEN)?;
+        let ek = P::EncapsKey::try_from(ek).map_err(|_| FIPS203_ERR_INVALID)?;
+        let mut rng = Rng::new(rng, rng_ctx);
+        let result = ek.try_encaps_with_rng_vt(&mut rng);
+        if rng.failed() {
+            return Err(FIPS203_ERR_RNG);
+        }
+        let (ssk_out, ct_out) = result.map_err(|_| FIPS203_ERR_INTERNAL)?;
+        ct.copy_from_slice(ct_out.as_ref());
+        ssk.copy_from_slice(&ssk_out.into_bytes());
+        Ok(())
+    })())
+}
+
+unsafe fn decaps<P: ParameterSet>(
+    dk: *const u8, dk_len: usize, ct: *const u8, ct_len: usize, ssk: *mut u8, ssk_len: usize,
+) -> c_int {
+    status((|| {
+        let dk = input(dk, dk_len, P::DK_LEN)?;
+        let ct = input(ct, ct_len, P::CT_LEN)?;
+        let ssk = output(ssk, ssk_len, FIPS203_SSK_LEN)?;
+        let dk = P::DecapsKey::try_from(dk).map_err(|_| FIPS203_ERR_INVALID)?;
+        let ct = P::CipherText::try_from(ct).map_err(|_| FIPS203_ERR_INVALID)?;
+        let ssk_out = dk.try_decaps_vt(&ct).map_err(|_| FIPS203_ERR_INTERNAL)?;
+        ssk.copy_from_slice(&ssk_out.into_bytes());
+        Ok(())
+    })())
+}
+
+unsafe fn validate<T: for<'a> TryFrom<&'a [u8], Error = &'static str>>(
+    bytes: *const u8, len: usize, expected: usize,
+) -> c_int {
+    status((|| {
+        let bytes = input(bytes, len, expected)?;
+        T::try_from(bytes)
+            .map(|_| ())
+            .map_err(|_| FIPS203_ERR_INVALID)
+    })())
+}
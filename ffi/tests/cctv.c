@@ -0,0 +1,210 @@
+/*
+ * Runs the CCTV intermediate vectors through the C API: keygen and encapsulation replay the
+ * vectors' randomness through the RNG callback, then decapsulation and the validation and
+ * error paths are checked. Build and run with `make test`.
+ */
+#define _POSIX_C_SOURCE 200809L
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "fips203.h"
+
+#define VECTORS "../tests/cctv_vectors/ML-KEM/intermediate/"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                                    \
+    do {                                                                               \
+        if (!(cond)) {                                                                 \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond);   \
+            failures++;                                                                \
+        }                                                                              \
+    } while (0)
+
+
+/* The fields used from one vector file, each the first line named so */
+struct vector {
+    uint8_t d[32], z[32], m[32], K[32];
+    uint8_t ek[1568], dk[3168], c[1568];
+    size_t ek_len, dk_len, c_len;
+};
+
+static int hex_decode(const char *hex, uint8_t *out, size_t cap, size_t *len) {
+    size_t n = strlen(hex);
+    if (n % 2 != 0 || n / 2 > cap) return -1;
+    for (size_t i = 0; i < n / 2; i++) {
+        unsigned int byte;
+        if (sscanf(hex + 2 * i, "%2x", &byte) != 1) return -1;
+        out[i] = (uint8_t)byte;
+    }
+    *len = n / 2;
+    return 0;
+}
+
+static int read_vector(const char *path, struct vector *v) {
+    FILE *f = fopen(path, "r");
+    if (f == NULL) {
+        perror(path);
+        return -1;
+    }
+    struct {
+        const char *name;
+        uint8_t *out;
+        size_t cap, *len;
+        int seen;
+    } fields[] = {
+        {"d", v->d, 32, NULL, 0},          {"z", v->z, 32, NULL, 0},
+        {"m", v->m, 32, NULL, 0},          {"K", v->K, 32, NULL, 0},
+        {"ek", v->ek, 1568, &v->ek_len, 0}, {"dk", v->dk, 3168, &v->dk_len, 0},
+        {"c", v->c, 1568, &v->c_len, 0},
+    };
+    size_t count = sizeof(fields) / sizeof(fields[0]);
+    char *line = NULL;
+    size_t line_cap = 0;
+    ssize_t n;
+    int rc = 0;
+    while ((n = getline(&line, &line_cap, f)) > 0) {
+        while (n > 0 && (line[n - 1] == '\n' || line[n - 1] == '\r')) line[--n] = '\0';
+        char *sep = strstr(line, " = ");
+        if (sep == NULL) continue;
+        *sep = '\0';
+        for (size_t i = 0; i < count; i++) {
+            if (fields[i].seen || strcmp(line, fields[i].name) != 0) continue;
+            size_t len = 0;
+            if (hex_decode(sep + 3, fields[i].out, fields[i].cap, &len) != 0 ||
+                (fields[i].len == NULL && len != fields[i].cap)) {
+                fprintf(stderr, "%s: bad field %s\n", path, fields[i].name);
+                rc = -1;
+            }
+            if (fields[i].len != NULL) *fields[i].len = len;
+            fields[i].seen = 1;
+        }
+    }
+    for (size_t i = 0; i < count; i++) {
+        if (!fields[i].seen) {
+            fprintf(stderr, "%s: missing field %s\n", path, fields[i].name);
+            rc = -1;
+        }
+    }
+    free(line);
+    fclose(f);
+    return rc;
+}
+
+
+/* Replays fixed bytes: keygen draws z then d, encapsulation draws m */
+struct replay {
+    const uint8_t *chunks[2];
+    size_t next, count;
+};
+
+static int replay_rng(void *ctx, uint8_t *out, size_t len) {
+    struct replay *r = ctx;
+    if (r->next >= r->count || len != 32) return -1;
+    memcpy(out, r->chunks[r->next++], len);
+    return 0;
+}
+
+static int failing_rng(void *ctx, uint8_t *out, size_t len) {
+    (void)ctx;
+    (void)out;
+    (void)len;
+    return 1;
+}
+
+
+struct parameter_set {
+    const char *name;
+    size_t ek_len, dk_len, ct_len;
+    int (*keygen)(uint8_t *, size_t, uint8_t *, size_t, fips203_rng_callback, void *);
+    int (*keygen_from_seed)(const uint8_t *, size_t, uint8_t *, size_t, uint8_t *, size_t);
+    int (*encaps)(const uint8_t *, size_t, uint8_t *, size_t, uint8_t *, size_t,
+                  fips203_rng_callback, void *);
+    int (*decaps)(const uint8_t *, size_t, const uint8_t *, size_t, uint8_t *, size_t);
+    int (*validate_ek)(const uint8_t *, size_t);
+    int (*validate_dk)(const uint8_t *, size_t);
+    int (*validate_ct)(const uint8_t *, size_t);
+};
+
+#define PARAMETER_SET(bits)                                                                  \
+    {"ML-KEM-" #bits, FIPS203_ML_KEM_##bits##_EK_LEN, FIPS203_ML_KEM_##bits##_DK_LEN,        \
+     FIPS203_ML_KEM_##bits##_CT_LEN, fips203_ml_kem_##bits##_keygen,                        \
+     fips203_ml_kem_##bits##_keygen_from_seed, fips203_ml_kem_##bits##_encaps,              \
+     fips203_ml_kem_##bits##_decaps, fips203_ml_kem_##bits##_validate_encaps_key,           \
+     fips203_ml_kem_##bits##_validate_decaps_key, fips203_ml_kem_##bits##_validate_ciphertext}
+
+static void run(const struct parameter_set *p) {
+    char path[256];
+    snprintf(path, sizeof(path), VECTORS "%s.txt", p->name);
+    static struct vector v;
+    if (read_vector(path, &v) != 0) {
+        failures++;
+        return;
+    }
+    CHECK(v.ek_len == p->ek_len && v.dk_len == p->dk_len && v.c_len == p->ct_len);
+
+    static uint8_t ek[FIPS203_ML_KEM_1024_EK_LEN], dk[FIPS203_ML_KEM_1024_DK_LEN];
+    static uint8_t ct[FIPS203_ML_KEM_1024_CT_LEN];
+    uint8_t ssk[FIPS203_SSK_LEN], seed[FIPS203_SEED_LEN];
+
+    /* Key generation, through the callback and from the seed d || z */
+    struct replay keygen_rng = {{v.z, v.d}, 0, 2};
+    CHECK(p->keygen(ek, p->ek_len, dk, p->dk_len, replay_rng, &keygen_rng) == FIPS203_OK);
+    CHECK(memcmp(ek, v.ek, p->ek_len) == 0);
+    CHECK(memcmp(dk, v.dk, p->dk_len) == 0);
+    memcpy(seed, v.d, 32);
+    memcpy(seed + 32, v.z, 32);
+    memset(ek, 0, p->ek_len);
+    CHECK(p->keygen_from_seed(seed, sizeof(seed), ek, p->ek_len, dk, p->dk_len) == FIPS203_OK);
+    CHECK(memcmp(ek, v.ek, p->ek_len) == 0);
+
+    /* Encapsulation */
+    struct replay encaps_rng = {{v.m, NULL}, 0, 1};
+    CHECK(p->encaps(v.ek, p->ek_len, ct, p->ct_len, ssk, sizeof(ssk), replay_rng, &encaps_rng) ==
+          FIPS203_OK);
+    CHECK(memcmp(ct, v.c, p->ct_len) == 0);
+    CHECK(memcmp(ssk, v.K, 32) == 0);
+
+    /* Decapsulation */
+    memset(ssk, 0, sizeof(ssk));
+    CHECK(p->decaps(v.dk, p->dk_len, v.c, p->ct_len, ssk, sizeof(ssk)) == FIPS203_OK);
+    CHECK(memcmp(ssk, v.K, 32) == 0);
+
+    /* Validation, and the error codes */
+    CHECK(p->validate_ek(v.ek, p->ek_len) == FIPS203_OK);
+    CHECK(p->validate_dk(v.dk, p->dk_len) == FIPS203_OK);
+    CHECK(p->validate_ct(v.c, p->ct_len) == FIPS203_OK);
+    memcpy(ek, v.ek, p->ek_len);
+    ek[0] = 0xFF;
+    ek[1] |= 0x0F; /* A coefficient not below q */
+    CHECK(p->validate_ek(ek, p->ek_len) == FIPS203_ERR_INVALID);
+    CHECK(p->encaps(ek, p->ek_len, ct, p->ct_len, ssk, sizeof(ssk), NULL, NULL) ==
+          FIPS203_ERR_INVALID);
+    CHECK(p->validate_ct(v.c, p->ct_len - 1) == FIPS203_ERR_LENGTH);
+    CHECK(p->decaps(NULL, p->dk_len, v.c, p->ct_len, ssk, sizeof(ssk)) ==
+          FIPS203_ERR_NULL_POINTER);
+    CHECK(p->keygen(ek, p->ek_len, dk, p->dk_len, failing_rng, NULL) == FIPS203_ERR_RNG);
+
+    /* The operating system generator round trips */
+    uint8_t ssk2[FIPS203_SSK_LEN];
+    CHECK(p->keygen(ek, p->ek_len, dk, p->dk_len, NULL, NULL) == FIPS203_OK);
+    CHECK(p->encaps(ek, p->ek_len, ct, p->ct_len, ssk, sizeof(ssk), NULL, NULL) == FIPS203_OK);
+    CHECK(p->decaps(dk, p->dk_len, ct, p->ct_len, ssk2, sizeof(ssk2)) == FIPS203_OK);
+    CHECK(memcmp(ssk, ssk2, sizeof(ssk)) == 0);
+}
+
+int main(void) {
+    const struct parameter_set sets[] = {PARAMETER_SET(512), PARAMETER_SET(768),
+                                         PARAMETER_SET(1024)};
+    for (size_t i = 0; i < sizeof(sets) / sizeof(sets[0]); i++) run(&sets[i]);
+    CHECK(strcmp(fips203_error_string(FIPS203_ERR_RNG), "random number generator failed") == 0);
+
+    if (failures != 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    printf("all CCTV intermediate vectors passed\n");
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,206 @@
+#![deny(clippy::pedantic)]
+#![deny(warnings)]
+#![deny(missing_docs)]
+
+//! Python bindings for the `fips203` crate, built with maturin into the `fips203` module.
+//!
+//! Each parameter set is a class, e.g. `MlKem768`, with static `keygen()` and
+//! `keygen_from_seed(seed)` methods returning an `(ek, dk)` pair of `MlKem768EncapsKey` and
+//! `MlKem768DecapsKey` objects. `ek.encaps()` returns `(ssk, ct)` as `bytes` and `dk.decaps(ct)`
+//! returns `ssk`. Keys convert to and from their FIPS 203 byte encodings with `to_bytes()` (or
+//! `bytes(key)`) and `from_bytes()`, which validates as `SerDes::try_from_bytes()` does.
+//!
+//! Every error the crate reports, including inputs of the wrong length, is raised as
+//! `fips203.Fips203Error`, a subclass of `ValueError` carrying the crate's message.
+
+use fips203::compat::Coins;
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+create_exception!(
+    fips203,
+    Fips203Error,
+    PyValueError,
+    "Raised for any error reported by the fips203 crate, e.g. an encapsulation key failing the \
+     modulus check or an input of the wrong length."
+);
+
+fn error(message: &'static str) -> PyErr { Fips203Error::new_err(message) }
+
+
+// Defines the three classes for one parameter set; the Python names are passed in as Rust cannot
+// concatenate identifiers
+macro_rules! parameter_set {
+    ($module:ident, $name:literal, $class:ident, $ek:ident, $ek_name:literal, $dk:ident, $dk_name:literal) => {
+        #[doc = concat!("The ", $name, " parameter set.")]
+        #[pyclass(frozen, module = "fips203")]
+        struct $class;
+
+        #[pymethods]
+        impl $class {
+            #[classattr]
+            const CT_LEN: usize = $module::CipherText::LEN;
+            #[classattr]
+            const DK_LEN: usize = $module::DecapsKey::LEN;
+            #[classattr]
+            const EK_LEN: usize = $module::EncapsKey::LEN;
+            #[classattr]
+            const NAME: &'static str = $name;
+            #[classattr]
+            const SEED_LEN: usize = 64;
+            #[classattr]
+            const SSK_LEN: usize = 32;
+
+            /// Generates a key pair with the operating system random number generator.
+            #[staticmethod]
+            fn keygen() -> PyResult<($ek, $dk)> {
+                let (ek, dk) = $module::KG::try_keygen_vt().map_err(error)?;
+                Ok(($ek(ek), $dk(dk)))
+            }
+
+            /// Derives a key pair from the 64-byte seed `d ∥ z`.
+            #[staticmethod]
+            fn keygen_from_seed(seed: &[u8]) -> PyResult<($ek, $dk)> {
+                let seed = seed
+                    .try_into()
+                    .map_err(|_| error("seed must be 64 bytes"))?;
+                let (ek, dk) = $module::KG::try_keygen_from_seed_vt(seed).map_err(error)?;
+                Ok(($ek(ek), $dk(dk)))
+            }
+        }
+
+
+        #[doc = concat!("An ", $name, " encapsulation key.")]
+        #[pyclass(frozen, module = "fips203", name = $ek_name)]
+        struct $ek($module::EncapsKey);
+
+        #[pymethods]
+        impl $ek {
+            /// Deserializes and validates an encapsulation key.
+            #[staticmethod]
+            fn from_bytes(ek: &[u8]) -> PyResult<Self> {
+                $module::EncapsKey::try_from(ek).map(Self).map_err(error)
+            }
+
+            /// Serializes the encapsulation key.
+            fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+                PyBytes::new_bound(py, self.0.as_ref())
+            }
+
+            fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> { self.to_bytes(py) }
+
+            fn __eq__(&self, other: &Self) -> bool { self.0 == other.0 }
+
+            fn __hash__(&self) -> u64 {
+                let fingerprint = self.0.fingerprint();
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(&fingerprint.as_bytes()[..8]);
+                u64::from_le_bytes(bytes)
+            }
+
+            fn __repr__(&self) -> String {
+                format!("<{} {}>", $ek_name, self.0.fingerprint().short_id())
+            }
+
+            /// Encapsulates against the key, returning `(ssk, ct)`. Draws the 32 random bytes `m`
+            /// from the operating system unless given, as when replaying test vectors.
+            #[pyo3(signature = (m = None))]
+            fn encaps<'py>(
+                &self, py: Python<'py>, m: Option<&[u8]>,
+            ) -> PyResult<(Bound<'py, PyBytes>, Bound<'py, PyBytes>)> {
+                let (ssk, ct) = match m {
+                    None => self.0.try_encaps_vt(),
+                    Some(m) => {
+                        let m = m.try_into().map_err(|_| error("m must be 32 bytes"))?;
+                        self.0.try_encaps_with_rng_vt(&mut Coins::new(m))
+                    }
+                }
+                .map_err(error)?;
+                Ok((PyBytes::new_bound(py, &ssk.into_bytes()), PyBytes::new_bound(py, ct.as_ref())))
+            }
+        }
+
+
+        #[doc = concat!("An ", $name, " decapsulation key.")]
+        #[pyclass(frozen, module = "fips203", name = $dk_name)]
+        struct $dk($module::DecapsKey);
+
+        #[pymethods]
+        impl $dk {
+            /// Deserializes a decapsulation key.
+            #[staticmethod]
+            fn from_bytes(dk: &[u8]) -> PyResult<Self> {
+                $module::DecapsKey::try_from(dk).map(Self).map_err(error)
+            }
+
+            /// Serializes the decapsulation key.
+            fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+                PyBytes::new_bound(py, self.0.as_ref())
+            }
+
+            fn __bytes__<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> { self.to_bytes(py) }
+
+            // Identifies the key by its encapsulation key, without showing any secret bytes
+            fn __repr__(&self) -> String {
+                format!("<{} for {}>", $dk_name, self.0.encaps_key_fingerprint().short_id())
+            }
+
+            /// Decapsulates the ciphertext `ct`, returning `ssk`.
+            fn decaps<'py>(&self, py: Python<'py>, ct: &[u8]) -> PyResult<Bound<'py, PyBytes>> {
+                let ct = $module::CipherText::try_from(ct).map_err(error)?;
+                let ssk = self.0.try_decaps_vt(&ct).map_err(error)?;
+                Ok(PyBytes::new_bound(py, &ssk.into_bytes()))
+            }
+        }
+    };
+}
+
+parameter_set!(
+    ml_kem_512,
+    "ML-KEM-512",
+    MlKem512,
+    EncapsKey512,
+    "MlKem512EncapsKey",
+    DecapsKey512,
+    "MlKem512DecapsKey"
+);
+parameter_set!(
+    ml_kem_768,
+    "ML-KEM-768",
+    MlKem768,
+    EncapsKey768,
+    "MlKem768EncapsKey",
+    DecapsKey768,
+    "MlKem768DecapsKey"
+);
+parameter_set!(
+    ml_kem_1024,
+    "ML-KEM-1024",
+    MlKem1024,
+    EncapsKey1024,
+    "MlKem1024EncapsKey",
+    DecapsKey1024,
+    "MlKem1024DecapsKey"
+);
+
+
+/// The `fips203` Python module.
+#[pymodule]
+#[pyo3(name = "fips203")]
+fn fips203_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("Fips203Error", m.py().get_type_bound::<Fips203Error>())?;
+    m.add_class::<MlKem512>()?;
+    m.add_class::<EncapsKey512>()?;
+    m.add_class::<DecapsKey512>()?;
+    m.add_class::<MlKem768>()?;
+    m.add_class::<EncapsKey768>()?;
+    m.add_class::<DecapsKey768>()?;
+    m.add_class::<MlKem1024>()?;
+    m.add_class::<EncapsKey1024>()?;
+    m.add_class::<DecapsKey1024>()?;
+    Ok(())
+}
@@ -0,0 +1,105 @@
+"""Replays the NIST vectors in tests/nist_vectors through the bindings, and checks the key
+conversions and the errors raised for invalid inputs."""
+
+import pathlib
+import re
+
+import pytest
+
+import fips203
+
+VECTORS = pathlib.Path(__file__).resolve().parents[2] / "tests" / "nist_vectors"
+
+PARAMETER_SETS = [
+    (fips203.MlKem512, "ML-KEM-512", (800, 1632, 768)),
+    (fips203.MlKem768, "ML-KEM-768", (1184, 2400, 1088)),
+    (fips203.MlKem1024, "ML-KEM-1024", (1568, 3168, 1568)),
+]
+IDS = [name for _, name, _ in PARAMETER_SETS]
+
+
+def field(operation, name, label):
+    """Returns the first `label: hex` value in the vector file for `operation` and `name`."""
+    text = (VECTORS / f"{operation} -- {name}.txt").read_text()
+    return bytes.fromhex(re.search(rf"^{label}: ([0-9a-fA-F]+)$", text, re.MULTILINE).group(1))
+
+
+def key_classes(kem):
+    return getattr(fips203, f"{kem.__name__}EncapsKey"), getattr(fips203, f"{kem.__name__}DecapsKey")
+
+
+@pytest.mark.parametrize("kem, name, lengths", PARAMETER_SETS, ids=IDS)
+def test_keygen_vectors(kem, name, lengths):
+    assert kem.NAME == name
+    assert (kem.EK_LEN, kem.DK_LEN, kem.CT_LEN, kem.SSK_LEN, kem.SEED_LEN) == (*lengths, 32, 64)
+
+    seed = field("Key Generation", name, "d") + field("Key Generation", name, "z")
+    ek, dk = kem.keygen_from_seed(seed)
+    assert ek.to_bytes() == field("Key Generation", name, "ek")
+    assert bytes(dk) == field("Key Generation", name, "dk")
+
+
+@pytest.mark.parametrize("kem, name, lengths", PARAMETER_SETS, ids=IDS)
+def test_encaps_vectors(kem, name, lengths):
+    ek_class, _ = key_classes(kem)
+    ek = ek_class.from_bytes(field("Encapsulation", name, "ek"))
+    ssk, ct = ek.encaps(m=field("Encapsulation", name, "m"))
+    assert ssk == field("Encapsulation", name, "K")
+    assert ct == field("Encapsulation", name, "c")
+
+
+@pytest.mark.parametrize("kem, name, lengths", PARAMETER_SETS, ids=IDS)
+def test_decaps_vectors(kem, name, lengths):
+    _, dk_class = key_classes(kem)
+    dk = dk_class.from_bytes(field("Decapsulation", name, "dk"))
+    assert dk.decaps(field("Decapsulation", name, "c")) == field("Decapsulation", name, "KPrime")
+
+
+@pytest.mark.parametrize("kem, name, lengths", PARAMETER_SETS, ids=IDS)
+def test_round_trip(kem, name, lengths):
+    ek_class, dk_class = key_classes(kem)
+    ek, dk = kem.keygen()
+    ssk, ct = ek.encaps()
+    assert len(ssk) == kem.SSK_LEN and len(ct) == kem.CT_LEN
+    assert dk.decaps(ct) == ssk
+
+    # Keys survive conversion to and from bytes
+    ek2, dk2 = ek_class.from_bytes(bytes(ek)), dk_class.from_bytes(dk.to_bytes())
+    assert ek2 == ek and hash(ek2) == hash(ek)
+    ssk2, ct2 = ek2.encaps()
+    assert dk2.decaps(ct2) == ssk2
+
+    # The representations identify keys without showing decapsulation key bytes
+    assert repr(ek).startswith(f"<{kem.__name__}EncapsKey ")
+    assert dk.to_bytes().hex()[:16] not in repr(dk)
+
+
+@pytest.mark.parametrize("kem, name, lengths", PARAMETER_SETS, ids=IDS)
+def test_errors(kem, name, lengths):
+    ek_class, dk_class = key_classes(kem)
+    ek, dk = kem.keygen()
+
+    # An encapsulation key with a coefficient not below q
+    bad = bytearray(ek.to_bytes())
+    bad[0] = 0xFF
+    bad[1] |= 0x0F
+    with pytest.raises(fips203.Fips203Error):
+        ek_class.from_bytes(bytes(bad))
+
+    # Inputs of the wrong length
+    with pytest.raises(fips203.Fips203Error, match="ek shorter than EK_LEN"):
+        ek_class.from_bytes(ek.to_bytes()[1:])
+    with pytest.raises(fips203.Fips203Error, match="dk longer than DK_LEN"):
+        dk_class.from_bytes(dk.to_bytes() + b"\0")
+    with pytest.raises(fips203.Fips203Error, match="ct shorter than CT_LEN"):
+        dk.decaps(bytes(kem.CT_LEN - 1))
+    with pytest.raises(fips203.Fips203Error, match="seed must be 64 bytes"):
+        kem.keygen_from_seed(bytes(32))
+    with pytest.raises(fips203.Fips203Error, match="m must be 32 bytes"):
+        ek.encaps(m=bytes(31))
+
+    # Fips203Error is a ValueError, and non-bytes arguments are a TypeError
+    with pytest.raises(ValueError):
+        dk.decaps(b"")
+    with pytest.raises(TypeError):
+        dk.decaps("not bytes")
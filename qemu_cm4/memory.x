@@ -0,0 +1,6 @@
+/* Arm MPS2 FPGA image AN386 (Cortex-M4), as modelled by `qemu-system-arm -machine mps2-an386` */
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 4M  /* ZBT SSRAM1, holds the image */
+  RAM   : ORIGIN = 0x20000000, LENGTH = 4M  /* ZBT SSRAM2 and SSRAM3 */
+}
@@ -0,0 +1,216 @@
+#![no_std]
+#![no_main]
+// Runs KeyGen/Encaps/Decaps for every parameter set against the NIST vectors on a Cortex-M4,
+// reporting cycle counts and peak stack for each operation.
+//
+// One-off
+//   rustup target add thumbv7em-none-eabihf
+//   sudo apt install qemu-system-arm
+//
+// cd qemu_cm4
+// cargo run --release                           # DSP assembly kernels (`armv7em-asm`)
+// cargo run --release --no-default-features     # portable scalar kernels, for comparison
+// cargo run --release --features small-stack
+//
+// The process exits with a failure status if any result differs from the vectors. Cycle counts
+// need real hardware: QEMU does not model the DWT cycle counter, so under QEMU each line reports
+// `cycles n/a` and only the vector results and peak stack are meaningful.
+
+// Embedded heap allocator (since no_std); `alloc` is linked in but nothing is allocated
+extern crate alloc;
+
+use core::fmt::Write;
+use core::mem::MaybeUninit;
+
+use cortex_m::peripheral::DWT;
+use cortex_m_rt::entry;
+use cortex_m_semihosting::{debug, hio};
+use embedded_alloc::Heap;
+use panic_semihosting as _;
+use rand_core::{CryptoRng, RngCore};
+
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+
+
+#[global_allocator]
+static HEAP: Heap = Heap::empty();
+
+
+// ----- CUSTOM RNG TO REPLAY VALUES -----
+
+// Hands out the given values in order, one per `fill_bytes()` call
+struct ReplayRng<'a> {
+    data: [&'a [u8]; 2],
+    next: usize,
+}
+
+impl RngCore for ReplayRng<'_> {
+    fn next_u32(&mut self) -> u32 { unimplemented!() }
+
+    fn next_u64(&mut self) -> u64 { unimplemented!() }
+
+    fn fill_bytes(&mut self, out: &mut [u8]) {
+        out.copy_from_slice(self.data[self.next]);
+        self.next += 1;
+    }
+
+    fn try_fill_bytes(&mut self, out: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(out);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ReplayRng<'_> {}
+
+
+// ----- EXTRACT I/O VALUES FROM OFFICIAL VECTORS -----
+
+// Decodes the hex value on the line starting with `key`, e.g. "ek: "
+fn field<const N: usize>(data: &str, key: &str) -> [u8; N] {
+    let hex = data
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .expect("vector field missing");
+    assert_eq!(hex.len(), 2 * N, "vector field length");
+    let nibble = |c: u8| match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("vector field not hex"),
+    };
+    let mut out = [0u8; N];
+    for (byte, pair) in out.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+        *byte = (nibble(pair[0]) << 4) | nibble(pair[1]);
+    }
+    out
+}
+
+
+// ----- CYCLES AND STACK -----
+
+const PAINT: u32 = 0xCCCC_CCCC;
+
+// Set up by cortex-m-rt's `link.x`: the stack grows down from `_stack_start` towards `__sheap`
+extern "C" {
+    static _stack_start: u32;
+    static __sheap: u32;
+}
+
+struct Measurement {
+    cycles: u32,
+    stack: usize,
+}
+
+// Runs `op` once, returning its result along with the DWT cycles (zero under QEMU) and peak stack
+// bytes (including this function's frame) it took
+#[inline(never)]
+fn measure<T>(op: impl FnOnce() -> T) -> (T, Measurement) {
+    let bottom = core::ptr::addr_of!(__sheap) as usize;
+    let top = core::ptr::addr_of!(_stack_start) as usize;
+    let sp = cortex_m::register::msp::read() as usize - 64;
+    for addr in (bottom..sp).step_by(4) {
+        unsafe { core::ptr::write_volatile(addr as *mut u32, PAINT) };
+    }
+
+    cortex_m::asm::isb();
+    let cycles = DWT::cycle_count();
+    cortex_m::asm::isb();
+    let result = op();
+    cortex_m::asm::isb();
+    let cycles = DWT::cycle_count().wrapping_sub(cycles);
+    cortex_m::asm::isb();
+
+    let low = (bottom..sp)
+        .step_by(4)
+        .find(|&addr| unsafe { core::ptr::read_volatile(addr as *const u32) } != PAINT)
+        .unwrap_or(sp);
+    (result, Measurement { cycles, stack: top - low })
+}
+
+fn report(out: &mut hio::HostStream, name: &str, op: &str, pass: bool, m: &Measurement) -> bool {
+    let status = if pass { "ok  " } else { "FAIL" };
+    if m.cycles == 0 {
+        // No DWT cycle counter, as under QEMU
+        writeln!(out, "{name:<12}{op:<8}{status}  cycles        n/a  stack {:>6} B", m.stack)
+    } else {
+        writeln!(
+            out,
+            "{name:<12}{op:<8}{status}  cycles {:>10}  stack {:>6} B",
+            m.cycles, m.stack
+        )
+    }
+    .expect("hio write fail");
+    pass
+}
+
+
+// ----- RUN KEYGEN, ENCAPS AND DECAPS FOR ONE PARAMETER SET
+
+macro_rules! run_vectors {
+    ($out:expr, $module:ident, $name:literal) => {{
+        let mut pass = true;
+
+        let data =
+            include_str!(concat!("../../tests/nist_vectors/Key Generation -- ", $name, ".txt"));
+        let (d, z): ([u8; 32], [u8; 32]) = (field(data, "d: "), field(data, "z: "));
+        let mut rng = ReplayRng { data: [&d, &z], next: 0 };
+        let ((ek, dk), m) = measure(|| $module::KG::try_keygen_with_rng_vt(&mut rng).unwrap());
+        let ok = ek.into_bytes() == field(data, "ek: ") && dk.into_bytes() == field(data, "dk: ");
+        pass &= report($out, $name, "KeyGen", ok, &m);
+
+        let data =
+            include_str!(concat!("../../tests/nist_vectors/Encapsulation -- ", $name, ".txt"));
+        let ek = $module::EncapsKey::try_from_bytes(field(data, "ek: ")).unwrap();
+        let msg: [u8; 32] = field(data, "m: ");
+        let mut rng = ReplayRng { data: [&msg, &[]], next: 0 };
+        let ((ssk, ct), m) = measure(|| ek.try_encaps_with_rng_vt(&mut rng).unwrap());
+        let ok = ssk.into_bytes() == field(data, "K: ") && ct.into_bytes() == field(data, "c: ");
+        pass &= report($out, $name, "Encaps", ok, &m);
+
+        let data =
+            include_str!(concat!("../../tests/nist_vectors/Decapsulation -- ", $name, ".txt"));
+        let dk = $module::DecapsKey::try_from_bytes(field(data, "dk: ")).unwrap();
+        let ct = $module::CipherText::try_from_bytes(field(data, "c: ")).unwrap();
+        let (ssk, m) = measure(|| dk.try_decaps_vt(&ct).unwrap());
+        let ok = ssk.into_bytes() == field(data, "KPrime: ");
+        pass &= report($out, $name, "Decaps", ok, &m);
+
+        pass
+    }};
+}
+
+
+#[entry]
+fn main() -> ! {
+    // Configure heap
+    const HEAP_SIZE: usize = 1024;
+    static mut HEAP_MEM: [MaybeUninit<u8>; HEAP_SIZE] = [MaybeUninit::uninit(); HEAP_SIZE];
+    unsafe { HEAP.init(core::ptr::addr_of_mut!(HEAP_MEM) as usize, HEAP_SIZE) }
+
+    let mut core_periphs = cortex_m::Peripherals::take().expect("core_periphs fail");
+    core_periphs.DCB.enable_trace();
+    DWT::unlock();
+    core_periphs.DWT.enable_cycle_counter();
+
+    let mut out = hio::hstdout().expect("hio fail");
+    let kernels = if cfg!(feature = "armv7em-asm") {
+        "armv7em-asm"
+    } else {
+        "scalar"
+    };
+    writeln!(out, "fips203 on Cortex-M4, {kernels} kernels").expect("hio write fail");
+
+    let mut pass = run_vectors!(&mut out, ml_kem_512, "ML-KEM-512");
+    pass &= run_vectors!(&mut out, ml_kem_768, "ML-KEM-768");
+    pass &= run_vectors!(&mut out, ml_kem_1024, "ML-KEM-1024");
+
+    debug::exit(if pass {
+        debug::EXIT_SUCCESS
+    } else {
+        debug::EXIT_FAILURE
+    });
+    loop {
+        cortex_m::asm::wfi();
+    }
+}
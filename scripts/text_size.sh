@@ -0,0 +1,58 @@
+#!/bin/sh
+# Reproduces the `small-code` figure in the README: builds a small binary calling keygen, encaps
+# and decaps for all three parameter sets (`opt-level = "z"`, fat LTO) with and without the
+# feature, then totals the `.text` symbols belonging to this crate. Requires `nm` with demangling.
+set -eu
+
+crate=$(cd "$(dirname "$0")/.." && pwd)
+work=$(mktemp -d)
+trap 'rm -rf "$work"' EXIT
+
+mkdir "$work/src"
+cat > "$work/Cargo.toml" <<EOF
+[package]
+name = "text-size"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+fips203 = { path = "$crate", default-features = false, features = ["default-rng", "ml-kem-512", "ml-kem-768", "ml-kem-1024"] }
+
+[features]
+small-code = ["fips203/small-code"]
+
+[profile.release]
+opt-level = "z"
+lto = true
+codegen-units = 1
+panic = "abort"
+
+[workspace]
+EOF
+cat > "$work/src/main.rs" <<'EOF'
+use fips203::traits::{Decaps, Encaps, KeyGen};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+
+macro_rules! round_trip {
+    ($module:ident) => {{
+        let (ek, dk) = $module::KG::try_keygen_vt().unwrap();
+        let (ssk, ct) = ek.try_encaps_vt().unwrap();
+        assert_eq!(ssk, dk.try_decaps_vt(&ct).unwrap());
+    }};
+}
+
+fn main() {
+    round_trip!(ml_kem_512);
+    round_trip!(ml_kem_768);
+    round_trip!(ml_kem_1024);
+}
+EOF
+
+for features in "" "small-code"; do
+    cargo build --quiet --release --manifest-path "$work/Cargo.toml" --features "$features" \
+        --target-dir "$work/target"
+    bytes=$(nm --demangle --size-sort --radix=d "$work/target/release/text-size" \
+        | awk '$2 ~ /^[tT]$/ && /fips203::/ { sum += $1 } END { print sum + 0 }')
+    printf '%-12s %6d B\n' "${features:-default}" "$bytes"
+done
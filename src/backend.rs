@@ -0,0 +1,68 @@
+// Selects the polynomial kernels (NTT, inverse NTT, base multiplication, reduction, sampling,
+// compression and byte encoding) for the build. The scalar implementations in `ntt.rs`,
+// `sampling.rs`, `helpers.rs` and `byte_fns.rs` follow the spec and are used by default; a
+// vectorized backend replaces some or all of them when selected at compile time. All backends
+// produce bit-identical results.
+
+// Nightly `portable-simd` feature: `core::simd` for every kernel, on any target
+#[cfg(feature = "portable-simd")]
+pub(crate) use crate::portable_simd::{
+    byte_decode, byte_encode, multiply_ntts_acc, ntt, ntt_inv, sample_ntt, sample_poly_cbd,
+};
+
+// AArch64 NEON, part of the baseline of every `aarch64` target with floating point
+#[cfg(all(
+    not(feature = "portable-simd"),
+    target_arch = "aarch64",
+    target_feature = "neon"
+))]
+pub(crate) use crate::neon::{
+    compress, multiply_ntts_acc, ntt, ntt_inv, reduce_acc, sample_poly_cbd,
+};
+
+// RISC-V with the vector extension (`-C target-feature=+v`)
+#[cfg(all(not(feature = "portable-simd"), target_arch = "riscv64", target_feature = "v"))]
+pub(crate) use crate::rvv::{multiply_ntts_acc, ntt, ntt_inv};
+
+// ARMv7E-M (Cortex-M4/M7) or ARMv7-A DSP instructions, opted into with the `armv7em-asm` feature
+#[cfg(all(feature = "armv7em-asm", not(feature = "portable-simd"), target_arch = "arm"))]
+pub(crate) use crate::cm4::{multiply_ntts_acc, ntt, ntt_inv, reduce_acc};
+
+// WebAssembly with `-C target-feature=+simd128`
+#[cfg(all(
+    not(feature = "portable-simd"),
+    target_arch = "wasm32",
+    target_feature = "simd128"
+))]
+pub(crate) use crate::simd128::{multiply_ntts_acc, ntt, ntt_inv, sample_poly_cbd};
+
+// Scalar
+#[cfg(not(feature = "portable-simd"))]
+pub(crate) use crate::byte_fns::{byte_decode, byte_encode};
+#[cfg(not(all(
+    not(feature = "portable-simd"),
+    target_arch = "aarch64",
+    target_feature = "neon"
+)))]
+pub(crate) use crate::helpers::compress;
+#[cfg(not(any(
+    all(not(feature = "portable-simd"), target_arch = "aarch64", target_feature = "neon"),
+    all(feature = "armv7em-asm", not(feature = "portable-simd"), target_arch = "arm")
+)))]
+pub(crate) use crate::ntt::reduce_acc;
+#[cfg(not(any(
+    feature = "portable-simd",
+    all(target_arch = "aarch64", target_feature = "neon"),
+    all(target_arch = "riscv64", target_feature = "v"),
+    all(target_arch = "wasm32", target_feature = "simd128"),
+    all(feature = "armv7em-asm", target_arch = "arm")
+)))]
+pub(crate) use crate::ntt::{multiply_ntts_acc, ntt, ntt_inv};
+#[cfg(not(feature = "portable-simd"))]
+pub(crate) use crate::sampling::sample_ntt;
+#[cfg(not(any(
+    feature = "portable-simd",
+    all(target_arch = "aarch64", target_feature = "neon"),
+    all(target_arch = "wasm32", target_feature = "simd128")
+)))]
+pub(crate) use crate::sampling::sample_poly_cbd;
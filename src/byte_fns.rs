@@ -61,7 +61,9 @@ use crate::types::Z256;
 ///
 /// Input: integer array `F ∈ Z^256_m`, where `m = 2^d if d < 12` and `m = q if d = 12` <br>
 /// Output: byte array B ∈ B^{32d}
-pub(crate) fn byte_encode(
+///
+/// Generic over d; retained as the reference for the specialized packers in `byte_encode()`.
+pub(crate) fn byte_encode_generic(
     d: u32, integers_f: &[Z256; 256], bytes_b: &mut [u8],
 ) -> Result<(), &'static str> {
     let mut temp = 0u64;
@@ -134,7 +136,9 @@ pub(crate) fn byte_encode(
 ///
 /// Input: byte array B ∈ B^{32d} <br>
 /// Output: integer array `F ∈ Z^256_m`, where `m = 2^d if d < 12` and `m = q if d = 12`
-pub(crate) fn byte_decode(
+///
+/// Generic over d; retained as the reference for the specialized unpackers in `byte_decode()`.
+pub(crate) fn byte_decode_generic(
     d: u32, bytes_b: &[u8], integers_f: &mut [Z256; 256],
 ) -> Result<(), &'static str> {
     let bitlen = d;
@@ -192,6 +196,208 @@ pub(crate) fn byte_decode(
 // } // 5: return F
 
 
+/// Algorithm 4 `ByteEncode<d>(F)` on page 19.
+/// Encodes an array of d-bit integers into a byte array, for 1 ≤ d ≤ 12.
+///
+/// Dispatches to an unrolled packer for each d that ML-KEM uses (1, 4, 5, 10, 11 and 12), after a
+/// single branch-free range check over all coefficients; any other d takes the generic path.
+#[cfg_attr(feature = "portable-simd", allow(dead_code))]
+pub(crate) fn byte_encode(
+    d: u32, integers_f: &[Z256; 256], bytes_b: &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!((1..=12).contains(&d), "Alg4: d out of range");
+    ensure!(bytes_b.len() == 32 * d as usize, "Alg4: bytes len not 32 * d");
+    if !matches!(d, 1 | 4 | 5 | 10 | 11 | 12) {
+        return byte_encode_generic(d, integers_f, bytes_b);
+    }
+    let m = if d < 12 { 1 << d } else { Q as u16 };
+    let in_range = integers_f
+        .iter()
+        .fold(true, |ok, coeff| ok & (coeff.get_u16() <= m));
+    ensure!(in_range, "Alg4: Coeff out of range");
+    let mask = (1 << d) - 1;
+    match d {
+        1 => encode_1(integers_f, mask, bytes_b),
+        4 => encode_4(integers_f, mask, bytes_b),
+        5 => encode_5(integers_f, mask, bytes_b),
+        10 => encode_10(integers_f, mask, bytes_b),
+        11 => encode_11(integers_f, mask, bytes_b),
+        _ => encode_12(integers_f, mask, bytes_b),
+    }
+    Ok(())
+}
+
+
+/// Algorithm 5 `ByteDecode<d>(B)` on page 19.
+/// Decodes a byte array into an array of d-bit integers, for 1 ≤ d ≤ 12.
+///
+/// Dispatches to an unrolled unpacker for each d that ML-KEM uses (1, 4, 5, 10, 11 and 12); any
+/// other d takes the generic path. Only d = 12 can produce out-of-range integers, and these are
+/// detected with a single branch-free pass after unpacking.
+#[cfg_attr(feature = "portable-simd", allow(dead_code))]
+pub(crate) fn byte_decode(
+    d: u32, bytes_b: &[u8], integers_f: &mut [Z256; 256],
+) -> Result<(), &'static str> {
+    ensure!((1..=12).contains(&d), "Alg5: d out of range");
+    ensure!(bytes_b.len() == 32 * d as usize, "Alg5: bytes len not 32 * d");
+    match d {
+        1 => decode_1(bytes_b, integers_f),
+        4 => decode_4(bytes_b, integers_f),
+        5 => decode_5(bytes_b, integers_f),
+        10 => decode_10(bytes_b, integers_f),
+        11 => decode_11(bytes_b, integers_f),
+        12 => {
+            decode_12(bytes_b, integers_f);
+            let in_range = integers_f
+                .iter()
+                .fold(true, |ok, int| ok & (int.get_u16() < Q as u16));
+            ensure!(in_range, "Alg5: integers out of range");
+        }
+        _ => return byte_decode_generic(d, bytes_b, integers_f),
+    }
+    Ok(())
+}
+
+
+// The packers below each take a group of coefficients that fills a whole number of bytes, and
+// write those bytes directly. Coefficients are masked to d bits first, matching the generic code.
+
+fn encode_1(integers_f: &[Z256; 256], mask: u16, bytes_b: &mut [u8]) {
+    for (f, b) in integers_f.chunks_exact(8).zip(bytes_b.iter_mut()) {
+        *b = f
+            .iter()
+            .rev()
+            .fold(0, |acc, coeff| (acc << 1) | (coeff.get_u16() & mask) as u8);
+    }
+}
+
+
+fn encode_4(integers_f: &[Z256; 256], mask: u16, bytes_b: &mut [u8]) {
+    for (f, b) in integers_f.chunks_exact(2).zip(bytes_b.iter_mut()) {
+        *b = ((f[0].get_u16() & mask) | ((f[1].get_u16() & mask) << 4)) as u8;
+    }
+}
+
+
+fn encode_5(integers_f: &[Z256; 256], mask: u16, bytes_b: &mut [u8]) {
+    for (f, b) in integers_f.chunks_exact(8).zip(bytes_b.chunks_exact_mut(5)) {
+        let t: [u16; 8] = core::array::from_fn(|i| f[i].get_u16() & mask);
+        b[0] = (t[0] | (t[1] << 5)) as u8;
+        b[1] = ((t[1] >> 3) | (t[2] << 2) | (t[3] << 7)) as u8;
+        b[2] = ((t[3] >> 1) | (t[4] << 4)) as u8;
+        b[3] = ((t[4] >> 4) | (t[5] << 1) | (t[6] << 6)) as u8;
+        b[4] = ((t[6] >> 2) | (t[7] << 3)) as u8;
+    }
+}
+
+
+fn encode_10(integers_f: &[Z256; 256], mask: u16, bytes_b: &mut [u8]) {
+    for (f, b) in integers_f.chunks_exact(4).zip(bytes_b.chunks_exact_mut(5)) {
+        let t: [u16; 4] = core::array::from_fn(|i| f[i].get_u16() & mask);
+        b[0] = t[0] as u8;
+        b[1] = ((t[0] >> 8) | (t[1] << 2)) as u8;
+        b[2] = ((t[1] >> 6) | (t[2] << 4)) as u8;
+        b[3] = ((t[2] >> 4) | (t[3] << 6)) as u8;
+        b[4] = (t[3] >> 2) as u8;
+    }
+}
+
+
+fn encode_11(integers_f: &[Z256; 256], mask: u16, bytes_b: &mut [u8]) {
+    for (f, b) in integers_f.chunks_exact(8).zip(bytes_b.chunks_exact_mut(11)) {
+        let t: [u16; 8] = core::array::from_fn(|i| f[i].get_u16() & mask);
+        b[0] = t[0] as u8;
+        b[1] = ((t[0] >> 8) | (t[1] << 3)) as u8;
+        b[2] = ((t[1] >> 5) | (t[2] << 6)) as u8;
+        b[3] = (t[2] >> 2) as u8;
+        b[4] = ((t[2] >> 10) | (t[3] << 1)) as u8;
+        b[5] = ((t[3] >> 7) | (t[4] << 4)) as u8;
+        b[6] = ((t[4] >> 4) | (t[5] << 7)) as u8;
+        b[7] = (t[5] >> 1) as u8;
+        b[8] = ((t[5] >> 9) | (t[6] << 2)) as u8;
+        b[9] = ((t[6] >> 6) | (t[7] << 5)) as u8;
+        b[10] = (t[7] >> 3) as u8;
+    }
+}
+
+
+fn encode_12(integers_f: &[Z256; 256], mask: u16, bytes_b: &mut [u8]) {
+    for (f, b) in integers_f.chunks_exact(2).zip(bytes_b.chunks_exact_mut(3)) {
+        let t: [u16; 2] = core::array::from_fn(|i| f[i].get_u16() & mask);
+        b[0] = t[0] as u8;
+        b[1] = ((t[0] >> 8) | (t[1] << 4)) as u8;
+        b[2] = (t[1] >> 4) as u8;
+    }
+}
+
+
+fn decode_1(bytes_b: &[u8], integers_f: &mut [Z256; 256]) {
+    for (b, f) in bytes_b.iter().zip(integers_f.chunks_exact_mut(8)) {
+        for (i, int) in f.iter_mut().enumerate() {
+            *int = Z256(u16::from((b >> i) & 1));
+        }
+    }
+}
+
+
+fn decode_4(bytes_b: &[u8], integers_f: &mut [Z256; 256]) {
+    for (b, f) in bytes_b.iter().zip(integers_f.chunks_exact_mut(2)) {
+        f[0] = Z256(u16::from(b & 0x0F));
+        f[1] = Z256(u16::from(b >> 4));
+    }
+}
+
+
+fn decode_5(bytes_b: &[u8], integers_f: &mut [Z256; 256]) {
+    for (b, f) in bytes_b.chunks_exact(5).zip(integers_f.chunks_exact_mut(8)) {
+        let b: [u16; 5] = core::array::from_fn(|i| u16::from(b[i]));
+        f[0] = Z256(b[0] & 0x1F);
+        f[1] = Z256(((b[0] >> 5) | (b[1] << 3)) & 0x1F);
+        f[2] = Z256((b[1] >> 2) & 0x1F);
+        f[3] = Z256(((b[1] >> 7) | (b[2] << 1)) & 0x1F);
+        f[4] = Z256(((b[2] >> 4) | (b[3] << 4)) & 0x1F);
+        f[5] = Z256((b[3] >> 1) & 0x1F);
+        f[6] = Z256(((b[3] >> 6) | (b[4] << 2)) & 0x1F);
+        f[7] = Z256(b[4] >> 3);
+    }
+}
+
+
+fn decode_10(bytes_b: &[u8], integers_f: &mut [Z256; 256]) {
+    for (b, f) in bytes_b.chunks_exact(5).zip(integers_f.chunks_exact_mut(4)) {
+        let b: [u16; 5] = core::array::from_fn(|i| u16::from(b[i]));
+        f[0] = Z256((b[0] | (b[1] << 8)) & 0x3FF);
+        f[1] = Z256(((b[1] >> 2) | (b[2] << 6)) & 0x3FF);
+        f[2] = Z256(((b[2] >> 4) | (b[3] << 4)) & 0x3FF);
+        f[3] = Z256((b[3] >> 6) | (b[4] << 2));
+    }
+}
+
+
+fn decode_11(bytes_b: &[u8], integers_f: &mut [Z256; 256]) {
+    for (b, f) in bytes_b.chunks_exact(11).zip(integers_f.chunks_exact_mut(8)) {
+        let b: [u16; 11] = core::array::from_fn(|i| u16::from(b[i]));
+        f[0] = Z256((b[0] | (b[1] << 8)) & 0x7FF);
+        f[1] = Z256(((b[1] >> 3) | (b[2] << 5)) & 0x7FF);
+        f[2] = Z256(((b[2] >> 6) | (b[3] << 2) | (b[4] << 10)) & 0x7FF);
+        f[3] = Z256(((b[4] >> 1) | (b[5] << 7)) & 0x7FF);
+        f[4] = Z256(((b[5] >> 4) | (b[6] << 4)) & 0x7FF);
+        f[5] = Z256(((b[6] >> 7) | (b[7] << 1) | (b[8] << 9)) & 0x7FF);
+        f[6] = Z256(((b[8] >> 2) | (b[9] << 6)) & 0x7FF);
+        f[7] = Z256((b[9] >> 5) | (b[10] << 3));
+    }
+}
+
+
+fn decode_12(bytes_b: &[u8], integers_f: &mut [Z256; 256]) {
+    for (b, f) in bytes_b.chunks_exact(3).zip(integers_f.chunks_exact_mut(2)) {
+        let b: [u16; 3] = core::array::from_fn(|i| u16::from(b[i]));
+        f[0] = Z256(b[0] | ((b[1] & 0x0F) << 8));
+        f[1] = Z256((b[1] >> 4) | (b[2] << 4));
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     extern crate alloc;
@@ -201,7 +407,7 @@ mod tests {
 
     use rand::{Rng, SeedableRng};
 
-    use crate::byte_fns::{byte_decode, byte_encode};
+    use crate::byte_fns::{byte_decode, byte_decode_generic, byte_encode, byte_encode_generic};
     use crate::types::Z256;
 
 // #[test]
@@ -253,4 +459,60 @@ mod tests {
             assert_eq!(bytes1, bytes2);
         }
     }
+
+
+    #[test]
+    fn test_wrong_lengths() {
+        let integers = [Z256(0); 256];
+        let mut decoded = [Z256(0); 256];
+        for d in [1, 4, 5, 10, 11, 12] {
+            for len in [32 * d as usize - 1, 32 * d as usize + 1] {
+                let mut bytes = vec![0u8; len];
+                assert!(byte_encode(d, &integers, &mut bytes).is_err());
+                assert!(byte_decode(d, &bytes, &mut decoded).is_err());
+            }
+        }
+        for d in [0, 13] {
+            let mut bytes = vec![0u8; 32 * d as usize];
+            assert!(byte_encode(d, &integers, &mut bytes).is_err());
+            assert!(byte_decode(d, &bytes, &mut decoded).is_err());
+        }
+    }
+
+    #[test]
+    fn test_specialized_match_generic() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(456);
+        for _i in 0..100 {
+            for d in [1, 4, 5, 10, 11, 12] {
+                let num_bytes = 32 * d as usize;
+                let m = if d < 12 { 1 << d } else { 3329 };
+
+                // Includes the occasional out-of-range coefficient
+                let mut integers: [Z256; 256] =
+                    core::array::from_fn(|_| Z256(rng.gen_range(0..m + 2)));
+                let (mut bytes1, mut bytes2) = (vec![0u8; num_bytes], vec![0u8; num_bytes]);
+                let res1 = byte_encode(d, &integers, &mut bytes1);
+                let res2 = byte_encode_generic(d, &integers, &mut bytes2);
+                assert_eq!(res1, res2);
+                if res1.is_ok() {
+                    assert_eq!(bytes1, bytes2);
+                }
+                integers = core::array::from_fn(|_| Z256(rng.gen_range(0..m)));
+                byte_encode(d, &integers, &mut bytes1).unwrap();
+                byte_encode_generic(d, &integers, &mut bytes2).unwrap();
+                assert_eq!(bytes1, bytes2);
+
+                // Random bytes also exercise the d = 12 range check
+                let bytes: Vec<u8> = (0..num_bytes).map(|_| rng.gen()).collect();
+                let (mut integers1, mut integers2) = ([Z256(0); 256], [Z256(0); 256]);
+                let res1 = byte_decode(d, &bytes, &mut integers1);
+                let res2 = byte_decode_generic(d, &bytes, &mut integers2);
+                assert_eq!(res1, res2);
+                assert!(integers1
+                    .iter()
+                    .zip(integers2.iter())
+                    .all(|(a, b)| a.get_u16() == b.get_u16()));
+            }
+        }
+    }
 }
@@ -0,0 +1,324 @@
+use core::arch::asm;
+
+use crate::helpers::count;
+use crate::ntt::ZETA_TABLE;
+use crate::types::Z256;
+use crate::Q;
+
+// ARMv7E-M (Cortex-M4/M7) assembly implementations of the NTT, inverse NTT, base multiplication
+// and accumulator reduction, selected by `backend.rs` when the `armv7em-asm` feature is enabled on
+// an `arm` target. Each function mirrors its scalar counterpart (same signature, same output).
+//
+// The kernels use the DSP extension to hold two 16-bit coefficients per register. Butterflies
+// pair a word of lower coefficients with a word of upper coefficients, which always share one zeta
+// since `len ≥ 2`. Products by a zeta come from `smulbb`/`smultb` and are reduced with a Barrett
+// step: `smmul` by `⌊2^32 / q⌋` gives the quotient (exact or one short), and `smlabt` subtracts it
+// times `q`. The two halves are then repacked and brought from `[0, 2q)` to `[0, q)` together with
+// `usub16` + `sel`, which is also how the packed additions and subtractions are reduced. The base
+// multiplication accumulates both halves of each degree-one product with `smlad`/`smladx`.
+//
+// The instructions used exist in both Thumb-2 and ARM state, so the kernels also run on ARMv7-A.
+
+/// Barrett constant `⌊2^32 / q⌋`, as in `Z256::reduce_u32()`.
+#[allow(clippy::cast_possible_truncation)]
+const BARRETT_M: u32 = ((1u64 << 32) / Q as u64) as u32;
+
+/// `q` in both halfwords, for the packed reductions.
+const QQ: u32 = Q | (Q << 16);
+
+/// The NTT⁻¹ scaling factor `128^{-1} mod q`.
+const F: u16 = 3303;
+
+
+/// A polynomial aligned for the word loads and stores of the kernels, as `[Z256; 256]` alone is
+/// only halfword aligned.
+#[repr(C, align(4))]
+struct Aligned([Z256; 256]);
+
+
+/// Packs a zeta (bottom halfword) with `−q` (top halfword) for the `smulbb`/`smlabt` sequence.
+const fn zeta_q(zeta: u16) -> u32 { zeta as u32 | (Q.wrapping_neg() << 16) }
+
+
+/// Algorithm 8 steps 8-10 on packed pairs: `(a, b) ← (a + ζ · b, a − ζ · b) mod q`.
+fn ct_butterflies(lower: &mut [Z256], upper: &mut [Z256], zeta: u16) {
+    assert!(lower.len() == upper.len() && lower.len() % 2 == 0 && !lower.is_empty());
+    assert!(lower.as_ptr() as usize % 4 == 0 && upper.as_ptr() as usize % 4 == 0);
+    // SAFETY: `lower.len() / 2` words are read and written through each pointer, which are word
+    // aligned as asserted (the callers slice an `Aligned` at even offsets)
+    unsafe {
+        asm!(
+            "2:",
+            "ldr {b}, [{bp}]",
+            "ldr {a}, [{ap}]",
+            // t ← ζ · b mod q, low and high halves
+            "smulbb {p0}, {b}, {zq}",
+            "smultb {p1}, {b}, {zq}",
+            "smmul {b}, {p0}, {m}",
+            "smlabt {p0}, {b}, {zq}, {p0}",
+            "smmul {b}, {p1}, {m}",
+            "smlabt {p1}, {b}, {zq}, {p1}",
+            "pkhbt {p0}, {p0}, {p1}, lsl #16",
+            "usub16 {b}, {p0}, {qq}",
+            "sel {p0}, {b}, {p0}",
+            // b ← a − t mod q
+            "usub16 {p1}, {qq}, {p0}",
+            "uadd16 {p1}, {a}, {p1}",
+            "usub16 {b}, {p1}, {qq}",
+            "sel {p1}, {b}, {p1}",
+            // a ← a + t mod q
+            "uadd16 {a}, {a}, {p0}",
+            "usub16 {b}, {a}, {qq}",
+            "sel {a}, {b}, {a}",
+            "str {a}, [{ap}], #4",
+            "str {p1}, [{bp}], #4",
+            "subs {n}, {n}, #1",
+            "bne 2b",
+            ap = inout(reg) lower.as_mut_ptr() => _,
+            bp = inout(reg) upper.as_mut_ptr() => _,
+            n = inout(reg) lower.len() / 2 => _,
+            zq = in(reg) zeta_q(zeta),
+            m = in(reg) BARRETT_M,
+            qq = in(reg) QQ,
+            a = out(reg) _,
+            b = out(reg) _,
+            p0 = out(reg) _,
+            p1 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+
+/// Algorithm 9 steps 8-10 on packed pairs: `(a, b) ← (a + b, ζ · (b − a)) mod q`.
+fn gs_butterflies(lower: &mut [Z256], upper: &mut [Z256], zeta: u16) {
+    assert!(lower.len() == upper.len() && lower.len() % 2 == 0 && !lower.is_empty());
+    assert!(lower.as_ptr() as usize % 4 == 0 && upper.as_ptr() as usize % 4 == 0);
+    // SAFETY: as for `ct_butterflies()`
+    unsafe {
+        asm!(
+            "2:",
+            "ldr {a}, [{ap}]",
+            "ldr {b}, [{bp}]",
+            // d ← b − a mod q
+            "usub16 {p0}, {qq}, {a}",
+            "uadd16 {p0}, {b}, {p0}",
+            "usub16 {p1}, {p0}, {qq}",
+            "sel {p0}, {p1}, {p0}",
+            // a ← a + b mod q
+            "uadd16 {a}, {a}, {b}",
+            "usub16 {p1}, {a}, {qq}",
+            "sel {a}, {p1}, {a}",
+            "str {a}, [{ap}], #4",
+            // b ← ζ · d mod q, low and high halves
+            "smulbb {p1}, {p0}, {zq}",
+            "smultb {b}, {p0}, {zq}",
+            "smmul {p0}, {p1}, {m}",
+            "smlabt {p1}, {p0}, {zq}, {p1}",
+            "smmul {p0}, {b}, {m}",
+            "smlabt {b}, {p0}, {zq}, {b}",
+            "pkhbt {p1}, {p1}, {b}, lsl #16",
+            "usub16 {p0}, {p1}, {qq}",
+            "sel {p1}, {p0}, {p1}",
+            "str {p1}, [{bp}], #4",
+            "subs {n}, {n}, #1",
+            "bne 2b",
+            ap = inout(reg) lower.as_mut_ptr() => _,
+            bp = inout(reg) upper.as_mut_ptr() => _,
+            n = inout(reg) lower.len() / 2 => _,
+            zq = in(reg) zeta_q(zeta),
+            m = in(reg) BARRETT_M,
+            qq = in(reg) QQ,
+            a = out(reg) _,
+            b = out(reg) _,
+            p0 = out(reg) _,
+            p1 = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+
+/// Algorithm 8 `NTT(f)` on page 22.
+#[must_use]
+pub(crate) fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt);
+    count!(reductions, 7 * 128);
+    let mut f_hat = Aligned(*array_f);
+    let mut k = 1;
+    for len in [128, 64, 32, 16, 8, 4, 2] {
+        for start in (0..256).step_by(2 * len) {
+            let (lower, upper) = f_hat.0[start..start + 2 * len].split_at_mut(len);
+            ct_butterflies(lower, upper, ZETA_TABLE[k << 1]);
+            k += 1;
+        }
+    }
+    f_hat.0
+}
+
+
+/// Algorithm 9 `NTTinv(f)` on page 23.
+#[must_use]
+pub(crate) fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt_inv);
+    count!(reductions, 7 * 128 + 256);
+    let mut f = Aligned(*f_hat);
+    let mut k = 127;
+    for len in [2, 4, 8, 16, 32, 64, 128] {
+        for start in (0..256).step_by(2 * len) {
+            let (lower, upper) = f.0[start..start + 2 * len].split_at_mut(len);
+            gs_butterflies(lower, upper, ZETA_TABLE[k << 1]);
+            k -= 1;
+        }
+    }
+
+    // f ← f · 3303 mod q
+    // SAFETY: 128 words of the word-aligned `f` are read and written
+    unsafe {
+        asm!(
+            "2:",
+            "ldr {b}, [{p}]",
+            "smulbb {p0}, {b}, {zq}",
+            "smultb {p1}, {b}, {zq}",
+            "smmul {b}, {p0}, {m}",
+            "smlabt {p0}, {b}, {zq}, {p0}",
+            "smmul {b}, {p1}, {m}",
+            "smlabt {p1}, {b}, {zq}, {p1}",
+            "pkhbt {p0}, {p0}, {p1}, lsl #16",
+            "usub16 {b}, {p0}, {qq}",
+            "sel {p0}, {b}, {p0}",
+            "str {p0}, [{p}], #4",
+            "subs {n}, {n}, #1",
+            "bne 2b",
+            p = inout(reg) f.0.as_mut_ptr() => _,
+            n = inout(reg) 128 => _,
+            zq = in(reg) zeta_q(F),
+            m = in(reg) BARRETT_M,
+            qq = in(reg) QQ,
+            b = out(reg) _,
+            p0 = out(reg) _,
+            p1 = out(reg) _,
+            options(nostack),
+        );
+    }
+    f.0
+}
+
+
+/// `multiply_ntts_acc()` with `smlad`/`smladx`: the words `(a0, a1)` of `f_hat`, `(b0, b1)` of
+/// `g_hat` and `(b0, b1 · γ)` with `g_cache` are assembled from halfword loads with `pkhbt`, as the
+/// callers' polynomials need not be word aligned.
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut [u32; 256], f_hat: &[Z256; 256], g_hat: &[Z256; 256], g_cache: &[Z256; 128],
+) {
+    // SAFETY: 256 halfwords of `f_hat` and `g_hat`, 128 halfwords of `g_cache` and 256 words of
+    // the word-aligned `acc` are accessed
+    unsafe {
+        asm!(
+            "2:",
+            "ldrh {a}, [{f}], #2",
+            "ldrh {t}, [{f}], #2",
+            "pkhbt {a}, {a}, {t}, lsl #16",
+            "ldrh {b}, [{g}], #2",
+            "ldrh {t}, [{g}], #2",
+            "pkhbt {b}, {b}, {t}, lsl #16",
+            "ldrh {t}, [{gc}], #2",
+            "pkhbt {t}, {b}, {t}, lsl #16",
+            // c0 ← a0 · b0 + a1 · b1 · γ   and   c1 ← a0 · b1 + a1 · b0   (reduction deferred)
+            "ldr {c}, [{acc}]",
+            "smlad {c}, {a}, {t}, {c}",
+            "str {c}, [{acc}], #4",
+            "ldr {c}, [{acc}]",
+            "smladx {c}, {a}, {b}, {c}",
+            "str {c}, [{acc}], #4",
+            "subs {n}, {n}, #1",
+            "bne 2b",
+            acc = inout(reg) acc.as_mut_ptr() => _,
+            f = inout(reg) f_hat.as_ptr() => _,
+            g = inout(reg) g_hat.as_ptr() => _,
+            gc = inout(reg) g_cache.as_ptr() => _,
+            n = inout(reg) 128 => _,
+            a = out(reg) _,
+            b = out(reg) _,
+            c = out(reg) _,
+            t = out(reg) _,
+            options(nostack),
+        );
+    }
+}
+
+
+/// `reduce_acc()` with a `umull`-based Barrett reduction, two coefficients per iteration.
+#[must_use]
+pub(crate) fn reduce_acc(acc: &[u32; 256]) -> [Z256; 256] {
+    count!(reductions, 256);
+    let mut h_hat = Aligned([Z256(0); 256]);
+    // SAFETY: 256 words of the word-aligned `acc` are read and 128 words of `h_hat` written
+    unsafe {
+        asm!(
+            "2:",
+            "ldr {w0}, [{acc}], #4",
+            "ldr {w1}, [{acc}], #4",
+            "umull {t}, {h}, {w0}, {m}",
+            "mls {w0}, {h}, {q}, {w0}",
+            "umull {t}, {h}, {w1}, {m}",
+            "mls {w1}, {h}, {q}, {w1}",
+            "pkhbt {w0}, {w0}, {w1}, lsl #16",
+            "usub16 {t}, {w0}, {qq}",
+            "sel {w0}, {t}, {w0}",
+            "str {w0}, [{out}], #4",
+            "subs {n}, {n}, #1",
+            "bne 2b",
+            acc = inout(reg) acc.as_ptr() => _,
+            out = inout(reg) h_hat.0.as_mut_ptr() => _,
+            n = inout(reg) 128 => _,
+            m = in(reg) BARRETT_M,
+            q = in(reg) Q,
+            qq = in(reg) QQ,
+            w0 = out(reg) _,
+            w1 = out(reg) _,
+            t = out(reg) _,
+            h = out(reg) _,
+            options(nostack),
+        );
+    }
+    h_hat.0
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use crate::ntt::mul_cache;
+    use crate::types::Z256;
+    use crate::{cm4, ntt};
+
+    fn random_poly(rng: &mut impl Rng) -> [Z256; 256] {
+        core::array::from_fn(|_| Z256(rng.gen_range(0..3329)))
+    }
+
+    fn same(a: &[Z256], b: &[Z256]) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.get_u16() == y.get_u16())
+    }
+
+    #[test]
+    fn test_cm4_matches_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(4);
+        for _i in 0..100 {
+            let (f, g) = (random_poly(&mut rng), random_poly(&mut rng));
+            assert!(same(&cm4::ntt(&f), &ntt::ntt(&f)));
+            assert!(same(&cm4::ntt_inv(&f), &ntt::ntt_inv(&f)));
+
+            let (mut acc_cm4, mut acc_scalar) = ([0u32; 256], [0u32; 256]);
+            for _j in 0..4 {
+                cm4::multiply_ntts_acc(&mut acc_cm4, &f, &g, &mul_cache(&g));
+                ntt::multiply_ntts_acc(&mut acc_scalar, &f, &g, &mul_cache(&g));
+            }
+            assert_eq!(acc_cm4, acc_scalar);
+            assert!(same(&cm4::reduce_acc(&acc_cm4), &ntt::reduce_acc(&acc_scalar)));
+        }
+    }
+}
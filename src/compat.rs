@@ -0,0 +1,312 @@
+//! Byte-level entry points in the shapes of the NIST/PQClean `crypto_kem_*` API and of liboqs'
+//! `OQS_KEM_ml_kem_*` functions (available with the `compat` feature), for dropping this crate in
+//! behind integrations written against either.
+//!
+//! Each parameter set module has the `CRYPTO_*` size constants and `crypto_kem_keypair()`,
+//! `crypto_kem_enc()` and `crypto_kem_dec()` (plus the `_derand` variants taking the randomness
+//! as "coins"), alongside the liboqs `OQS_KEM_alg_*` name, `*_length_*` constants and functions.
+//! The buffers are fixed-size arrays of `EK_LEN`/`DK_LEN`/`CT_LEN` and 32 bytes, and every function
+//! returns 0 (`OQS_SUCCESS`) or -1 (`OQS_ERROR`) rather than a `Result`. The argument order follows
+//! the C functions: outputs first.
+//!
+//! As this crate implements the initial public draft of FIPS 203, the outputs match `PQClean` and
+//! liboqs builds of that draft (e.g. liboqs 0.9 `ML-KEM-*-ipd`), not of the final standard.
+
+#![allow(non_snake_case, non_upper_case_globals)]
+
+use rand_core::{CryptoRng, RngCore};
+
+/// The status type returned by the liboqs-style functions (a C `enum` of `int` size).
+#[allow(non_camel_case_types)]
+pub type OQS_STATUS = i32;
+
+/// Returned on success, as is 0 by the `crypto_kem_*` functions.
+pub const OQS_SUCCESS: OQS_STATUS = 0;
+
+/// Returned on failure, e.g. for an encapsulation key that fails the modulus check, as is -1 by
+/// the `crypto_kem_*` functions.
+pub const OQS_ERROR: OQS_STATUS = -1;
+
+
+/// A random number generator yielding 32 fixed `coins` exactly once, as the message `m` of one
+/// encapsulation: the `_derand` functions use it, and it replays encapsulation test vectors through
+/// `try_encaps_with_rng_vt()`. Any other draw, including a second one, fails rather than repeating
+/// the coins or padding them with zeros.
+pub struct Coins<'a>(Option<&'a [u8; 32]>);
+
+impl<'a> Coins<'a> {
+    /// Wraps the 32 `coins`.
+    #[must_use]
+    pub fn new(coins: &'a [u8; 32]) -> Self { Self(Some(coins)) }
+}
+
+impl RngCore for Coins<'_> {
+    fn next_u32(&mut self) -> u32 { rand_core::impls::next_u32_via_fill(self) }
+
+    fn next_u64(&mut self) -> u64 { rand_core::impls::next_u64_via_fill(self) }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("coins already used");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        match self.0.take() {
+            Some(coins) if dest.len() == coins.len() => {
+                dest.copy_from_slice(coins);
+                Ok(())
+            }
+            _ => Err(core::num::NonZeroU32::new(rand_core::Error::CUSTOM_START)
+                .unwrap()
+                .into()),
+        }
+    }
+}
+
+impl CryptoRng for Coins<'_> {}
+
+
+// Implements both APIs for one parameter set; the liboqs names are passed in as Rust cannot
+// concatenate identifiers
+macro_rules! compat {
+    (
+        $module:ident, $name:literal, $alg:ident, $length_public_key:ident,
+        $length_secret_key:ident, $length_ciphertext:ident, $length_shared_secret:ident,
+        $length_keypair_seed:ident, $length_encaps_seed:ident, $keypair:ident,
+        $keypair_derand:ident, $encaps:ident, $encaps_derand:ident, $decaps:ident
+    ) => {
+        #[doc = concat!("The ", $name, " parameter set.")]
+        pub mod $module {
+            use super::{Coins, OQS_ERROR, OQS_STATUS, OQS_SUCCESS};
+            use crate::traits::{Decaps, Encaps, SerDes};
+            use crate::$module::{CipherText, DecapsKey, EncapsKey, KG};
+
+            /// The length of the public (encapsulation) key in bytes.
+            pub const CRYPTO_PUBLICKEYBYTES: usize = EncapsKey::LEN;
+            /// The length of the secret (decapsulation) key in bytes.
+            pub const CRYPTO_SECRETKEYBYTES: usize = DecapsKey::LEN;
+            /// The length of the ciphertext in bytes.
+            pub const CRYPTO_CIPHERTEXTBYTES: usize = CipherText::LEN;
+            /// The length of the shared secret in bytes.
+            pub const CRYPTO_BYTES: usize = 32;
+            /// The algorithm name.
+            pub const CRYPTO_ALGNAME: &str = $name;
+
+            /// Generates a key pair into `pk` and `sk` with the default random number generator.
+            #[cfg(feature = "default-rng")]
+            #[must_use]
+            pub fn crypto_kem_keypair(
+                pk: &mut [u8; CRYPTO_PUBLICKEYBYTES], sk: &mut [u8; CRYPTO_SECRETKEYBYTES],
+            ) -> i32 {
+                match <KG as crate::traits::KeyGen>::try_keygen_vt() {
+                    Ok((ek, dk)) => write_keypair(&ek, &dk, pk, sk),
+                    Err(_) => OQS_ERROR,
+                }
+            }
+
+            /// Derives a key pair into `pk` and `sk` from the 64-byte `coins` `d ∥ z`, as
+            /// `KG::try_keygen_from_seed_vt()`.
+            #[must_use]
+            pub fn crypto_kem_keypair_derand(
+                pk: &mut [u8; CRYPTO_PUBLICKEYBYTES], sk: &mut [u8; CRYPTO_SECRETKEYBYTES],
+                coins: &[u8; 64],
+            ) -> i32 {
+                match KG::try_keygen_from_seed_vt(coins) {
+                    Ok((ek, dk)) => write_keypair(&ek, &dk, pk, sk),
+                    Err(_) => OQS_ERROR,
+                }
+            }
+
+            /// Encapsulates against `pk` with the default random number generator, writing the
+            /// ciphertext into `ct` and the shared secret into `ss`. Fails for a `pk` that does
+            /// not pass the modulus check.
+            #[cfg(feature = "default-rng")]
+            #[must_use]
+            pub fn crypto_kem_enc(
+                ct: &mut [u8; CRYPTO_CIPHERTEXTBYTES], ss: &mut [u8; CRYPTO_BYTES],
+                pk: &[u8; CRYPTO_PUBLICKEYBYTES],
+            ) -> i32 {
+                let Ok(ek) = EncapsKey::try_from(&pk[..]) else {
+                    return OQS_ERROR;
+                };
+                match ek.try_encaps_vt() {
+                    Ok((ssk, ciphertext)) => write_encaps(ssk, &ciphertext, ct, ss),
+                    Err(_) => OQS_ERROR,
+                }
+            }
+
+            /// Encapsulates against `pk` with the 32-byte `coins` as the message `m`, writing the
+            /// ciphertext into `ct` and the shared secret into `ss`.
+            #[must_use]
+            pub fn crypto_kem_enc_derand(
+                ct: &mut [u8; CRYPTO_CIPHERTEXTBYTES], ss: &mut [u8; CRYPTO_BYTES],
+                pk: &[u8; CRYPTO_PUBLICKEYBYTES], coins: &[u8; 32],
+            ) -> i32 {
+                let Ok(ek) = EncapsKey::try_from(&pk[..]) else {
+                    return OQS_ERROR;
+                };
+                match ek.try_encaps_with_rng_vt(&mut Coins::new(coins)) {
+                    Ok((ssk, ciphertext)) => write_encaps(ssk, &ciphertext, ct, ss),
+                    Err(_) => OQS_ERROR,
+                }
+            }
+
+            /// Decapsulates `ct` with `sk`, writing the shared secret into `ss`. A ciphertext
+            /// that fails the re-encryption check yields the implicit rejection secret and still
+            /// returns 0.
+            #[must_use]
+            pub fn crypto_kem_dec(
+                ss: &mut [u8; CRYPTO_BYTES], ct: &[u8; CRYPTO_CIPHERTEXTBYTES],
+                sk: &[u8; CRYPTO_SECRETKEYBYTES],
+            ) -> i32 {
+                let (Ok(dk), Ok(ciphertext)) =
+                    (DecapsKey::try_from(&sk[..]), CipherText::try_from(&ct[..]))
+                else {
+                    return OQS_ERROR;
+                };
+                match dk.try_decaps_vt(&ciphertext) {
+                    Ok(ssk) => {
+                        ss.copy_from_slice(&ssk.into_bytes());
+                        OQS_SUCCESS
+                    }
+                    Err(_) => OQS_ERROR,
+                }
+            }
+
+            /// The liboqs algorithm identifier.
+            pub const $alg: &str = $name;
+            /// The liboqs public key length.
+            pub const $length_public_key: usize = CRYPTO_PUBLICKEYBYTES;
+            /// The liboqs secret key length.
+            pub const $length_secret_key: usize = CRYPTO_SECRETKEYBYTES;
+            /// The liboqs ciphertext length.
+            pub const $length_ciphertext: usize = CRYPTO_CIPHERTEXTBYTES;
+            /// The liboqs shared secret length.
+            pub const $length_shared_secret: usize = CRYPTO_BYTES;
+            /// The liboqs key generation seed length.
+            pub const $length_keypair_seed: usize = 64;
+            /// The liboqs encapsulation seed length.
+            pub const $length_encaps_seed: usize = 32;
+
+            /// As `crypto_kem_keypair()`, with liboqs naming.
+            #[cfg(feature = "default-rng")]
+            #[must_use]
+            pub fn $keypair(
+                public_key: &mut [u8; CRYPTO_PUBLICKEYBYTES],
+                secret_key: &mut [u8; CRYPTO_SECRETKEYBYTES],
+            ) -> OQS_STATUS {
+                crypto_kem_keypair(public_key, secret_key)
+            }
+
+            /// As `crypto_kem_keypair_derand()`, with liboqs naming.
+            #[must_use]
+            pub fn $keypair_derand(
+                public_key: &mut [u8; CRYPTO_PUBLICKEYBYTES],
+                secret_key: &mut [u8; CRYPTO_SECRETKEYBYTES], seed: &[u8; 64],
+            ) -> OQS_STATUS {
+                crypto_kem_keypair_derand(public_key, secret_key, seed)
+            }
+
+            /// As `crypto_kem_enc()`, with liboqs naming.
+            #[cfg(feature = "default-rng")]
+            #[must_use]
+            pub fn $encaps(
+                ciphertext: &mut [u8; CRYPTO_CIPHERTEXTBYTES],
+                shared_secret: &mut [u8; CRYPTO_BYTES], public_key: &[u8; CRYPTO_PUBLICKEYBYTES],
+            ) -> OQS_STATUS {
+                crypto_kem_enc(ciphertext, shared_secret, public_key)
+            }
+
+            /// As `crypto_kem_enc_derand()`, with liboqs naming.
+            #[must_use]
+            pub fn $encaps_derand(
+                ciphertext: &mut [u8; CRYPTO_CIPHERTEXTBYTES],
+                shared_secret: &mut [u8; CRYPTO_BYTES], public_key: &[u8; CRYPTO_PUBLICKEYBYTES],
+                seed: &[u8; 32],
+            ) -> OQS_STATUS {
+                crypto_kem_enc_derand(ciphertext, shared_secret, public_key, seed)
+            }
+
+            /// As `crypto_kem_dec()`, with liboqs naming.
+            #[must_use]
+            pub fn $decaps(
+                shared_secret: &mut [u8; CRYPTO_BYTES], ciphertext: &[u8; CRYPTO_CIPHERTEXTBYTES],
+                secret_key: &[u8; CRYPTO_SECRETKEYBYTES],
+            ) -> OQS_STATUS {
+                crypto_kem_dec(shared_secret, ciphertext, secret_key)
+            }
+
+            fn write_keypair(
+                ek: &EncapsKey, dk: &DecapsKey, pk: &mut [u8; CRYPTO_PUBLICKEYBYTES],
+                sk: &mut [u8; CRYPTO_SECRETKEYBYTES],
+            ) -> i32 {
+                pk.copy_from_slice(ek.as_ref());
+                sk.copy_from_slice(dk.as_ref());
+                OQS_SUCCESS
+            }
+
+            fn write_encaps(
+                ssk: crate::SharedSecretKey, ciphertext: &CipherText,
+                ct: &mut [u8; CRYPTO_CIPHERTEXTBYTES], ss: &mut [u8; CRYPTO_BYTES],
+            ) -> i32 {
+                ct.copy_from_slice(ciphertext.as_ref());
+                ss.copy_from_slice(&ssk.into_bytes());
+                OQS_SUCCESS
+            }
+        }
+    };
+}
+
+
+#[cfg(feature = "ml-kem-512")]
+compat!(
+    ml_kem_512,
+    "ML-KEM-512",
+    OQS_KEM_alg_ml_kem_512,
+    OQS_KEM_ml_kem_512_length_public_key,
+    OQS_KEM_ml_kem_512_length_secret_key,
+    OQS_KEM_ml_kem_512_length_ciphertext,
+    OQS_KEM_ml_kem_512_length_shared_secret,
+    OQS_KEM_ml_kem_512_length_keypair_seed,
+    OQS_KEM_ml_kem_512_length_encaps_seed,
+    OQS_KEM_ml_kem_512_keypair,
+    OQS_KEM_ml_kem_512_keypair_derand,
+    OQS_KEM_ml_kem_512_encaps,
+    OQS_KEM_ml_kem_512_encaps_derand,
+    OQS_KEM_ml_kem_512_decaps
+);
+
+#[cfg(feature = "ml-kem-768")]
+compat!(
+    ml_kem_768,
+    "ML-KEM-768",
+    OQS_KEM_alg_ml_kem_768,
+    OQS_KEM_ml_kem_768_length_public_key,
+    OQS_KEM_ml_kem_768_length_secret_key,
+    OQS_KEM_ml_kem_768_length_ciphertext,
+    OQS_KEM_ml_kem_768_length_shared_secret,
+    OQS_KEM_ml_kem_768_length_keypair_seed,
+    OQS_KEM_ml_kem_768_length_encaps_seed,
+    OQS_KEM_ml_kem_768_keypair,
+    OQS_KEM_ml_kem_768_keypair_derand,
+    OQS_KEM_ml_kem_768_encaps,
+    OQS_KEM_ml_kem_768_encaps_derand,
+    OQS_KEM_ml_kem_768_decaps
+);
+
+#[cfg(feature = "ml-kem-1024")]
+compat!(
+    ml_kem_1024,
+    "ML-KEM-1024",
+    OQS_KEM_alg_ml_kem_1024,
+    OQS_KEM_ml_kem_1024_length_public_key,
+    OQS_KEM_ml_kem_1024_length_secret_key,
+    OQS_KEM_ml_kem_1024_length_ciphertext,
+    OQS_KEM_ml_kem_1024_length_shared_secret,
+    OQS_KEM_ml_kem_1024_length_keypair_seed,
+    OQS_KEM_ml_kem_1024_length_encaps_seed,
+    OQS_KEM_ml_kem_1024_keypair,
+    OQS_KEM_ml_kem_1024_keypair_derand,
+    OQS_KEM_ml_kem_1024_encaps,
+    OQS_KEM_ml_kem_1024_encaps_derand,
+    OQS_KEM_ml_kem_1024_decaps
+);
@@ -0,0 +1,494 @@
+//! COSE encodings of ML-KEM keys and `COSE_Encrypt0`/`COSE_Encrypt` key establishment (available with
+//! the `cose` feature). Everything is written into caller-provided buffers, so this works in
+//! `no_std` without an allocator.
+//!
+//! Keys are `COSE_Key`s of the "AKP" (algorithm key pair) key type (kty 7), whose `alg` names the
+//! parameter set, `pub` (-1) holds the encapsulation key and `priv` (-2) the 64-byte seed `d ∥ z`.
+//!
+//! Messages use direct key agreement: the sender encapsulates against the recipient's key,
+//! carries the ciphertext in the `ek` (-4) header parameter and derives the content key from the
+//! shared secret with HKDF-SHA256 over the `COSE_KDF_Context` of RFC 9053 (as ECDH-ES+HKDF-256
+//! does), whose `AlgorithmID` is the content encryption algorithm and whose `protected` member is
+//! the protected header of the layer that carries `ek`:
+//!
+//! ```text
+//! `COSE_Encrypt0` = 16([ << {1: enc} >>, {5: iv, -4: ek}, ciphertext ])
+//! `COSE_Encrypt`  = 96([ << {1: enc} >>, {5: iv}, ciphertext,
+//!                      [[ << {1: ML-KEM alg} >>, {-4: ek}, h'' ]] ])
+//! ```
+//!
+//! The COSE ML-KEM drafts have yet to be assigned algorithm code points, so the parameter sets use
+//! the values below from the private-use range until they are. As this crate implements the
+//! initial public draft of FIPS 203, its keys and ciphertexts do not interoperate with
+//! implementations of the final standard either.
+
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::{self, AeadInPlace, KeyInit};
+use aes_gcm::aes::Aes192;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, AesGcm};
+use hkdf::Hkdf;
+use rand_core::CryptoRngCore;
+use sha2::Sha256;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::cose::cbor::{Reader, Writer, ARRAY, MAP, TAG};
+use crate::helpers::ensure;
+use crate::SharedSecretKey;
+
+mod cbor;
+
+/// The provisional (private-use) COSE algorithm value for ML-KEM-512.
+pub const ALG_ML_KEM_512: i64 = -65537;
+
+/// The provisional (private-use) COSE algorithm value for ML-KEM-768.
+pub const ALG_ML_KEM_768: i64 = -65538;
+
+/// The provisional (private-use) COSE algorithm value for ML-KEM-1024.
+pub const ALG_ML_KEM_1024: i64 = -65539;
+
+/// The most bytes a message adds to the ML-KEM ciphertext and the plaintext, for sizing the
+/// output buffer of e.g. `ml_kem_768::EncapsKey::cose_encrypt()`.
+pub const OVERHEAD: usize = 64;
+
+/// The longest supported `external_aad`, which is authenticated from a fixed-size stack buffer.
+pub const MAX_EXTERNAL_AAD_LEN: usize = 256;
+
+type Aes192Gcm = AesGcm<Aes192, U12>;
+
+// The encapsulation key and, if present, the seed of a decoded COSE_Key
+type KeyParts<'a> = (&'a [u8], Option<Zeroizing<[u8; SEED_LEN]>>);
+
+const SEED_LEN: usize = 64;
+const IV_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+// COSE_Key and header parameter labels, and the message tags
+const KTY: i64 = 1;
+const KTY_AKP: i64 = 7;
+const KEY_ALG: i64 = 3;
+const AKP_PUB: i64 = -1;
+const AKP_PRIV: i64 = -2;
+const HEADER_ALG: i64 = 1;
+const HEADER_CRIT: i64 = 2;
+const HEADER_IV: i64 = 5;
+const HEADER_EK: i64 = -4;
+const TAG_ENCRYPT0: u64 = 16;
+const TAG_ENCRYPT: u64 = 96;
+
+// A protected header holds a single algorithm, and a COSE_KDF_Context little more
+const MAX_PROTECTED_LEN: usize = 16;
+
+
+/// The COSE content encryption algorithm, whose key is derived from the ML-KEM shared secret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentAlgorithm {
+    /// `A128GCM` (1): AES-128 in Galois/Counter Mode.
+    A128Gcm,
+    /// `A192GCM` (2): AES-192 in Galois/Counter Mode.
+    A192Gcm,
+    /// `A256GCM` (3): AES-256 in Galois/Counter Mode.
+    A256Gcm,
+}
+
+impl ContentAlgorithm {
+    /// Returns the COSE algorithm value.
+    #[must_use]
+    pub fn value(self) -> i64 {
+        match self {
+            ContentAlgorithm::A128Gcm => 1,
+            ContentAlgorithm::A192Gcm => 2,
+            ContentAlgorithm::A256Gcm => 3,
+        }
+    }
+
+    /// Returns the content key length in bytes.
+    #[must_use]
+    pub fn key_len(self) -> usize {
+        match self {
+            ContentAlgorithm::A128Gcm => 16,
+            ContentAlgorithm::A192Gcm => 24,
+            ContentAlgorithm::A256Gcm => 32,
+        }
+    }
+
+    /// Parses a COSE algorithm value.
+    /// # Errors
+    /// Returns an error on an unsupported algorithm.
+    pub fn try_from_value(value: i64) -> Result<Self, &'static str> {
+        match value {
+            1 => Ok(ContentAlgorithm::A128Gcm),
+            2 => Ok(ContentAlgorithm::A192Gcm),
+            3 => Ok(ContentAlgorithm::A256Gcm),
+            _ => Err("COSE: unsupported content algorithm"),
+        }
+    }
+}
+
+
+/// Encodes an AKP `COSE_Key` with the encapsulation key and, if given, the seed into `out`,
+/// returning the length.
+pub(crate) fn encode_key(
+    alg: i64, ek: &[u8], seed: Option<&[u8; SEED_LEN]>, out: &mut [u8],
+) -> Result<usize, &'static str> {
+    let mut writer = Writer::new(out);
+    writer.head(MAP, if seed.is_some() { 4 } else { 3 })?;
+    writer.int(KTY)?;
+    writer.int(KTY_AKP)?;
+    writer.int(KEY_ALG)?;
+    writer.int(alg)?;
+    writer.int(AKP_PUB)?;
+    writer.bstr(ek)?;
+    if let Some(seed) = seed {
+        writer.int(AKP_PRIV)?;
+        writer.bstr(seed)?;
+    }
+    Ok(writer.len())
+}
+
+
+/// Decodes an AKP `COSE_Key` for `alg`, returning the encapsulation key and, if present, the seed.
+pub(crate) fn decode_key(alg: i64, key: &[u8], ek_len: usize) -> Result<KeyParts<'_>, &'static str> {
+    let mut reader = Reader::new(key);
+    let (mut kty, mut key_alg, mut ek, mut seed) = (None, None, None, None);
+    for _ in 0..reader.expect(MAP)? {
+        let label = reader.int()?;
+        let slot_taken = match label {
+            KTY => kty.replace(reader.int()?).is_some(),
+            KEY_ALG => key_alg.replace(reader.int()?).is_some(),
+            AKP_PUB => ek.replace(reader.bstr()?).is_some(),
+            AKP_PRIV => {
+                let bytes = reader.bstr()?;
+                ensure!(bytes.len() == SEED_LEN, "COSE_Key: priv is not a 64-byte seed");
+                let mut value = Zeroizing::new([0u8; SEED_LEN]);
+                value.copy_from_slice(bytes);
+                seed.replace(value).is_some()
+            }
+            _ => {
+                reader.skip()?; // e.g. kid
+                false
+            }
+        };
+        ensure!(!slot_taken, "COSE_Key: duplicate label");
+    }
+    ensure!(reader.is_empty(), "COSE_Key: trailing data");
+    ensure!(kty == Some(KTY_AKP), "COSE_Key: kty is not AKP");
+    ensure!(key_alg == Some(alg), "COSE_Key: alg does not match the parameter set");
+    let ek = ek.ok_or("COSE_Key: missing pub")?;
+    ensure!(ek.len() == ek_len, "COSE_Key: pub has the wrong length");
+    Ok((ek, seed))
+}
+
+
+/// Writes a `COSE_Encrypt0` (when `kem_alg` is `None`) or a `COSE_Encrypt` with a single direct key
+/// agreement recipient for `kem_alg`, carrying `ek`, into `out` and returns the length.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn encrypt(
+    rng: &mut impl CryptoRngCore, kem_alg: Option<i64>, alg: ContentAlgorithm,
+    ssk: &SharedSecretKey, ek: &[u8], plaintext: &[u8], external_aad: &[u8], out: &mut [u8],
+) -> Result<usize, &'static str> {
+    let mut iv = [0u8; IV_LEN];
+    rng.try_fill_bytes(&mut iv)
+        .map_err(|_| "COSE: random number generator failed")?;
+    let (mut body_protected, mut recipient_protected) =
+        ([0u8; MAX_PROTECTED_LEN], [0u8; MAX_PROTECTED_LEN]);
+    let body_protected = encode_protected(alg.value(), &mut body_protected)?;
+    let recipient_protected = match kem_alg {
+        Some(kem_alg) => Some(encode_protected(kem_alg, &mut recipient_protected)?),
+        None => None,
+    };
+    let cek = derive_key(alg, ssk, recipient_protected.unwrap_or(body_protected))?;
+    let (context, tag) = match kem_alg {
+        Some(_) => ("Encrypt", TAG_ENCRYPT),
+        None => ("Encrypt0", TAG_ENCRYPT0),
+    };
+
+    let mut writer = Writer::new(out);
+    writer.head(TAG, tag)?;
+    writer.head(ARRAY, if kem_alg.is_some() { 4 } else { 3 })?;
+    writer.bstr(body_protected)?;
+    writer.head(MAP, if kem_alg.is_some() { 1 } else { 2 })?;
+    writer.int(HEADER_IV)?;
+    writer.bstr(&iv)?;
+    if kem_alg.is_none() {
+        writer.int(HEADER_EK)?;
+        writer.bstr(ek)?;
+    }
+    let ciphertext_len = plaintext
+        .len()
+        .checked_add(TAG_LEN)
+        .ok_or("COSE: plaintext too long")?;
+    writer.head(cbor::BSTR, ciphertext_len as u64)?;
+    let ciphertext = writer.reserve(ciphertext_len)?;
+    if let Some(recipient_protected) = recipient_protected {
+        writer.head(ARRAY, 1)?;
+        writer.head(ARRAY, 3)?;
+        writer.bstr(recipient_protected)?;
+        writer.head(MAP, 1)?;
+        writer.int(HEADER_EK)?;
+        writer.bstr(ek)?;
+        writer.bstr(b"")?; // Direct key agreement has no wrapped key
+    }
+    let len = writer.len();
+
+    // The plaintext is encrypted in place in the output
+    let buf = &mut writer.into_buf()[ciphertext];
+    let (in_out, tag) = buf.split_at_mut(plaintext.len());
+    in_out.copy_from_slice(plaintext);
+    seal(alg, &cek, &iv, context, body_protected, external_aad, in_out, tag)?;
+    Ok(len)
+}
+
+
+/// A parsed `COSE_Encrypt0` or `COSE_Encrypt`, ahead of decapsulating its `ek` and decrypting.
+pub(crate) struct Message<'a> {
+    context: &'static str,
+    alg: ContentAlgorithm,
+    iv: &'a [u8],
+    body_protected: &'a [u8],
+    // The protected header of the layer carrying `ek`, which the COSE_KDF_Context binds
+    kdf_protected: &'a [u8],
+    ciphertext: &'a [u8],
+}
+
+impl<'a> Message<'a> {
+    /// Parses a `COSE_Encrypt0` (when `kem_alg` is `None`) or a `COSE_Encrypt` with a single
+    /// recipient for `kem_alg` (tagged or not), returning the message and the `ek` to decapsulate.
+    pub(crate) fn parse(
+        message: &'a [u8], kem_alg: Option<i64>,
+    ) -> Result<(Self, &'a [u8]), &'static str> {
+        let mut reader = Reader::new(message);
+        let (context, tag, items) = match kem_alg {
+            Some(_) => ("Encrypt", TAG_ENCRYPT, 4),
+            None => ("Encrypt0", TAG_ENCRYPT0, 3),
+        };
+        if reader.peek()? == TAG {
+            ensure!(reader.expect(TAG)? == tag, "COSE: unexpected message tag");
+        }
+        ensure!(reader.expect(ARRAY)? == items, "COSE: unexpected message structure");
+        let body_protected = reader.bstr()?;
+        let body = Headers::parse(body_protected, &mut reader)?;
+        let ciphertext = reader
+            .bstr_or_null()?
+            .ok_or("COSE: detached content is not supported")?;
+
+        let (kdf_protected, ek) = match kem_alg {
+            None => (body_protected, body.ek.ok_or("COSE: missing ek")?),
+            Some(kem_alg) => {
+                ensure!(body.ek.is_none(), "COSE: unexpected ek");
+                ensure!(reader.expect(ARRAY)? == 1, "COSE: expected a single recipient");
+                ensure!(reader.expect(ARRAY)? == 3, "COSE: unexpected recipient structure");
+                let recipient_protected = reader.bstr()?;
+                let recipient = Headers::parse(recipient_protected, &mut reader)?;
+                ensure!(recipient.alg == Some(kem_alg), "COSE: alg does not match the key");
+                ensure!(recipient.iv.is_none(), "COSE: unexpected recipient iv");
+                let wrapped_key = reader.bstr_or_null()?;
+                ensure!(wrapped_key.map_or(true, <[u8]>::is_empty), "COSE: unexpected wrapped key");
+                (recipient_protected, recipient.ek.ok_or("COSE: missing ek")?)
+            }
+        };
+        ensure!(reader.is_empty(), "COSE: trailing data");
+
+        let message = Message {
+            context,
+            alg: ContentAlgorithm::try_from_value(body.alg.ok_or("COSE: missing alg")?)?,
+            iv: body.iv.ok_or("COSE: missing iv")?,
+            body_protected,
+            kdf_protected,
+            ciphertext,
+        };
+        ensure!(message.iv.len() == IV_LEN, "COSE: iv has the wrong length");
+        ensure!(message.ciphertext.len() >= TAG_LEN, "COSE: ciphertext too short");
+        Ok((message, ek))
+    }
+
+    /// Returns the plaintext length, for sizing the output of `decrypt()`.
+    fn plaintext_len(&self) -> usize { self.ciphertext.len() - TAG_LEN }
+
+    /// Decrypts and authenticates the content into `out` with the key derived from `ssk`,
+    /// returning the plaintext length. `out` is zeroized on failure.
+    pub(crate) fn decrypt(
+        &self, ssk: &SharedSecretKey, external_aad: &[u8], out: &mut [u8],
+    ) -> Result<usize, &'static str> {
+        let len = self.plaintext_len();
+        ensure!(out.len() >= len, "COSE: output buffer too small");
+        let cek = derive_key(self.alg, ssk, self.kdf_protected)?;
+        let (content, tag) = self.ciphertext.split_at(len);
+        out[..len].copy_from_slice(content);
+        let result = open(
+            self.alg,
+            &cek,
+            self.iv,
+            self.context,
+            self.body_protected,
+            external_aad,
+            &mut out[..len],
+            tag,
+        );
+        if result.is_err() {
+            out[..len].zeroize();
+        }
+        result.map(|()| len)
+    }
+}
+
+
+/// The header parameters this implementation understands, from a protected and an unprotected
+/// header map (no label may appear in both).
+struct Headers<'a> {
+    alg: Option<i64>,
+    iv: Option<&'a [u8]>,
+    ek: Option<&'a [u8]>,
+}
+
+impl<'a> Headers<'a> {
+    fn parse(protected: &'a [u8], unprotected: &mut Reader<'a>) -> Result<Self, &'static str> {
+        let mut headers = Headers { alg: None, iv: None, ek: None };
+        let mut protected_reader = Reader::new(protected);
+        // An empty protected header may be a zero-length byte string
+        if !protected.is_empty() {
+            headers.parse_map(&mut protected_reader)?;
+            ensure!(protected_reader.is_empty(), "COSE: trailing protected header data");
+        }
+        headers.parse_map(unprotected)?;
+        Ok(headers)
+    }
+
+    fn parse_map(&mut self, reader: &mut Reader<'a>) -> Result<(), &'static str> {
+        for _ in 0..reader.expect(MAP)? {
+            let label = match reader.peek()? {
+                cbor::UINT | cbor::NINT => reader.int()?,
+                cbor::TSTR => {
+                    reader.skip()?; // Text labels are never understood (nor critical)
+                    reader.skip()?;
+                    continue;
+                }
+                _ => return Err("COSE: malformed header label"),
+            };
+            let slot_taken = match label {
+                HEADER_ALG => self.alg.replace(reader.int()?).is_some(),
+                HEADER_IV => self.iv.replace(reader.bstr()?).is_some(),
+                HEADER_EK => self.ek.replace(reader.bstr()?).is_some(),
+                HEADER_CRIT => return Err("COSE: critical header parameters are not supported"),
+                _ => {
+                    reader.skip()?; // e.g. kid
+                    false
+                }
+            };
+            ensure!(!slot_taken, "COSE: duplicate header parameter");
+        }
+        Ok(())
+    }
+}
+
+
+/// Encodes the protected header `{1: alg}` into `buf`.
+fn encode_protected(alg: i64, buf: &mut [u8; MAX_PROTECTED_LEN]) -> Result<&[u8], &'static str> {
+    let mut writer = Writer::new(buf);
+    writer.head(MAP, 1)?;
+    writer.int(HEADER_ALG)?;
+    writer.int(alg)?;
+    let len = writer.len();
+    Ok(&buf[..len])
+}
+
+
+/// Derives the content key for `alg` from `ssk` with HKDF-SHA256 (no salt) over the
+/// `COSE_KDF_Context` of RFC 9053 section 5.2, with null party information:
+/// `[alg, [null, null, null], [null, null, null], [key bits, protected]]`.
+fn derive_key(
+    alg: ContentAlgorithm, ssk: &SharedSecretKey, protected: &[u8],
+) -> Result<Zeroizing<[u8; 32]>, &'static str> {
+    let mut context = [0u8; 32 + MAX_PROTECTED_LEN];
+    let mut writer = Writer::new(&mut context);
+    writer.head(ARRAY, 4)?;
+    writer.int(alg.value())?;
+    for _party in 0..2 {
+        writer.head(ARRAY, 3)?;
+        writer.raw(&[cbor::NULL; 3])?;
+    }
+    writer.head(ARRAY, 2)?;
+    writer.head(cbor::UINT, alg.key_len() as u64 * 8)?; // keyDataLength in bits
+    writer
+        .bstr(protected)
+        .map_err(|_| "COSE: protected header too long")?;
+    let len = writer.len();
+
+    let mut key = Zeroizing::new([0u8; 32]);
+    Hkdf::<Sha256>::new(None, &ssk.0)
+        .expand(&context[..len], &mut key[..alg.key_len()])
+        .map_err(|_| "COSE: key derivation failed")?;
+    Ok(key)
+}
+
+
+/// Writes the `Enc_structure` that AES-GCM authenticates into `buf`, returning its length.
+fn enc_structure<'b>(
+    context: &str, body_protected: &[u8], external_aad: &[u8],
+    buf: &'b mut [u8; 48 + MAX_EXTERNAL_AAD_LEN],
+) -> Result<&'b [u8], &'static str> {
+    ensure!(external_aad.len() <= MAX_EXTERNAL_AAD_LEN, "COSE: external_aad too long");
+    let mut writer = Writer::new(buf);
+    writer.head(ARRAY, 3)?;
+    writer.tstr(context)?;
+    writer
+        .bstr(body_protected)
+        .map_err(|_| "COSE: protected header too long")?;
+    writer.bstr(external_aad)?;
+    let len = writer.len();
+    Ok(&buf[..len])
+}
+
+
+#[allow(clippy::too_many_arguments)]
+fn seal(
+    alg: ContentAlgorithm, cek: &[u8; 32], iv: &[u8], context: &str, body_protected: &[u8],
+    external_aad: &[u8], in_out: &mut [u8], tag: &mut [u8],
+) -> Result<(), &'static str> {
+    let mut aad = [0u8; 48 + MAX_EXTERNAL_AAD_LEN];
+    let aad = enc_structure(context, body_protected, external_aad, &mut aad)?;
+    let cek = &cek[..alg.key_len()];
+    let computed = match alg {
+        ContentAlgorithm::A128Gcm => seal_with::<Aes128Gcm>(cek, iv, aad, in_out),
+        ContentAlgorithm::A192Gcm => seal_with::<Aes192Gcm>(cek, iv, aad, in_out),
+        ContentAlgorithm::A256Gcm => seal_with::<Aes256Gcm>(cek, iv, aad, in_out),
+    }?;
+    tag.copy_from_slice(&computed);
+    Ok(())
+}
+
+
+#[allow(clippy::too_many_arguments)]
+fn open(
+    alg: ContentAlgorithm, cek: &[u8; 32], iv: &[u8], context: &str, body_protected: &[u8],
+    external_aad: &[u8], in_out: &mut [u8], tag: &[u8],
+) -> Result<(), &'static str> {
+    let mut aad = [0u8; 48 + MAX_EXTERNAL_AAD_LEN];
+    let aad = enc_structure(context, body_protected, external_aad, &mut aad)?;
+    let cek = &cek[..alg.key_len()];
+    match alg {
+        ContentAlgorithm::A128Gcm => open_with::<Aes128Gcm>(cek, iv, aad, in_out, tag),
+        ContentAlgorithm::A192Gcm => open_with::<Aes192Gcm>(cek, iv, aad, in_out, tag),
+        ContentAlgorithm::A256Gcm => open_with::<Aes256Gcm>(cek, iv, aad, in_out, tag),
+    }
+}
+
+
+fn seal_with<C: KeyInit + AeadInPlace>(
+    cek: &[u8], iv: &[u8], aad: &[u8], content: &mut [u8],
+) -> Result<aead::Tag<C>, &'static str> {
+    let cipher = C::new_from_slice(cek).map_err(|_| "COSE: key has the wrong length")?;
+    cipher
+        .encrypt_in_place_detached(aead::Nonce::<C>::from_slice(iv), aad, content)
+        .map_err(|_| "COSE: encryption failed")
+}
+
+
+fn open_with<C: KeyInit + AeadInPlace>(
+    cek: &[u8], iv: &[u8], aad: &[u8], content: &mut [u8], tag: &[u8],
+) -> Result<(), &'static str> {
+    let cipher = C::new_from_slice(cek).map_err(|_| "COSE: key has the wrong length")?;
+    let (iv, tag) = (aead::Nonce::<C>::from_slice(iv), aead::Tag::<C>::from_slice(tag));
+    cipher
+        .decrypt_in_place_detached(iv, aad, content, tag)
+        .map_err(|_| "COSE: decryption failed")
+}
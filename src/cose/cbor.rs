@@ -0,0 +1,197 @@
+use crate::helpers::ensure;
+
+// The subset of CBOR (RFC 8949) needed for COSE: definite-length items, written into and read
+// from caller-provided buffers without allocation. The writer produces the core deterministic
+// encoding (shortest heads); the reader accepts any well-formed definite-length item.
+
+pub(crate) const UINT: u8 = 0;
+pub(crate) const NINT: u8 = 1;
+pub(crate) const BSTR: u8 = 2;
+pub(crate) const TSTR: u8 = 3;
+pub(crate) const ARRAY: u8 = 4;
+pub(crate) const MAP: u8 = 5;
+pub(crate) const TAG: u8 = 6;
+pub(crate) const SIMPLE: u8 = 7;
+
+pub(crate) const NULL: u8 = 0xF6;
+
+// Bounds the recursion of `Reader::skip()` on untrusted input
+const MAX_DEPTH: usize = 8;
+
+
+/// Appends CBOR items to a buffer.
+pub(crate) struct Writer<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl<'a> Writer<'a> {
+    pub(crate) fn new(buf: &'a mut [u8]) -> Self { Writer { buf, pos: 0 } }
+
+    /// Returns the number of bytes written.
+    pub(crate) fn len(&self) -> usize { self.pos }
+
+    pub(crate) fn raw(&mut self, bytes: &[u8]) -> Result<(), &'static str> {
+        let end = self
+            .pos
+            .checked_add(bytes.len())
+            .ok_or("CBOR: buffer too small")?;
+        ensure!(end <= self.buf.len(), "CBOR: buffer too small");
+        self.buf[self.pos..end].copy_from_slice(bytes);
+        self.pos = end;
+        Ok(())
+    }
+
+    pub(crate) fn head(&mut self, major: u8, arg: u64) -> Result<(), &'static str> {
+        let major = major << 5;
+        let bytes = arg.to_be_bytes();
+        match arg {
+            0..=23 => self.raw(&[major | bytes[7]]),
+            24..=0xFF => self.raw(&[major | 0x18, bytes[7]]),
+            0x100..=0xFFFF => {
+                self.raw(&[major | 0x19])?;
+                self.raw(&bytes[6..])
+            }
+            0x1_0000..=0xFFFF_FFFF => {
+                self.raw(&[major | 0x1A])?;
+                self.raw(&bytes[4..])
+            }
+            _ => {
+                self.raw(&[major | 0x1B])?;
+                self.raw(&bytes)
+            }
+        }
+    }
+
+    pub(crate) fn int(&mut self, value: i64) -> Result<(), &'static str> {
+        if value < 0 {
+            self.head(NINT, (!value).unsigned_abs()) // -1 - value
+        } else {
+            self.head(UINT, value.unsigned_abs())
+        }
+    }
+
+    pub(crate) fn bstr(&mut self, bytes: &[u8]) -> Result<(), &'static str> {
+        self.head(BSTR, bytes.len() as u64)?;
+        self.raw(bytes)
+    }
+
+    pub(crate) fn tstr(&mut self, text: &str) -> Result<(), &'static str> {
+        self.head(TSTR, text.len() as u64)?;
+        self.raw(text.as_bytes())
+    }
+
+    /// Reserves `len` bytes, e.g. for content that is encrypted in place, returning their range.
+    pub(crate) fn reserve(&mut self, len: usize) -> Result<core::ops::Range<usize>, &'static str> {
+        let start = self.pos;
+        let end = start.checked_add(len).ok_or("CBOR: buffer too small")?;
+        ensure!(end <= self.buf.len(), "CBOR: buffer too small");
+        self.pos = end;
+        Ok(start..end)
+    }
+
+    pub(crate) fn into_buf(self) -> &'a mut [u8] { self.buf }
+}
+
+
+/// Reads CBOR items from a buffer.
+#[derive(Clone)]
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self { Reader { buf, pos: 0 } }
+
+    pub(crate) fn is_empty(&self) -> bool { self.pos == self.buf.len() }
+
+    fn take(&mut self, len: u64) -> Result<&'a [u8], &'static str> {
+        let len = usize::try_from(len).map_err(|_| "CBOR: truncated")?;
+        ensure!(len <= self.buf.len() - self.pos, "CBOR: truncated");
+        let bytes = &self.buf[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Returns the next major type without consuming it.
+    pub(crate) fn peek(&self) -> Result<u8, &'static str> {
+        self.buf
+            .get(self.pos)
+            .map(|byte| byte >> 5)
+            .ok_or("CBOR: truncated")
+    }
+
+    /// Reads an item head, returning its major type and argument.
+    pub(crate) fn head(&mut self) -> Result<(u8, u64), &'static str> {
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1F);
+        let arg = match info {
+            0..=23 => u64::from(info),
+            24..=27 => {
+                let bytes = self.take(1 << (info - 24))?;
+                bytes
+                    .iter()
+                    .fold(0, |arg, byte| arg << 8 | u64::from(*byte))
+            }
+            _ => return Err("CBOR: indefinite lengths are not supported"),
+        };
+        Ok((major, arg))
+    }
+
+    pub(crate) fn expect(&mut self, major: u8) -> Result<u64, &'static str> {
+        let (actual, arg) = self.head()?;
+        ensure!(actual == major, "CBOR: unexpected type");
+        Ok(arg)
+    }
+
+    pub(crate) fn int(&mut self) -> Result<i64, &'static str> {
+        let (major, arg) = self.head()?;
+        let value = i64::try_from(arg).map_err(|_| "CBOR: integer out of range")?;
+        match major {
+            UINT => Ok(value),
+            NINT => Ok(!value), // -1 - arg
+            _ => Err("CBOR: expected an integer"),
+        }
+    }
+
+    pub(crate) fn bstr(&mut self) -> Result<&'a [u8], &'static str> {
+        let len = self.expect(BSTR)?;
+        self.take(len)
+    }
+
+    /// Reads a byte string or `null` (as `None`).
+    pub(crate) fn bstr_or_null(&mut self) -> Result<Option<&'a [u8]>, &'static str> {
+        if self.buf.get(self.pos) == Some(&NULL) {
+            self.pos += 1;
+            return Ok(None);
+        }
+        self.bstr().map(Some)
+    }
+
+    /// Skips one complete item of any type.
+    pub(crate) fn skip(&mut self) -> Result<(), &'static str> { self.skip_nested(0) }
+
+    fn skip_nested(&mut self, depth: usize) -> Result<(), &'static str> {
+        ensure!(depth < MAX_DEPTH, "CBOR: nested too deeply");
+        let (major, arg) = self.head()?;
+        match major {
+            UINT | NINT | SIMPLE => {}
+            BSTR | TSTR => {
+                self.take(arg)?;
+            }
+            ARRAY => {
+                for _ in 0..arg {
+                    self.skip_nested(depth + 1)?;
+                }
+            }
+            MAP => {
+                for _ in 0..arg.saturating_mul(2) {
+                    self.skip_nested(depth + 1)?;
+                }
+            }
+            _ => self.skip_nested(depth + 1)?, // The tagged item
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use sha3::digest::XofReader;
+
+// Global operation counters behind the `counters` feature. The kernels record each call through
+// the `count!()` macro in `helpers.rs`, which compiles to nothing when the feature is disabled.
+// Reductions are counted per coefficient: the scalar code records each `Z256::mul()` and
+// `Z256::reduce_u32()`, and the vectorized and assembly kernels record the coefficients they
+// reduce as a whole, so every backend reports the same number. The fused base multiplications
+// defer their reductions to `reduce_acc()` and so record none.
+
+static NTT: AtomicUsize = AtomicUsize::new(0);
+static NTT_INV: AtomicUsize = AtomicUsize::new(0);
+static KECCAK_F: AtomicUsize = AtomicUsize::new(0);
+static REDUCTIONS: AtomicUsize = AtomicUsize::new(0);
+
+
+/// Operations performed since the last `reset()`, as returned by `read()` and `measure()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OpCounts {
+    /// Forward NTTs (Algorithm 8).
+    pub ntt: usize,
+    /// Inverse NTTs (Algorithm 9).
+    pub ntt_inv: usize,
+    /// Keccak-f\[1600\] permutations across G, H, J, PRF and XOF.
+    pub keccak_f: usize,
+    /// Coefficients reduced modulo q after a multiplication or a deferred accumulation: 896 per
+    /// NTT, 1152 per inverse NTT (including the final scaling), 128 per base multiplication
+    /// `b1 · γ` cache and 256 per reduced accumulator.
+    pub reductions: usize,
+}
+
+
+/// Zeroes all counters.
+pub fn reset() {
+    for counter in [&NTT, &NTT_INV, &KECCAK_F, &REDUCTIONS] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+
+/// Returns the counts accumulated since the last `reset()`. The counters are global, so
+/// operations running concurrently on other threads are included.
+#[must_use]
+pub fn read() -> OpCounts {
+    OpCounts {
+        ntt: NTT.load(Ordering::Relaxed),
+        ntt_inv: NTT_INV.load(Ordering::Relaxed),
+        keccak_f: KECCAK_F.load(Ordering::Relaxed),
+        reductions: REDUCTIONS.load(Ordering::Relaxed),
+    }
+}
+
+
+/// Resets the counters, runs `op` and returns its result along with the counts it accumulated.
+///
+/// # Examples
+/// ```rust
+/// use fips203::ml_kem_512;
+/// use fips203::traits::KeyGen;
+///
+/// let (_keys, counts) = fips203::counters::measure(|| ml_kem_512::KG::try_keygen_vt());
+/// assert_eq!(counts.ntt, 4);
+/// ```
+pub fn measure<T>(op: impl FnOnce() -> T) -> (T, OpCounts) {
+    reset();
+    let result = op();
+    (result, read())
+}
+
+
+pub(crate) fn ntt() { NTT.fetch_add(1, Ordering::Relaxed); }
+
+pub(crate) fn ntt_inv() { NTT_INV.fetch_add(1, Ordering::Relaxed); }
+
+pub(crate) fn reductions(coefficients: usize) {
+    REDUCTIONS.fetch_add(coefficients, Ordering::Relaxed);
+}
+
+/// A sponge with the given `rate` (in bytes) that absorbs `absorbed` bytes and then squeezes
+/// `squeezed` bytes runs one permutation per full input block, one for the padded final block,
+/// and one per additional output block.
+pub(crate) fn sponge(rate: usize, absorbed: usize, squeezed: usize) {
+    KECCAK_F.fetch_add(absorbed / rate + 1 + squeezed.saturating_sub(1) / rate, Ordering::Relaxed);
+}
+
+
+/// Wraps an XOF reader to count the permutations behind each additional output block, as the
+/// number of bytes squeezed is only known to the caller.
+pub(crate) struct CountingReader<R> {
+    inner: R,
+    rate: usize,
+    squeezed: usize,
+}
+
+impl<R> CountingReader<R> {
+    pub(crate) fn new(inner: R, rate: usize) -> Self { Self { inner, rate, squeezed: 0 } }
+}
+
+impl<R: XofReader> XofReader for CountingReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) {
+        let before = self.squeezed.saturating_sub(1) / self.rate;
+        self.squeezed += buffer.len();
+        let after = self.squeezed.saturating_sub(1) / self.rate;
+        KECCAK_F.fetch_add(after - before, Ordering::Relaxed);
+        self.inner.read(buffer);
+    }
+}
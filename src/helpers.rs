@@ -2,7 +2,10 @@ use sha3::digest::Update;
 use sha3::digest::{ExtendableOutput, XofReader};
 use sha3::{Digest, Sha3_256, Sha3_512, Shake128, Shake256};
 
-use crate::ntt::multiply_ntts;
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+use crate::ntt::mul_cache;
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+use crate::backend::{multiply_ntts_acc, reduce_acc};
 use crate::types::Z256;
 use crate::Q;
 
@@ -17,6 +20,16 @@ macro_rules! ensure {
 
 pub(crate) use ensure; // make available throughout crate
 
+/// Records an operation with the optional `counters` feature; expands to nothing without it.
+macro_rules! count {
+    ($counter:ident $(, $arg:expr)*) => {
+        #[cfg(feature = "counters")]
+        crate::counters::$counter($($arg),*);
+    };
+}
+
+pub(crate) use count;
+
 /// Vector addition; See bottom of page 9, second row: `z_hat` = `u_hat` + `v_hat`
 #[must_use]
 pub(crate) fn vec_add<const K: usize>(
@@ -32,89 +45,146 @@ pub(crate) fn vec_add<const K: usize>(
 }
 
 
+/// Precomputes the `b1 · γ` "mulcache" for each coordinate of a vector in the NTT domain, so that
+/// a fixed operand such as `s_hat` or `r_hat` can be reused across several products.
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+#[must_use]
+pub(crate) fn vec_mul_cache<const K: usize>(u_hat: &[[Z256; 256]; K]) -> [[Z256; 128]; K] {
+    let mut u_cache = [[Z256(0); 128]; K];
+    for i in 0..K {
+        u_cache[i] = mul_cache(&u_hat[i]);
+    }
+    u_cache
+}
+
+
 /// Matrix by vector multiplication; See top of page 10, first row: `w_hat` = `A_hat` mul `u_hat`
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
 #[must_use]
 pub(crate) fn mat_vec_mul<const K: usize>(
-    a_hat: &[[[Z256; 256]; K]; K], u_hat: &[[Z256; 256]; K],
+    a_hat: &[[[Z256; 256]; K]; K], u_hat: &[[Z256; 256]; K], u_cache: &[[Z256; 128]; K],
 ) -> [[Z256; 256]; K] {
     let mut w_hat = [[Z256(0); 256]; K];
     #[allow(clippy::needless_range_loop)]
     for i in 0..K {
-        #[allow(clippy::needless_range_loop)]
+        let mut acc = [0u32; 256];
         for j in 0..K {
-            let tmp = multiply_ntts(&a_hat[i][j], &u_hat[j]);
-            for k in 0..256 {
-                w_hat[i][k] = w_hat[i][k].add(tmp[k]);
-            }
+            multiply_ntts_acc(&mut acc, &a_hat[i][j], &u_hat[j], &u_cache[j]);
         }
+        w_hat[i] = reduce_acc(&acc);
     }
     w_hat
 }
 
 
 /// Matrix transpose by vector multiplication; See top of page 10, second row: `y_hat` = `A_hat^T` mul `u_hat`
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
 #[must_use]
 pub(crate) fn mat_t_vec_mul<const K: usize>(
-    a_hat: &[[[Z256; 256]; K]; K], u_hat: &[[Z256; 256]; K],
+    a_hat: &[[[Z256; 256]; K]; K], u_hat: &[[Z256; 256]; K], u_cache: &[[Z256; 128]; K],
 ) -> [[Z256; 256]; K] {
     let mut y_hat = [[Z256(0); 256]; K];
     #[allow(clippy::needless_range_loop)]
     for i in 0..K {
-        #[allow(clippy::needless_range_loop)]
+        let mut acc = [0u32; 256];
         for j in 0..K {
-            let tmp = multiply_ntts(&a_hat[j][i], &u_hat[j]);
-            for k in 0..256 {
-                y_hat[i][k] = y_hat[i][k].add(tmp[k]);
-            }
+            multiply_ntts_acc(&mut acc, &a_hat[j][i], &u_hat[j], &u_cache[j]);
         }
+        y_hat[i] = reduce_acc(&acc);
     }
     y_hat
 }
 
 
 /// Vector dot product; See top of page 10, third row: `z_hat` = `u_hat^T` mul `v_hat`
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
 #[must_use]
 pub(crate) fn dot_t_prod<const K: usize>(
-    u_hat: &[[Z256; 256]; K], v_hat: &[[Z256; 256]; K],
+    u_hat: &[[Z256; 256]; K], v_hat: &[[Z256; 256]; K], v_cache: &[[Z256; 128]; K],
 ) -> [Z256; 256] {
-    let mut result = [Z256(0); 256];
+    let mut acc = [0u32; 256];
     for j in 0..K {
-        let tmp = multiply_ntts(&u_hat[j], &v_hat[j]);
-        for k in 0..256 {
-            result[k] = result[k].add(tmp[k]);
-        }
+        multiply_ntts_acc(&mut acc, &u_hat[j], &v_hat[j], &v_cache[j]);
     }
-    result
+    reduce_acc(&acc)
 }
 
 // TODO: update hashers to take list of byte-strings to hash (to save assembly)
 
-/// Function PRF on page 16 (4.1).
-#[must_use]
-pub(crate) fn prf<const ETA_64: usize>(s: &[u8; 32], b: u8) -> [u8; ETA_64] {
-    let mut hasher = Shake256::default();
-    hasher.update(s);
-    hasher.update(&[b]);
-    let mut reader = hasher.finalize_xof();
-    let mut result = [0u8; ETA_64];
-    reader.read(&mut result);
-    result
+/// A SHAKE sponge that has absorbed a 32-byte seed once. PRF(σ, N) and XOF(ρ, i, j) only differ
+/// in their trailing index bytes across calls, so each call clones the absorbed state and appends
+/// those rather than absorbing the seed again.
+#[derive(Clone)]
+pub(crate) struct SeededSponge<H>(H);
+
+/// The absorbed σ or r from which PRF draws the CBD inputs.
+pub(crate) type SeededPrf = SeededSponge<Shake256>;
+
+/// The absorbed ρ from which XOF expands the entries of `A_hat`.
+pub(crate) type SeededXof = SeededSponge<Shake128>;
+
+impl<H: Clone + Default + Update + ExtendableOutput> SeededSponge<H> {
+    /// Absorbs `seed` (σ, r or ρ).
+    #[must_use]
+    pub(crate) fn new(seed: &[u8; 32]) -> Self {
+        let mut hasher = H::default();
+        hasher.update(seed);
+        Self(hasher)
+    }
+
+    /// Finalizes a copy of the absorbed state after appending `index`.
+    fn finalize_with(&self, index: &[u8]) -> H::Reader {
+        let mut hasher = self.0.clone();
+        hasher.update(index);
+        hasher.finalize_xof()
+    }
 }
 
+impl SeededPrf {
+    /// Function PRF on page 16 (4.1) for the absorbed seed.
+    #[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+    #[must_use]
+    pub(crate) fn prf<const ETA_64: usize>(&self, b: u8) -> [u8; ETA_64] {
+        let mut result = [0u8; ETA_64];
+        self.prf_into(b, &mut result);
+        result
+    }
+
+    /// Function PRF on page 16 (4.1) for the absorbed seed, writing `out.len()` = `64·η` bytes.
+    pub(crate) fn prf_into(&self, b: u8, out: &mut [u8]) {
+        count!(sponge, 136, 33, out.len());
+        self.finalize_with(&[b]).read(out);
+    }
+}
+
+impl SeededXof {
+    /// Function XOF on page 16 (4.2) for the absorbed seed.
+    #[must_use]
+    #[allow(clippy::let_and_return)]
+    pub(crate) fn xof(&self, i: u8, j: u8) -> impl XofReader {
+        count!(sponge, 168, 34, 0);
+        let reader = self.finalize_with(&[i, j]);
+        #[cfg(feature = "counters")]
+        let reader = crate::counters::CountingReader::new(reader, 168);
+        reader
+    }
+}
+
+
+/// Function PRF on page 16 (4.1), writing `out.len()` = `64·η` bytes for a runtime η.
+#[cfg(feature = "bench-internals")]
+pub(crate) fn prf_into(s: &[u8; 32], b: u8, out: &mut [u8]) { SeededPrf::new(s).prf_into(b, out); }
+
 
 /// Function XOF on page 16 (4.2).
+#[cfg(feature = "bench-internals")]
 #[must_use]
-pub(crate) fn xof(rho: &[u8; 32], i: u8, j: u8) -> impl XofReader {
-    let mut hasher = Shake128::default();
-    hasher.update(rho);
-    hasher.update(&[i]);
-    hasher.update(&[j]);
-    hasher.finalize_xof()
-}
+pub(crate) fn xof(rho: &[u8; 32], i: u8, j: u8) -> impl XofReader { SeededXof::new(rho).xof(i, j) }
 
 
 /// Function G on page 17 (4.4).
 pub(crate) fn g(bytes: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    count!(sponge, 72, bytes.iter().map(|b| b.len()).sum(), 64);
     let mut hasher = Sha3_512::new();
     bytes.iter().for_each(|b| Digest::update(&mut hasher, b));
     //Digest::update(&mut hasher, bytes);
@@ -130,6 +200,7 @@ pub(crate) fn g(bytes: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
 /// Function H on page 17 (4.3).
 #[must_use]
 pub(crate) fn h(bytes: &[u8]) -> [u8; 32] {
+    count!(sponge, 136, bytes.len(), 32);
     let mut hasher = Sha3_256::new();
     Digest::update(&mut hasher, bytes);
     let digest = hasher.finalize();
@@ -140,6 +211,7 @@ pub(crate) fn h(bytes: &[u8]) -> [u8; 32] {
 /// Function J n page 17 (4.4).
 #[must_use]
 pub(crate) fn j(bytes: &[&[u8]]) -> [u8; 32] {
+    count!(sponge, 136, bytes.iter().map(|b| b.len()).sum(), 32);
     let mut hasher = Shake256::default();
     bytes.iter().for_each(|b| hasher.update(b));
     // hasher.update(bytes);
@@ -181,3 +253,41 @@ pub(crate) fn decompress(d: u32, inout: &mut [Z256]) {
         y_ref.0 = nearest(Q * u32::from(y_ref.0), 2u32.pow(d));
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use sha3::digest::{ExtendableOutput, Update, XofReader};
+    use sha3::{Shake128, Shake256};
+
+    use crate::helpers::{SeededPrf, SeededXof};
+
+    #[test]
+    fn test_seeded_sponge_matches_fresh_absorption() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+        let seed: [u8; 32] = rng.gen();
+        let (prf_sponge, xof_sponge) = (SeededPrf::new(&seed), SeededXof::new(&seed));
+        for n in 0..8u8 {
+            let mut expected = [0u8; 192];
+            Shake256::default()
+                .chain(seed)
+                .chain([n])
+                .finalize_xof()
+                .read(&mut expected);
+            let mut actual = [0u8; 192];
+            prf_sponge.prf_into(n, &mut actual);
+            assert_eq!(expected, actual);
+
+            let mut expected = [0u8; 840];
+            Shake128::default()
+                .chain(seed)
+                .chain([n, 7 - n])
+                .finalize_xof()
+                .read(&mut expected);
+            let mut actual = [0u8; 840];
+            xof_sponge.xof(n, 7 - n).read(&mut actual);
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Internal primitives exposed for the `benches/internals.rs` microbenchmarks by the
+//! `bench-internals` feature. Not part of the public API: no stability guarantees, no input
+//! validation beyond that of the underlying functions.
+
+use sha3::digest::XofReader;
+
+use crate::types::Z256;
+use crate::{backend, helpers, ntt, Q};
+
+/// The 256 coefficients of a polynomial (or of its NTT representation), each in `[0, q)`.
+#[derive(Clone, Copy)]
+pub struct Poly([Z256; 256]);
+
+impl Poly {
+    /// Builds a polynomial from its coefficients, each reduced mod q.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn from_coeffs(coeffs: &[u16; 256]) -> Self {
+        Self(coeffs.map(|x| Z256((u32::from(x) % Q) as u16)))
+    }
+
+    /// Returns the coefficients.
+    #[must_use]
+    pub fn coeffs(&self) -> [u16; 256] { self.0.map(Z256::get_u16) }
+}
+
+/// Algorithm 8 `NTT(f)`, as selected by `backend.rs`.
+#[must_use]
+pub fn ntt(array_f: &Poly) -> Poly { Poly(backend::ntt(&array_f.0)) }
+
+/// Algorithm 9 `NTTinv(f)`, as selected by `backend.rs`.
+#[must_use]
+pub fn ntt_inv(f_hat: &Poly) -> Poly { Poly(backend::ntt_inv(&f_hat.0)) }
+
+/// Algorithm 10 `MultiplyNTTs(f, g)` as K-PKE computes it: a `b1 · γ` cache for `g_hat`, one fused
+/// multiply-accumulate and a final reduction.
+#[must_use]
+pub fn multiply_ntts(f_hat: &Poly, g_hat: &Poly) -> Poly {
+    let mut acc = [0u32; 256];
+    backend::multiply_ntts_acc(&mut acc, &f_hat.0, &g_hat.0, &ntt::mul_cache(&g_hat.0));
+    Poly(backend::reduce_acc(&acc))
+}
+
+/// Algorithm 6 `SampleNTT(B)` on the XOF stream for `(rho, i, j)`.
+#[must_use]
+pub fn sample_ntt(rho: &[u8; 32], i: u8, j: u8) -> Poly {
+    Poly(backend::sample_ntt(helpers::xof(rho, i, j)))
+}
+
+/// Algorithm 7 `SamplePolyCBDη(B)`.
+/// # Errors
+/// Returns an error when `byte_array_b` is not `64·η` bytes.
+pub fn sample_poly_cbd(eta: u32, byte_array_b: &[u8]) -> Result<Poly, &'static str> {
+    backend::sample_poly_cbd(eta, byte_array_b).map(Poly)
+}
+
+/// Compress on page 18, in place. As in K-PKE, the result may equal `2^d` (`ByteEncode` masks it).
+pub fn compress(d: u32, inout: &mut Poly) { backend::compress(d, &mut inout.0); }
+
+/// Algorithm 4 `ByteEncode_d(F)`.
+/// # Errors
+/// Returns an error on a `d` outside `1..=12`, a `bytes_b` that is not exactly `32 * d` bytes or
+/// a coefficient out of range.
+pub fn byte_encode(d: u32, integers_f: &Poly, bytes_b: &mut [u8]) -> Result<(), &'static str> {
+    backend::byte_encode(d, &integers_f.0, bytes_b)
+}
+
+/// Algorithm 5 `ByteDecode_d(B)`.
+/// # Errors
+/// Returns an error on a `d` outside `1..=12`, a `bytes_b` that is not exactly `32 * d` bytes or
+/// (for d = 12) an out-of-range coefficient.
+pub fn byte_decode(d: u32, bytes_b: &[u8], integers_f: &mut Poly) -> Result<(), &'static str> {
+    backend::byte_decode(d, bytes_b, &mut integers_f.0)
+}
+
+/// Function G on page 17.
+#[must_use]
+pub fn g(bytes: &[&[u8]]) -> ([u8; 32], [u8; 32]) { helpers::g(bytes) }
+
+/// Function H on page 17.
+#[must_use]
+pub fn h(bytes: &[u8]) -> [u8; 32] { helpers::h(bytes) }
+
+/// Function J on page 17.
+#[must_use]
+pub fn j(bytes: &[&[u8]]) -> [u8; 32] { helpers::j(bytes) }
+
+/// Function PRF on page 16, writing `out.len()` = `64·η` bytes.
+pub fn prf(s: &[u8; 32], b: u8, out: &mut [u8]) { helpers::prf_into(s, b, out); }
+
+/// Function XOF on page 16, squeezing `out.len()` bytes.
+pub fn xof(rho: &[u8; 32], i: u8, j: u8, out: &mut [u8]) { helpers::xof(rho, i, j).read(out); }
@@ -0,0 +1,393 @@
+//! JOSE encodings of ML-KEM keys and JWE key establishment (available with the `jose` feature).
+//!
+//! Keys are JSON Web Keys of the "AKP" (algorithm key pair) key type, whose `alg` names the
+//! parameter set (e.g. `ML-KEM-768`), `pub` holds the encapsulation key and `priv` the 64-byte
+//! seed `d ∥ z`, each base64url encoded:
+//!
+//! ```text
+//! {"kty":"AKP","alg":"ML-KEM-768","pub":"...","priv":"..."}
+//! ```
+//!
+//! JWE key establishment follows the direct key agreement mode of draft-ietf-jose-pqc-kem: the
+//! sender encapsulates against the recipient's key, carries the ciphertext in the `ek` header
+//! parameter and derives the content encryption key from the shared secret with the Concat KDF
+//! of RFC 7518 (as ECDH-ES does). Compact and (flattened) JSON serializations are produced by
+//! e.g. `ml_kem_768::EncapsKey::jwe_encrypt_compact()` and consumed by
+//! `ml_kem_768::DecapsKey::jwe_decrypt_compact()`; `jwe_encaps()` and `jwe_decaps()` expose just
+//! the key establishment for use with another JOSE library. As this crate implements the initial
+//! public draft of FIPS 203, its keys and ciphertexts do not interoperate with implementations of
+//! the final standard.
+
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::{self, AeadInPlace, KeyInit};
+use aes_gcm::aes::Aes192;
+use aes_gcm::{Aes128Gcm, Aes256Gcm, AesGcm};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64ct::{Base64UrlUnpadded, Encoding};
+use rand_core::CryptoRngCore;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::helpers::ensure;
+use crate::SharedSecretKey;
+
+const SEED_LEN: usize = 64;
+const IV_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+type Aes192Gcm = AesGcm<Aes192, U12>;
+
+
+/// The JWE content encryption algorithm (`enc`), whose key is derived from the ML-KEM shared
+/// secret.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContentEncryption {
+    /// `A128GCM`: AES-128 in Galois/Counter Mode.
+    A128Gcm,
+    /// `A192GCM`: AES-192 in Galois/Counter Mode.
+    A192Gcm,
+    /// `A256GCM`: AES-256 in Galois/Counter Mode.
+    A256Gcm,
+}
+
+impl ContentEncryption {
+    /// Returns the `enc` header parameter value, e.g. `A256GCM`.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            ContentEncryption::A128Gcm => "A128GCM",
+            ContentEncryption::A192Gcm => "A192GCM",
+            ContentEncryption::A256Gcm => "A256GCM",
+        }
+    }
+
+    /// Returns the content encryption key length in bytes.
+    #[must_use]
+    pub fn key_len(self) -> usize {
+        match self {
+            ContentEncryption::A128Gcm => 16,
+            ContentEncryption::A192Gcm => 24,
+            ContentEncryption::A256Gcm => 32,
+        }
+    }
+
+    /// Parses an `enc` header parameter value.
+    /// # Errors
+    /// Returns an error on an unsupported algorithm.
+    pub fn try_from_name(name: &str) -> Result<Self, &'static str> {
+        match name {
+            "A128GCM" => Ok(ContentEncryption::A128Gcm),
+            "A192GCM" => Ok(ContentEncryption::A192Gcm),
+            "A256GCM" => Ok(ContentEncryption::A256Gcm),
+            _ => Err("JWE: unsupported enc"),
+        }
+    }
+}
+
+
+/// Derives the content encryption key for `enc` from the ML-KEM shared secret `ssk` with the
+/// Concat KDF (SHA-256) of RFC 7518 section 4.6.2, as for direct key agreement: the
+/// `AlgorithmID` is the `enc` value and `PartyUInfo`/`PartyVInfo` are the (decoded) `apu`/`apv`
+/// header parameters, empty when absent.
+#[must_use]
+pub fn derive_cek(
+    enc: ContentEncryption, ssk: &SharedSecretKey, apu: &[u8], apv: &[u8],
+) -> Zeroizing<Vec<u8>> {
+    let key_len = enc.key_len();
+    let mut hasher = Sha256::new();
+    hasher.update(1u32.to_be_bytes()); // One round covers up to 256 bits
+    hasher.update(ssk.0);
+    for field in [enc.name().as_bytes(), apu, apv] {
+        hasher.update((field.len() as u32).to_be_bytes());
+        hasher.update(field);
+    }
+    hasher.update((key_len as u32 * 8).to_be_bytes());
+    let mut digest = hasher.finalize();
+    let cek = Zeroizing::new(digest[..key_len].to_vec());
+    digest.zeroize();
+    cek
+}
+
+
+/// Encodes an AKP JWK with the encapsulation key and, if given, the seed.
+pub(crate) fn encode_jwk(alg: &str, ek: &[u8], seed: Option<&[u8; SEED_LEN]>) -> String {
+    let pub_len = Base64UrlUnpadded::encoded_len(ek);
+    let priv_len = seed.map_or(0, |seed| Base64UrlUnpadded::encoded_len(seed) + 10);
+    // Allocated once at the final size, so no partial copy of the seed is left behind
+    let mut jwk = String::with_capacity(31 + alg.len() + pub_len + priv_len);
+    jwk.push_str(r#"{"kty":"AKP","alg":""#);
+    jwk.push_str(alg);
+    jwk.push_str(r#"","pub":""#);
+    jwk.push_str(&Base64UrlUnpadded::encode_string(ek));
+    if let Some(seed) = seed {
+        let seed = Zeroizing::new(Base64UrlUnpadded::encode_string(seed));
+        jwk.push_str(r#"","priv":""#);
+        jwk.push_str(&seed);
+    }
+    jwk.push_str(r#""}"#);
+    jwk
+}
+
+
+/// The encapsulation key and, if present, the seed of a decoded JWK.
+type JwkParts = (Vec<u8>, Option<Zeroizing<[u8; SEED_LEN]>>);
+
+
+/// Decodes an AKP JWK for `alg`, returning the encapsulation key and, if present, the seed.
+pub(crate) fn decode_jwk(alg: &str, jwk: &str, ek_len: usize) -> Result<JwkParts, &'static str> {
+    let mut jwk: Map<String, Value> =
+        serde_json::from_str(jwk).map_err(|_| "JWK: malformed JSON")?;
+    let mut seed_b64 = jwk.remove("priv");
+    let result = (|| {
+        ensure!(str_param(&jwk, "kty")? == Some("AKP"), "JWK: kty is not AKP");
+        ensure!(
+            str_param(&jwk, "alg")? == Some(alg),
+            "JWK: alg does not match the parameter set"
+        );
+        let ek = decode_b64(str_param(&jwk, "pub")?.ok_or("JWK: missing pub")?)?;
+        ensure!(ek.len() == ek_len, "JWK: pub has the wrong length");
+        let seed = match &seed_b64 {
+            None => None,
+            Some(Value::String(seed_b64)) => {
+                let mut seed = Zeroizing::new([0u8; SEED_LEN]);
+                let decoded = Base64UrlUnpadded::decode(seed_b64, seed.as_mut())
+                    .map_err(|_| "JWK: priv is not a 64-byte seed")?;
+                ensure!(decoded.len() == SEED_LEN, "JWK: priv is not a 64-byte seed");
+                Some(seed)
+            }
+            Some(_) => return Err("JWK: priv is not a string"),
+        };
+        Ok((ek, seed))
+    })();
+    if let Some(Value::String(seed_b64)) = &mut seed_b64 {
+        seed_b64.zeroize();
+    }
+    result
+}
+
+
+/// The parsed parts of a JWE, ahead of decapsulating its `ek` and decrypting.
+pub(crate) struct Jwe {
+    header: Map<String, Value>,
+    protected_b64: String,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+    tag: Vec<u8>,
+    aad_b64: Option<String>,
+}
+
+impl Jwe {
+    /// Parses the compact serialization, whose encrypted key is empty for direct key agreement.
+    pub(crate) fn try_from_compact(jwe: &str) -> Result<Self, &'static str> {
+        let mut parts = jwe.split('.');
+        let mut next = || parts.next().ok_or("JWE: expected five compact parts");
+        let (protected_b64, encrypted_key, iv, ciphertext, tag) =
+            (next()?, next()?, next()?, next()?, next()?);
+        ensure!(parts.next().is_none(), "JWE: expected five compact parts");
+        ensure!(encrypted_key.is_empty(), "JWE: unexpected encrypted key");
+        Ok(Jwe {
+            header: decode_header(protected_b64)?,
+            protected_b64: protected_b64.to_string(),
+            iv: decode_b64(iv)?,
+            ciphertext: decode_b64(ciphertext)?,
+            tag: decode_b64(tag)?,
+            aad_b64: None,
+        })
+    }
+
+    /// Parses the flattened JSON serialization, or the general one with a single recipient. The
+    /// header is the union of the protected, shared unprotected and recipient header parameters.
+    pub(crate) fn try_from_json(jwe: &str) -> Result<Self, &'static str> {
+        let jwe: Map<String, Value> =
+            serde_json::from_str(jwe).map_err(|_| "JWE: malformed JSON")?;
+        let recipient = match jwe.get("recipients") {
+            None => &jwe,
+            Some(Value::Array(recipients)) if recipients.len() == 1 => match &recipients[0] {
+                Value::Object(recipient) => recipient,
+                _ => return Err("JWE: malformed recipient"),
+            },
+            Some(_) => return Err("JWE: expected a single recipient"),
+        };
+        let encrypted_key = str_param(recipient, "encrypted_key")?;
+        ensure!(encrypted_key.map_or(true, str::is_empty), "JWE: unexpected encrypted key");
+
+        let protected_b64 = str_param(&jwe, "protected")?.unwrap_or_default();
+        let mut header = if protected_b64.is_empty() {
+            Map::new()
+        } else {
+            decode_header(protected_b64)?
+        };
+        for unprotected in [jwe.get("unprotected"), recipient.get("header")]
+            .into_iter()
+            .flatten()
+        {
+            let Value::Object(unprotected) = unprotected else {
+                return Err("JWE: malformed header");
+            };
+            for (name, value) in unprotected {
+                ensure!(!header.contains_key(name), "JWE: duplicate header parameter");
+                header.insert(name.clone(), value.clone());
+            }
+        }
+        let required = |name| {
+            str_param(&jwe, name)?
+                .ok_or("JWE: missing member")
+                .and_then(decode_b64)
+        };
+        Ok(Jwe {
+            header,
+            protected_b64: protected_b64.to_string(),
+            iv: required("iv")?,
+            ciphertext: required("ciphertext")?,
+            tag: required("tag")?,
+            aad_b64: str_param(&jwe, "aad")?.map(ToString::to_string),
+        })
+    }
+
+    /// Checks `alg` and the parameters this implementation does not understand, returning `enc`
+    /// and the `ek` ciphertext.
+    pub(crate) fn key_agreement(
+        &self, alg: &str,
+    ) -> Result<(ContentEncryption, Vec<u8>), &'static str> {
+        ensure!(str_param(&self.header, "alg")? == Some(alg), "JWE: alg does not match the key");
+        ensure!(!self.header.contains_key("zip"), "JWE: compression is not supported");
+        ensure!(!self.header.contains_key("crit"), "JWE: critical extensions are not supported");
+        let enc = ContentEncryption::try_from_name(
+            str_param(&self.header, "enc")?.ok_or("JWE: missing enc")?,
+        )?;
+        let ek = decode_b64(str_param(&self.header, "ek")?.ok_or("JWE: missing ek")?)?;
+        Ok((enc, ek))
+    }
+
+    /// Returns the decoded `apu` and `apv` header parameters, empty when absent.
+    pub(crate) fn party_info(&self) -> Result<(Vec<u8>, Vec<u8>), &'static str> {
+        let decode = |name| str_param(&self.header, name)?.map_or(Ok(Vec::new()), decode_b64);
+        Ok((decode("apu")?, decode("apv")?))
+    }
+
+    /// Decrypts and authenticates the content with the derived `cek`.
+    pub(crate) fn decrypt(
+        self, enc: ContentEncryption, cek: &[u8],
+    ) -> Result<Zeroizing<Vec<u8>>, &'static str> {
+        ensure!(self.iv.len() == IV_LEN, "JWE: iv has the wrong length");
+        ensure!(self.tag.len() == TAG_LEN, "JWE: tag has the wrong length");
+        let aad = additional_data(&self.protected_b64, self.aad_b64.as_deref());
+        let mut buffer = Zeroizing::new(self.ciphertext);
+        match enc {
+            ContentEncryption::A128Gcm => {
+                open::<Aes128Gcm>(cek, &self.iv, &aad, &mut buffer, &self.tag)
+            }
+            ContentEncryption::A192Gcm => {
+                open::<Aes192Gcm>(cek, &self.iv, &aad, &mut buffer, &self.tag)
+            }
+            ContentEncryption::A256Gcm => {
+                open::<Aes256Gcm>(cek, &self.iv, &aad, &mut buffer, &self.tag)
+            }
+        }?;
+        Ok(buffer)
+    }
+}
+
+
+/// Encrypts `plaintext` under `cek` and serializes the JWE, compact unless `json_aad` is given.
+pub(crate) fn encrypt(
+    rng: &mut impl CryptoRngCore, alg: &str, enc: ContentEncryption, cek: &[u8], ek: &[u8],
+    plaintext: &[u8], json_aad: Option<&[u8]>,
+) -> Result<String, &'static str> {
+    let mut header = Map::new();
+    header.insert("alg".to_string(), Value::from(alg));
+    header.insert("enc".to_string(), Value::from(enc.name()));
+    header.insert("ek".to_string(), Value::from(Base64UrlUnpadded::encode_string(ek)));
+    let header = serde_json::to_string(&header).map_err(|_| "JWE: header encoding failed")?;
+    let protected_b64 = Base64UrlUnpadded::encode_string(header.as_bytes());
+    let aad_b64 = json_aad
+        .filter(|aad| !aad.is_empty())
+        .map(Base64UrlUnpadded::encode_string);
+
+    let mut iv = [0u8; IV_LEN];
+    rng.try_fill_bytes(&mut iv)
+        .map_err(|_| "JWE: random number generator failed")?;
+    let aad = additional_data(&protected_b64, aad_b64.as_deref());
+    // The plaintext is encrypted in place, so this buffer holds ciphertext once sealed
+    let mut buffer = Zeroizing::new(plaintext.to_vec());
+    let tag = match enc {
+        ContentEncryption::A128Gcm => seal::<Aes128Gcm>(cek, &iv, &aad, &mut buffer),
+        ContentEncryption::A192Gcm => seal::<Aes192Gcm>(cek, &iv, &aad, &mut buffer),
+        ContentEncryption::A256Gcm => seal::<Aes256Gcm>(cek, &iv, &aad, &mut buffer),
+    }?;
+    let (iv, ciphertext, tag) = (
+        Base64UrlUnpadded::encode_string(&iv),
+        Base64UrlUnpadded::encode_string(&buffer),
+        Base64UrlUnpadded::encode_string(&tag),
+    );
+
+    if json_aad.is_none() {
+        return Ok([protected_b64.as_str(), "", &iv, &ciphertext, &tag].join("."));
+    }
+    let mut jwe = Map::new();
+    jwe.insert("protected".to_string(), Value::from(protected_b64));
+    if let Some(aad_b64) = aad_b64 {
+        jwe.insert("aad".to_string(), Value::from(aad_b64));
+    }
+    jwe.insert("iv".to_string(), Value::from(iv));
+    jwe.insert("ciphertext".to_string(), Value::from(ciphertext));
+    jwe.insert("tag".to_string(), Value::from(tag));
+    serde_json::to_string(&jwe).map_err(|_| "JWE: encoding failed")
+}
+
+
+// The AAD is ASCII(BASE64URL(protected)), followed by '.' and BASE64URL(aad) when present
+fn additional_data(protected_b64: &str, aad_b64: Option<&str>) -> Vec<u8> {
+    let mut aad = protected_b64.as_bytes().to_vec();
+    if let Some(aad_b64) = aad_b64 {
+        aad.push(b'.');
+        aad.extend_from_slice(aad_b64.as_bytes());
+    }
+    aad
+}
+
+
+fn seal<C: KeyInit + AeadInPlace>(
+    cek: &[u8], iv: &[u8], aad: &[u8], buffer: &mut [u8],
+) -> Result<Vec<u8>, &'static str> {
+    let cipher = C::new_from_slice(cek).map_err(|_| "JWE: cek has the wrong length")?;
+    let tag = cipher
+        .encrypt_in_place_detached(aead::Nonce::<C>::from_slice(iv), aad, buffer)
+        .map_err(|_| "JWE: encryption failed")?;
+    Ok(tag.to_vec())
+}
+
+
+fn open<C: KeyInit + AeadInPlace>(
+    cek: &[u8], iv: &[u8], aad: &[u8], buffer: &mut [u8], tag: &[u8],
+) -> Result<(), &'static str> {
+    let cipher = C::new_from_slice(cek).map_err(|_| "JWE: cek has the wrong length")?;
+    let (iv, tag) = (aead::Nonce::<C>::from_slice(iv), aead::Tag::<C>::from_slice(tag));
+    cipher
+        .decrypt_in_place_detached(iv, aad, buffer, tag)
+        .map_err(|_| "JWE: decryption failed")
+}
+
+
+fn decode_header(protected_b64: &str) -> Result<Map<String, Value>, &'static str> {
+    serde_json::from_slice(&decode_b64(protected_b64)?).map_err(|_| "JWE: malformed header")
+}
+
+
+fn decode_b64(encoded: &str) -> Result<Vec<u8>, &'static str> {
+    Base64UrlUnpadded::decode_vec(encoded).map_err(|_| "JOSE: malformed base64url")
+}
+
+
+/// Returns the string member `name`, if present.
+fn str_param<'a>(
+    object: &'a Map<String, Value>, name: &str,
+) -> Result<Option<&'a str>, &'static str> {
+    match object.get(name) {
+        None => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err("JOSE: member is not a string"),
+    }
+}
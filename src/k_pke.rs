@@ -1,32 +1,104 @@
-use rand_core::CryptoRngCore;
-
-use crate::byte_fns::{byte_decode, byte_encode};
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+use crate::backend::{
+    byte_decode, byte_encode, compress, ntt, ntt_inv, sample_ntt, sample_poly_cbd,
+};
+use crate::helpers::ensure;
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
 use crate::helpers::{
-    compress, decompress, dot_t_prod, ensure, g, mat_t_vec_mul, mat_vec_mul, prf, vec_add, xof,
+    decompress, dot_t_prod, g, mat_t_vec_mul, mat_vec_mul, vec_add, vec_mul_cache, SeededPrf,
+    SeededXof,
 };
-use crate::ntt::{ntt, ntt_inv};
-use crate::sampling::{sample_ntt, sample_poly_cbd};
+#[cfg(any(feature = "small-stack", feature = "small-code"))]
+use crate::k_pke_rt::{k_pke_decrypt_rt, k_pke_encrypt_rt, k_pke_key_gen_rt};
 use crate::types::Z256;
 
+/// The K-PKE parameters `k`, `η1`, `η2`, `du` and `dv` of a parameter set (table 2 on page 33),
+/// passed to the ML-KEM layer as values so that it is compiled once rather than per set.
+#[derive(Clone, Copy)]
+pub(crate) struct Params {
+    pub(crate) k: usize,
+    pub(crate) eta1: u32,
+    pub(crate) eta2: u32,
+    pub(crate) du: u32,
+    pub(crate) dv: u32,
+}
+
+
+// Without `small-code`, the functions below select the const-generic K-PKE for the parameter set,
+// i.e. the full implementation or, with `small-stack`, the wrapper sizing the scratch for `k`.
+// Those are `#[inline(never)]`, so that each set keeps its own stack frame rather than all of them
+// sharing the largest.
+
+
+/// Algorithm 12 `K-PKE.KeyGen()` on page 26 for the parameter set `p`.
+#[cfg(not(feature = "small-code"))]
+pub(crate) fn key_gen(
+    p: Params, d: &[u8; 32], ek_pke: &mut [u8], dk_pke: &mut [u8],
+) -> Result<(), &'static str> {
+    match (p.k, p.eta1) {
+        #[cfg(feature = "ml-kem-512")]
+        (2, 3) => k_pke_key_gen::<2, 3, 192>(d, ek_pke, dk_pke),
+        #[cfg(feature = "ml-kem-768")]
+        (3, 2) => k_pke_key_gen::<3, 2, 128>(d, ek_pke, dk_pke),
+        #[cfg(feature = "ml-kem-1024")]
+        (4, 2) => k_pke_key_gen::<4, 2, 128>(d, ek_pke, dk_pke),
+        _ => Err("Alg12: unsupported parameter set"),
+    }
+}
+
+
+/// Algorithm 13 `K-PKE.Encrypt(ekPKE , m, r)` on page 27 for the parameter set `p`.
+#[cfg(not(feature = "small-code"))]
+pub(crate) fn encrypt(
+    p: Params, ek: &[u8], m: &[u8], randomness: &[u8; 32], ct: &mut [u8],
+) -> Result<(), &'static str> {
+    match (p.k, p.eta1, p.eta2, p.du, p.dv) {
+        #[cfg(feature = "ml-kem-512")]
+        (2, 3, 2, 10, 4) => k_pke_encrypt::<2, 3, 192, 2, 128, 10, 4>(ek, m, randomness, ct),
+        #[cfg(feature = "ml-kem-768")]
+        (3, 2, 2, 10, 4) => k_pke_encrypt::<3, 2, 128, 2, 128, 10, 4>(ek, m, randomness, ct),
+        #[cfg(feature = "ml-kem-1024")]
+        (4, 2, 2, 11, 5) => k_pke_encrypt::<4, 2, 128, 2, 128, 11, 5>(ek, m, randomness, ct),
+        _ => Err("Alg13: unsupported parameter set"),
+    }
+}
+
+
+/// Algorithm 14 `K-PKE.Decrypt(dkPKE, c)` on page 28 for the parameter set `p`.
+#[cfg(not(feature = "small-code"))]
+pub(crate) fn decrypt(p: Params, dk: &[u8], ct: &[u8]) -> Result<[u8; 32], &'static str> {
+    match (p.k, p.du, p.dv) {
+        #[cfg(feature = "ml-kem-512")]
+        (2, 10, 4) => k_pke_decrypt::<2, 10, 4>(dk, ct),
+        #[cfg(feature = "ml-kem-768")]
+        (3, 10, 4) => k_pke_decrypt::<3, 10, 4>(dk, ct),
+        #[cfg(feature = "ml-kem-1024")]
+        (4, 11, 5) => k_pke_decrypt::<4, 11, 5>(dk, ct),
+        _ => Err("Alg14: unsupported parameter set"),
+    }
+}
+
+
 /// Algorithm 12 `K-PKE.KeyGen()` on page 26.
 /// Generates an encryption key and a corresponding decryption key.
 ///
 /// Output: encryption key `ekPKE ∈ B^{384*k+32}` <br>
 /// Output: decryption key `dkPKE ∈ B^{384*k}`
-#[allow(clippy::similar_names, clippy::module_name_repetitions)]
-pub fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
-    rng: &mut impl CryptoRngCore, ek_pke: &mut [u8], dk_pke: &mut [u8],
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+#[allow(clippy::similar_names)]
+#[inline(never)]
+fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
+    d: &[u8; 32], ek_pke: &mut [u8], dk_pke: &mut [u8],
 ) -> Result<(), &'static str> {
     ensure!(ek_pke.len() == 384 * K + 32, "Alg12: ek_pke not 384 * K + 32");
     ensure!(dk_pke.len() == 384 * K, "Alg12: dk_pke not 384 * K");
 
-    // 1: d ←− B^{32}                   ▷ d is 32 random bytes (see Section 3.3)
-    let mut d = [0u8; 32];
-    rng.try_fill_bytes(&mut d)
-        .map_err(|_| "Alg12: random number generator failed")?;
+    // 1: d ←− B^{32}                   ▷ d is 32 random bytes, drawn by `ml_kem_key_gen()`
 
     // 2: 2: (ρ, σ) ← G(d)             ▷ expand to two pseudorandom 32-byte seeds
-    let (rho, sigma) = g(&[&d]);
+    let (rho, sigma) = g(&[d]);
+    // Absorb each seed once; XOF(ρ, ·, ·) and PRF(σ, ·) clone the absorbed state per call
+    let (rho_sponge, sigma_sponge) = (SeededXof::new(&rho), SeededPrf::new(&sigma));
 
     // 3: 3: N ← 0
     let mut n = 0;
@@ -42,7 +114,8 @@ pub fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
             //
             // 6: A_hat[i, j] ← SampleNTT(XOF(ρ, i, j))     ▷ each entry of Â uniform in NTT domain
             // See page 21 regarding transpose of i, j -? j, i in XOF() https://csrc.nist.gov/files/pubs/fips/203/ipd/docs/fips-203-initial-public-comments-2023.pdf
-            a_hat[i][j] = sample_ntt(xof(&rho, u8::try_from(j).unwrap(), u8::try_from(i).unwrap()));
+            a_hat[i][j] =
+                sample_ntt(rho_sponge.xof(u8::try_from(j).unwrap(), u8::try_from(i).unwrap()));
             //
         } // 7: end for
     } // 8: end for
@@ -54,7 +127,7 @@ pub fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
     for i in 0..K {
         //
         // 10: s[i] ← SamplePolyCBDη1(PRFη1(σ, N))     ▷ s[i] ∈ Z^{256}_q sampled from CBD
-        s[i] = sample_poly_cbd(ETA1 as u32, &prf::<ETA1_64>(&sigma, n))?;
+        s[i] = sample_poly_cbd(ETA1 as u32, &sigma_sponge.prf::<ETA1_64>(n))?;
 
         // 11: N ← N +1
         n += 1;
@@ -68,7 +141,7 @@ pub fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
     for i in 0..K {
         //
         // 14: e[i] ← SamplePolyCBDη1(PRFη1(σ, N))     ▷ e[i] ∈ Z^{256}_q sampled from CBD
-        e[i] = sample_poly_cbd(ETA1 as u32, &prf::<ETA1_64>(&sigma, n))?;
+        e[i] = sample_poly_cbd(ETA1 as u32, &sigma_sponge.prf::<ETA1_64>(n))?;
 
         // 15: N ← N +1
         n += 1;
@@ -90,8 +163,9 @@ pub fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
     }
 
 
-    // 19: t̂ ← Â ◦ ŝ + ê
-    let as_hat = mat_vec_mul(&a_hat, &s_hat);
+    // 19: t̂ ← Â ◦ ŝ + ê                   ▷ ŝ is reused across every row, so cache its b1·γ terms
+    let s_cache = vec_mul_cache(&s_hat);
+    let as_hat = mat_vec_mul(&a_hat, &s_hat, &s_cache);
     let t_hat = vec_add(&as_hat, &e_hat);
 
     // 20: ek_{PKE} ← ByteEncode12(t̂)∥ρ        ▷ ByteEncode12 is run k times; include seed for Â
@@ -112,8 +186,10 @@ pub fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
 
 /// Algorithm 13 `K-PKE.Encrypt(ekPKE , m, r)` on page 27.
 /// Uses the encryption key to encrypt a plaintext message using the randomness r.
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
 #[allow(clippy::many_single_char_names)]
-pub(crate) fn k_pke_encrypt<
+#[inline(never)]
+fn k_pke_encrypt<
     const K: usize,
     const ETA1: usize,
     const ETA1_64: usize,
@@ -146,6 +222,8 @@ pub(crate) fn k_pke_encrypt<
     // 3: 3: ρ ← ekPKE [384k : 384k + 32]           ▷ extract 32-byte seed from ekPKE
     let mut rho = [0u8; 32];
     rho.copy_from_slice(&ek[384 * K..(384 * K + 32)]);
+    // Absorb each seed once; XOF(ρ, ·, ·) and PRF(r, ·) clone the absorbed state per call
+    let (rho_sponge, r_sponge) = (SeededXof::new(&rho), SeededPrf::new(randomness));
     let mut a_hat = [[[Z256(0); 256]; K]; K];
 
     // 4: for (i ← 0; i < k; i++)      ▷ re-generate matrix A_hat(Z_q{256})^{k×k}
@@ -158,7 +236,8 @@ pub(crate) fn k_pke_encrypt<
             //
             // 6: Â[i, j] ← SampleNTT(XOF(ρ, i, j))
 
-            a_hat[i][j] = sample_ntt(xof(&rho, u8::try_from(j).unwrap(), u8::try_from(i).unwrap()));
+            a_hat[i][j] =
+                sample_ntt(rho_sponge.xof(u8::try_from(j).unwrap(), u8::try_from(i).unwrap()));
             //
         } // 7: end for
     } // 8: end for
@@ -170,7 +249,7 @@ pub(crate) fn k_pke_encrypt<
     for i in 0..K {
         //
         // 10: r[i] ← SamplePolyCBDη 1 (PRFη 1 (r, N))      ▷ r[i] ∈ Z^{256}_q sampled from CBD
-        r[i] = sample_poly_cbd(ETA1 as u32, &prf::<ETA1_64>(randomness, n))?;
+        r[i] = sample_poly_cbd(ETA1 as u32, &r_sponge.prf::<ETA1_64>(n))?;
 
         // 11: N ← N +1
         n += 1;
@@ -184,7 +263,7 @@ pub(crate) fn k_pke_encrypt<
     for i in 0..K {
         //
         // 14: e1 [i] ← SamplePolyCBDη2(PRFη2(r, N))        ▷ e1 [i] ∈ Z^{256}_q sampled from CBD
-        e1[i] = sample_poly_cbd(ETA2 as u32, &prf::<ETA2_64>(randomness, n))?;
+        e1[i] = sample_poly_cbd(ETA2 as u32, &r_sponge.prf::<ETA2_64>(n))?;
 
         // 15: N ← N +1
         n += 1;
@@ -192,7 +271,7 @@ pub(crate) fn k_pke_encrypt<
     } // 16: end for
 
     // 17: 17: e2 ← SamplePolyCBDη(PRFη2(r, N))     ▷ sample e2 ∈ Z^{256}_q from CBD
-    let e2 = sample_poly_cbd(ETA2 as u32, &prf::<ETA2_64>(randomness, n))?;
+    let e2 = sample_poly_cbd(ETA2 as u32, &r_sponge.prf::<ETA2_64>(n))?;
 
     // 18: 18: r̂ ← NTT(r)              ▷ NTT is run k times
     let mut r_hat = [[Z256(0); 256]; K];
@@ -200,8 +279,11 @@ pub(crate) fn k_pke_encrypt<
         r_hat[i] = ntt(&r[i]);
     }
 
+    // r̂ is reused across every row of Â⊺ and again for t̂⊺, so cache its b1·γ terms
+    let r_cache = vec_mul_cache(&r_hat);
+
     // 19: u ← NTT−1 (Â⊺ ◦ r̂) + e1
-    let mut u = mat_t_vec_mul(&a_hat, &r_hat);
+    let mut u = mat_t_vec_mul(&a_hat, &r_hat, &r_cache);
     #[allow(clippy::needless_range_loop)]
     for i in 0..K {
         u[i] = ntt_inv(&u[i]);
@@ -214,7 +296,7 @@ pub(crate) fn k_pke_encrypt<
     decompress(1, &mut mu);
 
     // 21: v ← NTT−1 (t̂⊺ ◦ r̂) + e2 + µ        ▷ encode plaintext m into polynomial v.
-    let mut v = ntt_inv(&dot_t_prod(&t_hat, &r_hat));
+    let mut v = ntt_inv(&dot_t_prod(&t_hat, &r_hat, &r_cache));
     v = vec_add(&vec_add(&[v], &[e2]), &[mu])[0];
 
     // 22: c1 ← ByteEncode_{du}(Compress_{du}(u))       ▷ ByteEncodedu is run k times
@@ -235,7 +317,9 @@ pub(crate) fn k_pke_encrypt<
 
 /// Algorithm 14 `K-PKE.Decrypt(dkPKE, c)` on page 28.
 /// Uses the decryption key to decrypt a ciphertext.
-pub(crate) fn k_pke_decrypt<const K: usize, const DU: usize, const DV: usize>(
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+#[inline(never)]
+fn k_pke_decrypt<const K: usize, const DU: usize, const DV: usize>(
     dk: &[u8], ct: &[u8],
 ) -> Result<[u8; 32], &'static str> {
     // Input: decryption key dk_{PKE} ∈ B^{384*k}
@@ -268,19 +352,18 @@ pub(crate) fn k_pke_decrypt<const K: usize, const DU: usize, const DV: usize>(
         byte_decode(12, &dk[384 * i..384 * (i + 1)], &mut s_hat[i])?;
     }
 
-    // 6: w ← v − NTT−1 (ŝ⊺ ◦ NTT(u))           ▷ NTT−1 and NTT invoked k times
+    // 6: w ← v − NTT−1 (ŝ⊺ ◦ NTT(u))           ▷ NTT invoked k times, NTT−1 once
     let mut w = [Z256(0); 256];
     let mut ntt_u = [[Z256(0); 256]; K];
     #[allow(clippy::needless_range_loop)]
     for i in 0..K {
         ntt_u[i] = ntt(&u[i]);
     }
-    let st_ntt_u = dot_t_prod(&s_hat, &ntt_u);
-    for _i in 0..K {
-        let yy = ntt_inv(&st_ntt_u);
-        for i in 0..256 {
-            w[i] = v[i].sub(yy[i]);
-        }
+    // The b1·γ cache is built from the key operand ŝ, the product being commutative
+    let st_ntt_u = dot_t_prod(&ntt_u, &s_hat, &vec_mul_cache(&s_hat));
+    let yy = ntt_inv(&st_ntt_u);
+    for i in 0..256 {
+        w[i] = v[i].sub(yy[i]);
     }
 
     // 7: m ← ByteEncode1 (Compress1 (w))       ▷ decode plaintext m from polynomial v
@@ -291,3 +374,100 @@ pub(crate) fn k_pke_decrypt<const K: usize, const DU: usize, const DV: usize>(
     // 8: return m
     Ok(m)
 }
+
+
+// With `small-stack`, the const-generic functions only size the `k`-entry scratch buffers and call
+// the streaming implementation in `k_pke_rt.rs`, which never materializes `A_hat` (k*k
+// polynomials, so 8 KiB for ML-KEM-1024).
+
+
+/// Algorithm 12 `K-PKE.KeyGen()` on page 26, streaming `A_hat` (`small-stack` wrapper).
+/// Generates an encryption key and a corresponding decryption key.
+#[cfg(all(feature = "small-stack", not(feature = "small-code")))]
+#[inline(never)]
+fn k_pke_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
+    d: &[u8; 32], ek_pke: &mut [u8], dk_pke: &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!(ETA1 * 64 == ETA1_64, "Alg12: const probs");
+    let (mut s_hat, mut s_cache) = ([[Z256(0); 256]; K], [[Z256(0); 128]; K]);
+    k_pke_key_gen_rt(K, ETA1 as u32, d, ek_pke, dk_pke, &mut s_hat, &mut s_cache)
+}
+
+
+/// Algorithm 13 `K-PKE.Encrypt(ekPKE , m, r)` on page 27, streaming `A_hat` (`small-stack`
+/// wrapper). Uses the encryption key to encrypt a plaintext message using the randomness r.
+#[cfg(all(feature = "small-stack", not(feature = "small-code")))]
+#[inline(never)]
+fn k_pke_encrypt<
+    const K: usize,
+    const ETA1: usize,
+    const ETA1_64: usize,
+    const ETA2: usize,
+    const ETA2_64: usize,
+    const DU: usize,
+    const DV: usize,
+>(
+    ek: &[u8], m: &[u8], randomness: &[u8; 32], ct: &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!(ETA1 * 64 == ETA1_64, "Alg13: const probs");
+    ensure!(ETA2 * 64 == ETA2_64, "Alg13: const probs");
+    let p = Params {
+        k: K,
+        eta1: ETA1 as u32,
+        eta2: ETA2 as u32,
+        du: DU as u32,
+        dv: DV as u32,
+    };
+    let (mut r_hat, mut r_cache) = ([[Z256(0); 256]; K], [[Z256(0); 128]; K]);
+    k_pke_encrypt_rt(p, ek, m, randomness, ct, &mut r_hat, &mut r_cache)
+}
+
+
+/// Algorithm 14 `K-PKE.Decrypt(dkPKE, c)` on page 28, one coordinate at a time (`small-stack`
+/// wrapper). Uses the decryption key to decrypt a ciphertext.
+#[cfg(all(feature = "small-stack", not(feature = "small-code")))]
+#[inline(never)]
+fn k_pke_decrypt<const K: usize, const DU: usize, const DV: usize>(
+    dk: &[u8], ct: &[u8],
+) -> Result<[u8; 32], &'static str> {
+    k_pke_decrypt_rt(K, DU as u32, DV as u32, dk, ct)
+}
+
+
+// With `small-code`, every parameter set calls the runtime-parameterized implementation in
+// `k_pke_rt.rs` directly, with scratch buffers sized for the largest `k`, so no K-PKE code is
+// generated per set.
+
+/// Largest `k`, that of ML-KEM-1024.
+#[cfg(feature = "small-code")]
+const K_MAX: usize = 4;
+
+
+/// Algorithm 12 `K-PKE.KeyGen()` on page 26 for the parameter set `p` (`small-code`).
+#[cfg(feature = "small-code")]
+pub(crate) fn key_gen(
+    p: Params, d: &[u8; 32], ek_pke: &mut [u8], dk_pke: &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!(p.k <= K_MAX, "Alg12: unsupported parameter set");
+    let (mut s_hat, mut s_cache) = ([[Z256(0); 256]; K_MAX], [[Z256(0); 128]; K_MAX]);
+    k_pke_key_gen_rt(p.k, p.eta1, d, ek_pke, dk_pke, &mut s_hat[..p.k], &mut s_cache[..p.k])
+}
+
+
+/// Algorithm 13 `K-PKE.Encrypt(ekPKE , m, r)` on page 27 for the parameter set `p`
+/// (`small-code`).
+#[cfg(feature = "small-code")]
+pub(crate) fn encrypt(
+    p: Params, ek: &[u8], m: &[u8], randomness: &[u8; 32], ct: &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!(p.k <= K_MAX, "Alg13: unsupported parameter set");
+    let (mut r_hat, mut r_cache) = ([[Z256(0); 256]; K_MAX], [[Z256(0); 128]; K_MAX]);
+    k_pke_encrypt_rt(p, ek, m, randomness, ct, &mut r_hat[..p.k], &mut r_cache[..p.k])
+}
+
+
+/// Algorithm 14 `K-PKE.Decrypt(dkPKE, c)` on page 28 for the parameter set `p` (`small-code`).
+#[cfg(feature = "small-code")]
+pub(crate) fn decrypt(p: Params, dk: &[u8], ct: &[u8]) -> Result<[u8; 32], &'static str> {
+    k_pke_decrypt_rt(p.k, p.du, p.dv, dk, ct)
+}
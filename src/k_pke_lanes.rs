@@ -0,0 +1,175 @@
+use crate::backend::{byte_decode, byte_encode, compress, sample_ntt, sample_poly_cbd};
+use crate::helpers::{decompress, ensure, vec_add};
+use crate::k_pke::Params;
+use crate::lanes::{self, get_lane, set_lane, LaneAcc, LaneCache, LanePoly, LANES, PRF_MAX_LEN};
+use crate::types::Z256;
+
+// The batch functions in `ml_kem.rs` route each full group of `LANES` items through the functions
+// below, which run K-PKE for every item side by side: the G, H, J, PRF and XOF calls of all lanes
+// share one lane sponge, and the NTTs and base multiplications one `LanePoly` kernel (see
+// `lanes.rs`). Sampling, compression and byte encoding stay per item. As in `k_pke_rt.rs`, the
+// parameters are runtime values and each entry of `A_hat` is sampled and accumulated as it is
+// needed, so the outputs are bit-identical to those of the single-item functions.
+
+/// Largest `k`, that of ML-KEM-1024, sizing the `r_hat` scratch.
+const K_MAX: usize = 4;
+
+const LANE_ZERO: LanePoly = [[0u16; 2 * LANES]; 128];
+const ACC_ZERO: LaneAcc = [[0u32; 2 * LANES]; 128];
+
+
+/// Algorithm 13 `K-PKE.Encrypt(ekPKE , m, r)` on page 27, for `LANES` items at once.
+/// Encrypts each `ms[l]` under `eks[l]` with the randomness `rs[l]`, writing to `cts[l]`.
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn k_pke_encrypt_lanes(
+    p: Params, eks: [&[u8]; LANES], ms: &[[u8; 32]; LANES], rs: &[[u8; 32]; LANES],
+    mut cts: [&mut [u8]; LANES],
+) -> Result<(), &'static str> {
+    let Params { k, eta1, eta2, du, dv } = p;
+    ensure!(k <= K_MAX, "Alg13: unsupported parameter set");
+    for (ek, ct) in eks.iter().zip(&cts) {
+        ensure!(ek.len() == 384 * k + 32, "Alg13: ek len not 384 * K + 32");
+        ensure!(
+            ct.len() == 32 * (du as usize * k + dv as usize),
+            "Alg13: ct len not 32 * (DU * K + DV)"
+        );
+    }
+    let mut prf_out = [[0u8; PRF_MAX_LEN]; LANES];
+    let (prf1_len, prf2_len) = (64 * eta1 as usize, 64 * eta2 as usize);
+
+    // 1: N ← 0
+    let mut n = 0;
+
+    // 3: ρ ← ekPKE [384k : 384k + 32]           ▷ extract 32-byte seed from ekPKE
+    let rhos = eks.map(|ek| <&[u8; 32]>::try_from(&ek[384 * k..]).unwrap());
+    let seeds: [&[u8; 32]; LANES] = core::array::from_fn(|l| &rs[l]);
+
+    // 9-12: r[i] ← SamplePolyCBDη1(PRFη1(r, N)), then 18: r̂ ← NTT(r) one coordinate at a time
+    let (mut r_hat, mut r_cache) = ([LANE_ZERO; K_MAX], [[[0u16; LANES]; 128]; K_MAX]);
+    for (r_hat_i, r_cache_i) in r_hat.iter_mut().zip(r_cache.iter_mut()).take(k) {
+        lanes::prf_into(seeds, n, &mut prf_out, prf1_len);
+        for (l, prf_out) in prf_out.iter().enumerate() {
+            set_lane(r_hat_i, l, &sample_poly_cbd(eta1, &prf_out[..prf1_len])?);
+        }
+        lanes::ntt(r_hat_i);
+        *r_cache_i = lanes::mul_cache(r_hat_i);
+        n += 1;
+    }
+
+    // 19: u[i] ← NTT−1(∑_j Â[j, i] ◦ r̂[j]) + e1[i]    with Â[j, i] ← SampleNTT(XOF(ρ, j, i))
+    // 22: c1 ← ByteEncode_{du}(Compress_{du}(u))
+    let step = 32 * du as usize;
+    for i in 0..k {
+        let mut acc = ACC_ZERO;
+        let mut a_hat_ji = LANE_ZERO;
+        for j in 0..k {
+            let readers = lanes::xof(rhos, u8::try_from(i).unwrap(), u8::try_from(j).unwrap());
+            for (l, reader) in readers.into_iter().enumerate() {
+                set_lane(&mut a_hat_ji, l, &sample_ntt(reader));
+            }
+            lanes::multiply_ntts_acc(&mut acc, &a_hat_ji, &r_hat[j], &r_cache[j]);
+        }
+        let mut u_i = lanes::reduce_acc(&acc);
+        lanes::ntt_inv(&mut u_i);
+
+        // 14: e1[i] ← SamplePolyCBDη2(PRFη2(r, N))
+        lanes::prf_into(seeds, n, &mut prf_out, prf2_len);
+        n += 1;
+        for (l, ct) in cts.iter_mut().enumerate() {
+            let e1_i = sample_poly_cbd(eta2, &prf_out[l][..prf2_len])?;
+            let mut u_i = vec_add(&[get_lane(&u_i, l)], &[e1_i])[0];
+            compress(du, &mut u_i);
+            byte_encode(du, &u_i, &mut ct[i * step..(i + 1) * step])?;
+        }
+    }
+
+    // 17: e2 ← SamplePolyCBDη(PRFη2(r, N))     ▷ sample e2 ∈ Z^{256}_q from CBD
+    lanes::prf_into(seeds, n, &mut prf_out, prf2_len);
+
+    // 2 & 21: v ← NTT−1 (t̂⊺ ◦ r̂) + e2 + µ   with t̂[j] ← ByteDecode12(ekPKE[384j : 384(j+1)])
+    let mut acc = ACC_ZERO;
+    let (mut t_hat_j, mut t_hat_jl) = (LANE_ZERO, [Z256(0); 256]);
+    for j in 0..k {
+        for (l, ek) in eks.iter().enumerate() {
+            byte_decode(12, &ek[384 * j..384 * (j + 1)], &mut t_hat_jl)?;
+            set_lane(&mut t_hat_j, l, &t_hat_jl);
+        }
+        lanes::multiply_ntts_acc(&mut acc, &t_hat_j, &r_hat[j], &r_cache[j]);
+    }
+    let mut v = lanes::reduce_acc(&acc);
+    lanes::ntt_inv(&mut v);
+
+    for (l, ct) in cts.iter_mut().enumerate() {
+        let e2 = sample_poly_cbd(eta2, &prf_out[l][..prf2_len])?;
+
+        // 20: µ ← Decompress1(ByteDecode1(m)))
+        let mut mu = [Z256(0); 256];
+        byte_decode(1, &ms[l], &mut mu)?;
+        decompress(1, &mut mu);
+        let mut v = vec_add(&vec_add(&[get_lane(&v, l)], &[e2]), &[mu])[0];
+
+        // 23: c2 ← ByteEncode_{dv}(Compress_{dv}(v))
+        compress(dv, &mut v);
+        byte_encode(dv, &v, &mut ct[k * step..])?;
+    }
+
+    // 24: return c ← (c1 ∥ c2 )
+    Ok(())
+}
+
+
+/// Algorithm 14 `K-PKE.Decrypt(dkPKE, c)` on page 28, for `LANES` items at once.
+/// Decrypts each `cts[l]` with `dks[l]`.
+#[allow(clippy::similar_names)]
+pub(crate) fn k_pke_decrypt_lanes(
+    p: Params, dks: [&[u8]; LANES], cts: [&[u8]; LANES],
+) -> Result<[[u8; 32]; LANES], &'static str> {
+    let Params { k, du, dv, .. } = p;
+    let (du_usize, dv_usize) = (du as usize, dv as usize);
+    for (dk, ct) in dks.iter().zip(&cts) {
+        ensure!(dk.len() == 384 * k, "Alg14: dk len not 384 * K");
+        ensure!(ct.len() == 32 * (du_usize * k + dv_usize), "Alg14: 32 * (DU * K + DV)");
+    }
+
+    // 3, 5 & 6: ŝ⊺ ◦ NTT(u) accumulated over u[i] ← Decompress_{du}(ByteDecode_{du}(c1)) and
+    // ŝ[i] ← ByteDecode_{12}(dk_{PKE}), with 1: c1 ← c[0 : 32du k]
+    let mut acc = ACC_ZERO;
+    let (mut u_i, mut s_hat_i, mut poly) = (LANE_ZERO, LANE_ZERO, [Z256(0); 256]);
+    for i in 0..k {
+        for (l, (dk, ct)) in dks.iter().zip(&cts).enumerate() {
+            byte_decode(du, &ct[32 * du_usize * i..32 * du_usize * (i + 1)], &mut poly)?;
+            decompress(du, &mut poly);
+            set_lane(&mut u_i, l, &poly);
+            byte_decode(12, &dk[384 * i..384 * (i + 1)], &mut poly)?;
+            set_lane(&mut s_hat_i, l, &poly);
+        }
+        lanes::ntt(&mut u_i);
+        let s_cache_i: LaneCache = lanes::mul_cache(&s_hat_i);
+        lanes::multiply_ntts_acc(&mut acc, &u_i, &s_hat_i, &s_cache_i);
+    }
+    let mut yy = lanes::reduce_acc(&acc);
+    lanes::ntt_inv(&mut yy);
+
+    let mut ms = [[0u8; 32]; LANES];
+    for (l, (m, ct)) in ms.iter_mut().zip(&cts).enumerate() {
+        // 2: c2 ← c[32du k : 32(du*k + dv)]
+        // 4: v ← Decompress_{dv}(ByteDecode_{dv}(c_2))
+        let mut v = [Z256(0); 256];
+        byte_decode(dv, &ct[32 * du_usize * k..], &mut v)?;
+        decompress(dv, &mut v);
+
+        // 6: w ← v − NTT−1 (ŝ⊺ ◦ NTT(u))
+        let yy = get_lane(&yy, l);
+        let mut w = [Z256(0); 256];
+        for i in 0..256 {
+            w[i] = v[i].sub(yy[i]);
+        }
+
+        // 7: m ← ByteEncode1 (Compress1 (w))       ▷ decode plaintext m from polynomial v
+        compress(1, &mut w);
+        byte_encode(1, &w, m)?;
+    }
+
+    // 8: return m
+    Ok(ms)
+}
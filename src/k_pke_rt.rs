@@ -0,0 +1,215 @@
+use crate::backend::{
+    byte_decode, byte_encode, compress, multiply_ntts_acc, ntt, ntt_inv, reduce_acc, sample_ntt,
+    sample_poly_cbd,
+};
+use crate::helpers::{decompress, ensure, g, vec_add, SeededPrf, SeededXof};
+use crate::k_pke::Params;
+use crate::ntt::mul_cache;
+use crate::types::Z256;
+
+// The `small-stack` and `small-code` features route K-PKE through the functions below, which take
+// `k`, `η1`, `η2`, `du` and `dv` as runtime values rather than const generics, with the callers in
+// `k_pke.rs` providing the scratch buffers. They compute exactly the same outputs as the default
+// functions, but never materialize `A_hat` (k*k polynomials): each entry is sampled from the XOF
+// as it is needed and its product accumulated on the fly, and all other vectors are
+// (de)serialized one polynomial at a time where possible. The PRF counter `N` is still consumed in
+// specification order, so the outputs are bit-identical.
+
+/// Largest PRF output, `64·η` bytes for η ≤ 3.
+const PRF_MAX_LEN: usize = 64 * 3;
+
+
+/// Algorithm 12 `K-PKE.KeyGen()` on page 26, with runtime parameters.
+/// Generates an encryption key and a corresponding decryption key from the random seed `d`, using
+/// caller-provided `s_hat` and `s_cache` scratch of `k` entries each.
+///
+/// Output: encryption key `ekPKE ∈ B^{384*k+32}` <br>
+/// Output: decryption key `dkPKE ∈ B^{384*k}`
+#[allow(clippy::similar_names)]
+pub(crate) fn k_pke_key_gen_rt(
+    k: usize, eta1: u32, d: &[u8; 32], ek_pke: &mut [u8], dk_pke: &mut [u8],
+    s_hat: &mut [[Z256; 256]], s_cache: &mut [[Z256; 128]],
+) -> Result<(), &'static str> {
+    ensure!(ek_pke.len() == 384 * k + 32, "Alg12: ek_pke not 384 * K + 32");
+    ensure!(dk_pke.len() == 384 * k, "Alg12: dk_pke not 384 * K");
+    ensure!((s_hat.len() == k) & (s_cache.len() == k), "Alg12: scratch len not K");
+    let mut prf_out = [0u8; PRF_MAX_LEN];
+    let prf_len = 64 * eta1 as usize;
+
+    // 2: (ρ, σ) ← G(d)                 ▷ expand to two pseudorandom 32-byte seeds
+    let (rho, sigma) = g(&[d]);
+    // Absorb each seed once; XOF(ρ, ·, ·) and PRF(σ, ·) clone the absorbed state per call
+    let (rho_sponge, sigma_sponge) = (SeededXof::new(&rho), SeededPrf::new(&sigma));
+
+    // 3: N ← 0
+    let mut n = 0;
+
+    // 9-12: s[i] ← SamplePolyCBDη1(PRFη1(σ, N)), then 17: s_hat ← NTT(s) one coordinate at a time
+    for s_hat_i in s_hat.iter_mut() {
+        sigma_sponge.prf_into(n, &mut prf_out[..prf_len]);
+        *s_hat_i = ntt(&sample_poly_cbd(eta1, &prf_out[..prf_len])?);
+        n += 1;
+    }
+
+    // 21: dk_{PKE} ← ByteEncode12(ŝ)
+    for i in 0..k {
+        byte_encode(12, &s_hat[i], &mut dk_pke[i * 384..(i + 1) * 384])?;
+        s_cache[i] = mul_cache(&s_hat[i]);
+    }
+
+    // 13-16 & 18: e_hat[i] ← NTT(SamplePolyCBDη1(PRFη1(σ, N)))
+    // 19: t̂[i] ← ∑_j Â[i, j] ◦ ŝ[j] + ê[i]   with 6: Â[i, j] ← SampleNTT(XOF(ρ, i, j)) on demand
+    for i in 0..k {
+        sigma_sponge.prf_into(n, &mut prf_out[..prf_len]);
+        let e_hat_i = ntt(&sample_poly_cbd(eta1, &prf_out[..prf_len])?);
+        n += 1;
+        let mut acc = [0u32; 256];
+        for j in 0..k {
+            // See page 21 regarding transpose of i, j -> j, i in XOF()
+            let a_hat_ij =
+                sample_ntt(rho_sponge.xof(u8::try_from(j).unwrap(), u8::try_from(i).unwrap()));
+            multiply_ntts_acc(&mut acc, &a_hat_ij, &s_hat[j], &s_cache[j]);
+        }
+        let t_hat_i = vec_add(&[reduce_acc(&acc)], &[e_hat_i])[0];
+
+        // 20: ek_{PKE} ← ByteEncode12(t̂)∥ρ
+        byte_encode(12, &t_hat_i, &mut ek_pke[i * 384..(i + 1) * 384])?;
+    }
+    ek_pke[k * 384..].copy_from_slice(&rho);
+
+    // 22: return (ekPKE , dkPKE )
+    Ok(())
+}
+
+
+/// Algorithm 13 `K-PKE.Encrypt(ekPKE , m, r)` on page 27, with the runtime parameters `p`.
+/// Uses the encryption key to encrypt a plaintext message using the randomness r, with
+/// caller-provided `r_hat` and `r_cache` scratch of `k` entries each.
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn k_pke_encrypt_rt(
+    p: Params, ek: &[u8], m: &[u8], randomness: &[u8; 32], ct: &mut [u8],
+    r_hat: &mut [[Z256; 256]], r_cache: &mut [[Z256; 128]],
+) -> Result<(), &'static str> {
+    let Params { k, eta1, eta2, du, dv } = p;
+    ensure!(ek.len() == 384 * k + 32, "Alg13: ek len not 384 * K + 32");
+    ensure!(m.len() == 32, "Alg13: m len not 32");
+    ensure!((r_hat.len() == k) & (r_cache.len() == k), "Alg13: scratch len not K");
+    let mut prf_out = [0u8; PRF_MAX_LEN];
+    let (prf1_len, prf2_len) = (64 * eta1 as usize, 64 * eta2 as usize);
+
+    // 1: N ← 0
+    let mut n = 0;
+
+    // 3: ρ ← ekPKE [384k : 384k + 32]           ▷ extract 32-byte seed from ekPKE
+    let mut rho = [0u8; 32];
+    rho.copy_from_slice(&ek[384 * k..(384 * k + 32)]);
+    // Absorb each seed once; XOF(ρ, ·, ·) and PRF(r, ·) clone the absorbed state per call
+    let (rho_sponge, r_sponge) = (SeededXof::new(&rho), SeededPrf::new(randomness));
+
+    // 9-12: r[i] ← SamplePolyCBDη1(PRFη1(r, N)), then 18: r̂ ← NTT(r) one coordinate at a time
+    for i in 0..k {
+        r_sponge.prf_into(n, &mut prf_out[..prf1_len]);
+        r_hat[i] = ntt(&sample_poly_cbd(eta1, &prf_out[..prf1_len])?);
+        r_cache[i] = mul_cache(&r_hat[i]);
+        n += 1;
+    }
+
+    // 19: u[i] ← NTT−1(∑_j Â[j, i] ◦ r̂[j]) + e1[i]    with Â[j, i] ← SampleNTT(XOF(ρ, j, i))
+    // 22: c1 ← ByteEncode_{du}(Compress_{du}(u))
+    let step = 32 * du as usize;
+    for i in 0..k {
+        let mut acc = [0u32; 256];
+        for j in 0..k {
+            let a_hat_ji =
+                sample_ntt(rho_sponge.xof(u8::try_from(i).unwrap(), u8::try_from(j).unwrap()));
+            multiply_ntts_acc(&mut acc, &a_hat_ji, &r_hat[j], &r_cache[j]);
+        }
+        let mut u_i = reduce_acc(&acc);
+
+        // 14: e1[i] ← SamplePolyCBDη2(PRFη2(r, N))
+        r_sponge.prf_into(n, &mut prf_out[..prf2_len]);
+        let e1_i = sample_poly_cbd(eta2, &prf_out[..prf2_len])?;
+        n += 1;
+        u_i = vec_add(&[ntt_inv(&u_i)], &[e1_i])[0];
+        compress(du, &mut u_i);
+        byte_encode(du, &u_i, &mut ct[i * step..(i + 1) * step])?;
+    }
+
+    // 17: e2 ← SamplePolyCBDη(PRFη2(r, N))     ▷ sample e2 ∈ Z^{256}_q from CBD
+    r_sponge.prf_into(n, &mut prf_out[..prf2_len]);
+    let e2 = sample_poly_cbd(eta2, &prf_out[..prf2_len])?;
+
+    // 20: µ ← Decompress1(ByteDecode1(m)))
+    let mut mu = [Z256(0); 256];
+    byte_decode(1, m, &mut mu)?;
+    decompress(1, &mut mu);
+
+    // 2 & 21: v ← NTT−1 (t̂⊺ ◦ r̂) + e2 + µ   with t̂[j] ← ByteDecode12(ekPKE[384j : 384(j+1)])
+    let mut acc = [0u32; 256];
+    let mut t_hat_j = [Z256(0); 256];
+    for j in 0..k {
+        byte_decode(12, &ek[384 * j..384 * (j + 1)], &mut t_hat_j)?;
+        multiply_ntts_acc(&mut acc, &t_hat_j, &r_hat[j], &r_cache[j]);
+    }
+    let mut v = ntt_inv(&reduce_acc(&acc));
+    v = vec_add(&vec_add(&[v], &[e2]), &[mu])[0];
+
+    // 23: c2 ← ByteEncode_{dv}(Compress_{dv}(v))
+    compress(dv, &mut v);
+    byte_encode(dv, &v, &mut ct[k * step..(k * step + 32 * dv as usize)])?;
+
+    // 24: return c ← (c1 ∥ c2 )
+    Ok(())
+}
+
+
+/// Algorithm 14 `K-PKE.Decrypt(dkPKE, c)` on page 28, with runtime parameters.
+/// Uses the decryption key to decrypt a ciphertext.
+#[allow(clippy::similar_names)]
+pub(crate) fn k_pke_decrypt_rt(
+    k: usize, du: u32, dv: u32, dk: &[u8], ct: &[u8],
+) -> Result<[u8; 32], &'static str> {
+    let (du_usize, dv_usize) = (du as usize, dv as usize);
+    ensure!(dk.len() == 384 * k, "Alg14: dk len not 384 * K");
+    ensure!(ct.len() == 32 * (du_usize * k + dv_usize), "Alg14: 32 * (DU * K + DV)");
+
+    // 1: c1 ← c[0 : 32du k]
+    let c1 = &ct[0..32 * du_usize * k];
+
+    // 2: c2 ← c[32du k : 32(du*k + dv)]
+    let c2 = &ct[32 * du_usize * k..32 * (du_usize * k + dv_usize)];
+
+    // 3, 5 & 6: ŝ⊺ ◦ NTT(u) accumulated over u[i] ← Decompress_{du}(ByteDecode_{du}(c1)) and
+    // ŝ[i] ← ByteDecode_{12}(dk_{PKE})
+    let mut acc = [0u32; 256];
+    let mut u_i = [Z256(0); 256];
+    let mut s_hat_i = [Z256(0); 256];
+    for i in 0..k {
+        byte_decode(du, &c1[32 * du_usize * i..32 * du_usize * (i + 1)], &mut u_i)?;
+        decompress(du, &mut u_i);
+        byte_decode(12, &dk[384 * i..384 * (i + 1)], &mut s_hat_i)?;
+        let ntt_u_i = ntt(&u_i);
+        multiply_ntts_acc(&mut acc, &ntt_u_i, &s_hat_i, &mul_cache(&s_hat_i));
+    }
+    let st_ntt_u = reduce_acc(&acc);
+
+    // 4: v ← Decompress_{dv}(ByteDecode_{dv}(c_2))
+    let mut v = [Z256(0); 256];
+    byte_decode(dv, c2, &mut v)?;
+    decompress(dv, &mut v);
+
+    // 6: w ← v − NTT−1 (ŝ⊺ ◦ NTT(u))
+    let yy = ntt_inv(&st_ntt_u);
+    let mut w = [Z256(0); 256];
+    for i in 0..256 {
+        w[i] = v[i].sub(yy[i]);
+    }
+
+    // 7: m ← ByteEncode1 (Compress1 (w))       ▷ decode plaintext m from polynomial v
+    compress(1, &mut w);
+    let mut m = [0u8; 32];
+    byte_encode(1, &w, &mut m)?;
+
+    // 8: return m
+    Ok(m)
+}
@@ -0,0 +1,606 @@
+use sha3::digest::XofReader;
+use zeroize::Zeroize;
+
+use crate::helpers::count;
+use crate::ntt::{gen_zeta_table, ZETA_TABLE};
+use crate::types::Z256;
+use crate::Q;
+
+// Lane-batched kernels behind `encaps_batch()` and `decaps_batch()`, which run `LANES` independent
+// items through K-PKE side by side. A `LanePoly` holds one polynomial per lane, interleaved so that
+// chunk `i` carries coefficients `2i` and `2i + 1` of every lane: each NTT butterfly and base
+// multiplication then applies the same zeta to `2 · LANES` adjacent values, which the compiler
+// vectorizes on any target. Products by a known zeta use Shoup multiplication with a precomputed
+// `⌊ζ · 2^16 / q⌋`, as in the SIMD backends. The sponges absorb equal-length inputs for every lane
+// in lockstep, so their permutations can run interleaved where the target has 256-bit vectors.
+// Every function produces exactly the same output per lane as its single-item counterpart, and
+// records the same counts.
+
+/// Number of items processed side by side.
+pub(crate) const LANES: usize = 4;
+
+/// One polynomial per lane; chunk `i` holds coefficient `2i` of lanes `0..LANES` followed by
+/// coefficient `2i + 1` of lanes `0..LANES`.
+pub(crate) type LanePoly = [[u16; 2 * LANES]; 128];
+
+/// The `b1 · γ` "mulcache" of a `LanePoly`, one entry per lane for each coefficient pair.
+pub(crate) type LaneCache = [[u16; LANES]; 128];
+
+/// A wide (unreduced) accumulator in the layout of `LanePoly`.
+pub(crate) type LaneAcc = [[u32; 2 * LANES]; 128];
+
+/// Largest PRF output, `64·η` bytes for η ≤ 3.
+pub(crate) const PRF_MAX_LEN: usize = 64 * 3;
+
+#[allow(clippy::cast_possible_truncation)]
+const Q16: u16 = Q as u16;
+
+/// The Shoup companions `⌊ζ · 2^16 / q⌋` of each entry in `ZETA_TABLE`.
+static ZETA_SHOUP: [u16; 256] = gen_shoup_table();
+
+/// The NTT⁻¹ scaling factor `128^{-1} mod q` and its Shoup companion.
+const F: u16 = 3303;
+#[allow(clippy::cast_possible_truncation)]
+const F_SHOUP: u16 = (((F as u32) << 16) / Q) as u16;
+
+
+#[allow(clippy::cast_possible_truncation)]
+const fn gen_shoup_table() -> [u16; 256] {
+    let zetas = gen_zeta_table();
+    let mut result = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        result[i] = (((zetas[i] as u32) << 16) / Q) as u16;
+        i += 1;
+    }
+    result
+}
+
+
+/// Writes `f` into lane `lane` of `poly`.
+pub(crate) fn set_lane(poly: &mut LanePoly, lane: usize, f: &[Z256; 256]) {
+    for (chunk, pair) in poly.iter_mut().zip(f.chunks_exact(2)) {
+        chunk[lane] = pair[0].get_u16();
+        chunk[LANES + lane] = pair[1].get_u16();
+    }
+}
+
+
+/// Reads lane `lane` of `poly`.
+#[must_use]
+pub(crate) fn get_lane(poly: &LanePoly, lane: usize) -> [Z256; 256] {
+    let mut f = [Z256(0); 256];
+    for (pair, chunk) in f.chunks_exact_mut(2).zip(poly) {
+        pair[0] = Z256(chunk[lane]);
+        pair[1] = Z256(chunk[LANES + lane]);
+    }
+    f
+}
+
+
+/// Maps a value in `[0, 2q)` to `[0, q)`.
+#[inline(always)]
+#[allow(
+    clippy::inline_always,
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss
+)]
+fn reduce_once(x: u16) -> u16 {
+    let t = x.wrapping_sub(Q16) as i16;
+    (t + ((t >> 15) & Q16 as i16)) as u16
+}
+
+
+/// `b · ζ mod q` given `ζ` and its Shoup companion; `b` must be below `q`.
+#[inline(always)]
+#[allow(clippy::inline_always)]
+fn mul_shoup(b: u16, zeta: u16, zeta_shoup: u16) -> u16 {
+    let quot = ((u32::from(b) * u32::from(zeta_shoup)) >> 16) as u16;
+    reduce_once(b.wrapping_mul(zeta).wrapping_sub(quot.wrapping_mul(Q16)))
+}
+
+
+/// Algorithm 8 `NTT(f)` on page 22, in place on every lane.
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn ntt(f: &mut LanePoly) {
+    for _lane in 0..LANES {
+        count!(ntt);
+    }
+    count!(reductions, LANES * 7 * 128);
+    let mut k = 1;
+    for len in [128, 64, 32, 16, 8, 4, 2] {
+        let n = len / 2; // Chunks per half of each butterfly group
+        for start in (0..128).step_by(2 * n) {
+            let (zeta, zeta_shoup) = (ZETA_TABLE[k << 1], ZETA_SHOUP[k << 1]);
+            k += 1;
+            for c in start..(start + n) {
+                let (a, b) = (f[c], f[c + n]);
+                let t: [u16; 2 * LANES] =
+                    core::array::from_fn(|i| mul_shoup(b[i], zeta, zeta_shoup));
+                f[c + n] = core::array::from_fn(|i| reduce_once(a[i] + Q16 - t[i]));
+                f[c] = core::array::from_fn(|i| reduce_once(a[i] + t[i]));
+            }
+        }
+    }
+}
+
+
+/// Algorithm 9 `NTT−1(f_hat)` on page 23, in place on every lane.
+#[allow(clippy::many_single_char_names)]
+pub(crate) fn ntt_inv(f: &mut LanePoly) {
+    for _lane in 0..LANES {
+        count!(ntt_inv);
+    }
+    count!(reductions, LANES * (7 * 128 + 256));
+    let mut k = 127;
+    for len in [2, 4, 8, 16, 32, 64, 128] {
+        let n = len / 2;
+        for start in (0..128).step_by(2 * n) {
+            let (zeta, zeta_shoup) = (ZETA_TABLE[k << 1], ZETA_SHOUP[k << 1]);
+            k -= 1;
+            for c in start..(start + n) {
+                let (t, b) = (f[c], f[c + n]);
+                f[c] = core::array::from_fn(|i| reduce_once(t[i] + b[i]));
+                f[c + n] = core::array::from_fn(|i| {
+                    mul_shoup(reduce_once(b[i] + Q16 - t[i]), zeta, zeta_shoup)
+                });
+            }
+        }
+    }
+    for chunk in f.iter_mut() {
+        *chunk = core::array::from_fn(|i| mul_shoup(chunk[i], F, F_SHOUP));
+    }
+}
+
+
+/// Precomputes the `b1 · γ` "mulcache" of every lane of `g_hat`.
+#[must_use]
+pub(crate) fn mul_cache(g_hat: &LanePoly) -> LaneCache {
+    count!(reductions, LANES * 128);
+    let mut g_cache = [[0u16; LANES]; 128];
+    for (i, (cache, chunk)) in g_cache.iter_mut().zip(g_hat).enumerate() {
+        let (gamma, gamma_shoup) = (ZETA_TABLE[i ^ 0x80], ZETA_SHOUP[i ^ 0x80]);
+        *cache = core::array::from_fn(|l| mul_shoup(chunk[LANES + l], gamma, gamma_shoup));
+    }
+    g_cache
+}
+
+
+/// Fused multiply-accumulate variant of Algorithm 10 `MultiplyNTTs(f, g)` on page 24, on every
+/// lane; see `ntt::multiply_ntts_acc()` for the accumulator bounds.
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut LaneAcc, f_hat: &LanePoly, g_hat: &LanePoly, g_cache: &LaneCache,
+) {
+    for (((acc, f), g), b1_gamma) in acc.iter_mut().zip(f_hat).zip(g_hat).zip(g_cache) {
+        for l in 0..LANES {
+            let (a0, a1) = (u32::from(f[l]), u32::from(f[LANES + l]));
+            let (b0, b1) = (u32::from(g[l]), u32::from(g[LANES + l]));
+            acc[l] += a0 * b0 + a1 * u32::from(b1_gamma[l]);
+            acc[LANES + l] += a0 * b1 + a1 * b0;
+        }
+    }
+}
+
+
+/// Reduces a wide accumulator from `multiply_ntts_acc()` once per coefficient.
+#[must_use]
+pub(crate) fn reduce_acc(acc: &LaneAcc) -> LanePoly {
+    let mut h_hat = [[0u16; 2 * LANES]; 128];
+    for (chunk, wide) in h_hat.iter_mut().zip(acc) {
+        *chunk = core::array::from_fn(|i| Z256::reduce_u32(wide[i]).get_u16());
+    }
+    h_hat
+}
+
+
+// Keccak-f[1600] across lanes. With 256-bit vectors the permutations run interleaved, one 64-bit
+// word per lane in each vector; elsewhere that layout is slower than permuting each lane in turn.
+
+/// Round constants of the ι step.
+#[cfg(any(test, target_feature = "avx2"))]
+const RC: [u64; 24] = [
+    0x0000_0000_0000_0001,
+    0x0000_0000_0000_8082,
+    0x8000_0000_0000_808a,
+    0x8000_0000_8000_8000,
+    0x0000_0000_0000_808b,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8009,
+    0x0000_0000_0000_008a,
+    0x0000_0000_0000_0088,
+    0x0000_0000_8000_8009,
+    0x0000_0000_8000_000a,
+    0x0000_0000_8000_808b,
+    0x8000_0000_0000_008b,
+    0x8000_0000_0000_8089,
+    0x8000_0000_0000_8003,
+    0x8000_0000_0000_8002,
+    0x8000_0000_0000_0080,
+    0x0000_0000_0000_800a,
+    0x8000_0000_8000_000a,
+    0x8000_0000_8000_8081,
+    0x8000_0000_0000_8080,
+    0x0000_0000_8000_0001,
+    0x8000_0000_8000_8008,
+];
+
+/// Rotation offsets of the ρ step, in the order the π step visits the words.
+#[cfg(any(test, target_feature = "avx2"))]
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+/// Word visiting order of the π step.
+#[cfg(any(test, target_feature = "avx2"))]
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+
+/// Keccak-f\[1600\] on every lane of an interleaved state, where word `w` of lane `l` is `a[w][l]`.
+#[cfg(any(test, target_feature = "avx2"))]
+fn f1600_interleaved(a: &mut [[u64; LANES]; 25]) {
+    let rotl = |x: [u64; LANES], r: u32| -> [u64; LANES] { x.map(|w| w.rotate_left(r)) };
+    for rc in RC {
+        // θ
+        let c: [[u64; LANES]; 5] = core::array::from_fn(|x| {
+            core::array::from_fn(|l| {
+                a[x][l] ^ a[x + 5][l] ^ a[x + 10][l] ^ a[x + 15][l] ^ a[x + 20][l]
+            })
+        });
+        for x in 0..5 {
+            let r = rotl(c[(x + 1) % 5], 1);
+            let d: [u64; LANES] = core::array::from_fn(|l| c[(x + 4) % 5][l] ^ r[l]);
+            for y in 0..5 {
+                for l in 0..LANES {
+                    a[5 * y + x][l] ^= d[l];
+                }
+            }
+        }
+        // ρ and π
+        let mut last = a[1];
+        for (&pi, &rho) in PI.iter().zip(&RHO) {
+            let t = a[pi];
+            a[pi] = rotl(last, rho);
+            last = t;
+        }
+        // χ
+        for y in 0..5 {
+            let row: [[u64; LANES]; 5] = core::array::from_fn(|x| a[5 * y + x]);
+            for x in 0..5 {
+                a[5 * y + x] = core::array::from_fn(|l| {
+                    row[x][l] ^ (!row[(x + 1) % 5][l] & row[(x + 2) % 5][l])
+                });
+            }
+        }
+        // ι
+        for word in &mut a[0] {
+            *word ^= rc;
+        }
+    }
+}
+
+
+/// Keccak-f\[1600\] on the state of every lane.
+fn permute(states: &mut [[u64; 25]; LANES]) {
+    #[cfg(target_feature = "avx2")]
+    {
+        let mut interleaved: [[u64; LANES]; 25] =
+            core::array::from_fn(|w| core::array::from_fn(|l| states[l][w]));
+        f1600_interleaved(&mut interleaved);
+        for (l, state) in states.iter_mut().enumerate() {
+            *state = core::array::from_fn(|w| interleaved[w][l]);
+        }
+        interleaved.zeroize();
+    }
+    #[cfg(not(target_feature = "avx2"))]
+    states.iter_mut().for_each(keccak::f1600);
+}
+
+
+/// One Keccak sponge per lane, with every lane absorbing and squeezing the same number of bytes.
+/// The states are wiped on drop, as G and PRF absorb secrets.
+struct LaneSponge {
+    states: [[u64; 25]; LANES],
+    rate: usize,
+}
+
+impl LaneSponge {
+    /// Absorbs the concatenation of the `P` parts of `inputs[l]` into lane `l` and pads with the
+    /// domain separation bits `domain` (`0x06` for SHA3, `0x1F` for SHAKE). Every lane's input must
+    /// be the same length.
+    fn absorb<const P: usize>(rate: usize, domain: u8, inputs: [[&[u8]; P]; LANES]) -> Self {
+        let mut sponge = Self { states: [[0u64; 25]; LANES], rate };
+        let len = inputs[0].iter().map(|part| part.len()).sum::<usize>();
+        debug_assert!(inputs
+            .iter()
+            .all(|parts| parts.iter().map(|p| p.len()).sum::<usize>() == len));
+        let mut block = [0u8; 168];
+        for start in (0..=len).step_by(rate) {
+            let end = len.min(start + rate);
+            for (state, parts) in sponge.states.iter_mut().zip(&inputs) {
+                block[..rate].fill(0);
+                let mut offset = 0;
+                for part in parts {
+                    let (lo, hi) = (start.max(offset), end.min(offset + part.len()));
+                    if lo < hi {
+                        block[lo - start..hi - start]
+                            .copy_from_slice(&part[lo - offset..hi - offset]);
+                    }
+                    offset += part.len();
+                }
+                if end - start < rate {
+                    block[end - start] ^= domain;
+                    block[rate - 1] ^= 0x80;
+                }
+                for (word, bytes) in state.iter_mut().zip(block[..rate].chunks_exact(8)) {
+                    *word ^= u64::from_le_bytes(bytes.try_into().unwrap());
+                }
+            }
+            permute(&mut sponge.states);
+        }
+        block.zeroize();
+        sponge
+    }
+
+    /// Squeezes the first `len` bytes of every lane into `outputs`.
+    fn squeeze<const N: usize>(&mut self, outputs: &mut [[u8; N]; LANES], len: usize) {
+        for start in (0..len).step_by(self.rate) {
+            if start > 0 {
+                permute(&mut self.states);
+            }
+            let end = len.min(start + self.rate);
+            for (state, out) in self.states.iter().zip(outputs.iter_mut()) {
+                for (bytes, word) in out[start..end].chunks_mut(8).zip(state) {
+                    bytes.copy_from_slice(&word.to_le_bytes()[..bytes.len()]);
+                }
+            }
+        }
+    }
+}
+
+impl Drop for LaneSponge {
+    fn drop(&mut self) { self.states.zeroize(); }
+}
+
+
+/// Function G on page 17 (4.4) for every lane.
+pub(crate) fn g(inputs: [[&[u8]; 2]; LANES]) -> [([u8; 32], [u8; 32]); LANES] {
+    #[cfg(feature = "counters")]
+    for parts in &inputs {
+        count!(sponge, 72, parts.iter().map(|b| b.len()).sum(), 64);
+    }
+    let mut digests = [[0u8; 64]; LANES];
+    LaneSponge::absorb(72, 0x06, inputs).squeeze(&mut digests, 64);
+    let result = digests.map(|digest| {
+        let (mut a, mut b) = ([0u8; 32], [0u8; 32]);
+        a.copy_from_slice(&digest[0..32]);
+        b.copy_from_slice(&digest[32..64]);
+        (a, b)
+    });
+    digests.zeroize();
+    result
+}
+
+
+/// Function H on page 17 (4.3) for every lane.
+pub(crate) fn h(inputs: [&[u8]; LANES]) -> [[u8; 32]; LANES] {
+    #[cfg(feature = "counters")]
+    for bytes in &inputs {
+        count!(sponge, 136, bytes.len(), 32);
+    }
+    let mut digests = [[0u8; 32]; LANES];
+    LaneSponge::absorb(136, 0x06, inputs.map(|bytes| [bytes])).squeeze(&mut digests, 32);
+    digests
+}
+
+
+/// Function J on page 17 (4.4) for every lane.
+pub(crate) fn j(inputs: [[&[u8]; 2]; LANES]) -> [[u8; 32]; LANES] {
+    #[cfg(feature = "counters")]
+    for parts in &inputs {
+        count!(sponge, 136, parts.iter().map(|b| b.len()).sum(), 32);
+    }
+    let mut digests = [[0u8; 32]; LANES];
+    LaneSponge::absorb(136, 0x1F, inputs).squeeze(&mut digests, 32);
+    digests
+}
+
+
+/// Function PRF on page 16 (4.1) for every lane, writing the first `len` = `64·η` bytes of each
+/// output.
+pub(crate) fn prf_into(
+    seeds: [&[u8; 32]; LANES], b: u8, outputs: &mut [[u8; PRF_MAX_LEN]; LANES], len: usize,
+) {
+    for _lane in 0..LANES {
+        count!(sponge, 136, 33, len);
+    }
+    let index = [b];
+    let inputs = seeds.map(|seed| [seed.as_slice(), &index]);
+    LaneSponge::absorb(136, 0x1F, inputs).squeeze(outputs, len);
+}
+
+
+/// `SampleNTT` always draws at least three SHAKE128 blocks (`3 · 168 = 504` bytes, as 256
+/// coefficients need at least 384), so the lane XOF squeezes that much across all lanes up front.
+const XOF_BLOCKS: usize = 3;
+
+/// The per-lane output of the lane XOF: the bytes squeezed up front, continued block by block
+/// from the lane's own state.
+pub(crate) struct LaneXofReader {
+    state: [u64; 25],
+    buffer: [u8; XOF_BLOCKS * 168],
+    available: usize,
+    pos: usize,
+}
+
+impl XofReader for LaneXofReader {
+    fn read(&mut self, out: &mut [u8]) {
+        for byte in out {
+            if self.pos == self.available {
+                keccak::f1600(&mut self.state);
+                for (bytes, word) in self.buffer[..168].chunks_exact_mut(8).zip(&self.state) {
+                    bytes.copy_from_slice(&word.to_le_bytes());
+                }
+                (self.available, self.pos) = (168, 0);
+            }
+            *byte = self.buffer[self.pos];
+            self.pos += 1;
+        }
+    }
+}
+
+
+/// Function XOF on page 16 (4.2) for every lane, each with its own seed `rhos[l]`.
+#[must_use]
+#[allow(clippy::let_and_return)]
+pub(crate) fn xof(rhos: [&[u8; 32]; LANES], i: u8, j: u8) -> [impl XofReader; LANES] {
+    let index = [i, j];
+    let mut sponge = LaneSponge::absorb(168, 0x1F, rhos.map(|rho| [rho.as_slice(), &index]));
+    let mut buffers = [[0u8; XOF_BLOCKS * 168]; LANES];
+    sponge.squeeze(&mut buffers, XOF_BLOCKS * 168);
+    core::array::from_fn(|l| {
+        count!(sponge, 168, 34, 0);
+        let reader = LaneXofReader {
+            state: sponge.states[l],
+            buffer: buffers[l],
+            available: XOF_BLOCKS * 168,
+            pos: 0,
+        };
+        #[cfg(feature = "counters")]
+        let reader = crate::counters::CountingReader::new(reader, 168);
+        reader
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use sha3::digest::XofReader;
+
+    use crate::helpers::{self, SeededPrf, SeededXof};
+    use crate::lanes::{self, get_lane, set_lane, LanePoly, LANES, PRF_MAX_LEN};
+    use crate::ntt;
+    use crate::types::Z256;
+
+    #[test]
+    fn test_interleaved_permutation() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+        let mut states = [[0u64; 25]; LANES];
+        for state in &mut states {
+            rng.fill(&mut state[..]);
+        }
+        let mut interleaved: [[u64; LANES]; 25] =
+            core::array::from_fn(|w| core::array::from_fn(|l| states[l][w]));
+        for _i in 0..3 {
+            states.iter_mut().for_each(keccak::f1600);
+            lanes::f1600_interleaved(&mut interleaved);
+            for (l, state) in states.iter().enumerate() {
+                assert!((0..25).all(|w| interleaved[w][l] == state[w]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lane_sponges_match_single() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+        for len in [0, 32, 71, 72, 136, 1184, 1600] {
+            let mut inputs = [[0u8; 1600]; LANES];
+            for input in &mut inputs {
+                rng.fill(&mut input[..]);
+            }
+            let inputs: [&[u8]; LANES] = core::array::from_fn(|l| &inputs[l][..len]);
+            let suffixes: [[u8; 3]; LANES] = core::array::from_fn(|l| [l as u8; 3]);
+            let (g, h, j) = (
+                lanes::g(core::array::from_fn(|l| [inputs[l], &suffixes[l]])),
+                lanes::h(inputs),
+                lanes::j(core::array::from_fn(|l| [&suffixes[l], inputs[l]])),
+            );
+            for l in 0..LANES {
+                assert_eq!(g[l], helpers::g(&[inputs[l], &suffixes[l]]));
+                assert_eq!(h[l], helpers::h(inputs[l]));
+                assert_eq!(j[l], helpers::j(&[&suffixes[l], inputs[l]]));
+            }
+        }
+
+        let seeds: [[u8; 32]; LANES] = core::array::from_fn(|_| rng.gen());
+        let [s0, s1, s2, s3] = &seeds;
+        for len in [128, 192] {
+            let mut outputs = [[0u8; PRF_MAX_LEN]; LANES];
+            lanes::prf_into([s0, s1, s2, s3], 5, &mut outputs, len);
+            let mut readers = lanes::xof([s0, s1, s2, s3], 2, 1);
+            for l in 0..LANES {
+                let mut expected = [0u8; PRF_MAX_LEN];
+                SeededPrf::new(&seeds[l]).prf_into(5, &mut expected[..len]);
+                assert_eq!(outputs[l][..len], expected[..len]);
+
+                // Read past the bytes squeezed up front, in pieces that straddle the blocks
+                let (mut expected, mut actual) = ([0u8; 840], [0u8; 840]);
+                SeededXof::new(&seeds[l]).xof(2, 1).read(&mut expected);
+                for piece in actual.chunks_mut(len / 2 - 1) {
+                    readers[l].read(piece);
+                }
+                assert_eq!(expected, actual);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_lane_arithmetic_matches_single() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+        for _i in 0..20 {
+            let polys: [[[Z256; 256]; LANES]; 3] = core::array::from_fn(|_| {
+                core::array::from_fn(|_| core::array::from_fn(|_| Z256(rng.gen_range(0..3329))))
+            });
+            let mut lane_polys = [[[0u16; 2 * LANES]; 128]; 3];
+            for (lane_poly, lane_inputs) in lane_polys.iter_mut().zip(&polys) {
+                for (l, f) in lane_inputs.iter().enumerate() {
+                    set_lane(lane_poly, l, f);
+                }
+            }
+
+            let mut f_hat: LanePoly = lane_polys[0];
+            lanes::ntt(&mut f_hat);
+            let mut f = f_hat;
+            lanes::ntt_inv(&mut f);
+            let mut acc = [[0u32; 2 * LANES]; 128];
+            lanes::multiply_ntts_acc(
+                &mut acc,
+                &lane_polys[1],
+                &lane_polys[2],
+                &lanes::mul_cache(&lane_polys[2]),
+            );
+            lanes::multiply_ntts_acc(
+                &mut acc,
+                &f_hat,
+                &lane_polys[0],
+                &lanes::mul_cache(&lane_polys[0]),
+            );
+            let product = lanes::reduce_acc(&acc);
+
+            for l in 0..LANES {
+                let [a, b, c] = [polys[0][l], polys[1][l], polys[2][l]];
+                let a_hat = ntt::ntt(&a);
+                assert!(get_lane(&f_hat, l)
+                    .iter()
+                    .zip(&a_hat)
+                    .all(|(x, y)| x.0 == y.0));
+                assert!(get_lane(&f, l)
+                    .iter()
+                    .zip(&ntt::ntt_inv(&a_hat))
+                    .all(|(x, y)| x.0 == y.0));
+                let mut expected = [0u32; 256];
+                ntt::multiply_ntts_acc(&mut expected, &b, &c, &ntt::mul_cache(&c));
+                ntt::multiply_ntts_acc(&mut expected, &a_hat, &a, &ntt::mul_cache(&a));
+                let expected = ntt::reduce_acc(&expected);
+                assert!(get_lane(&product, l)
+                    .iter()
+                    .zip(&expected)
+                    .all(|(x, y)| x.0 == y.0));
+            }
+        }
+    }
+}
@@ -4,6 +4,7 @@
 #![deny(missing_docs)]
 #![allow(clippy::cast_lossless)] // TODO
 #![allow(clippy::cast_possible_truncation)] // TODO
+#![cfg_attr(feature = "portable-simd", feature(portable_simd))]
 
 #![doc = include_str!("../README.md")]
 
@@ -15,6 +16,15 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::traits::SerDes;
 
+// The parallel APIs collect their results into vectors, and DER and JSON documents are heap
+// allocated
+#[cfg(any(feature = "rayon", feature = "spki", feature = "jose"))]
+extern crate alloc;
+
+// The key pool runs a worker thread
+#[cfg(feature = "std")]
+extern crate std;
+
 // Functionality map per FIPS 203 draft
 //
 // Algorithm 2 BitsToBytes(b) on page 17                    --> optimized out (byte_fns.rs)
@@ -36,22 +46,80 @@ use crate::traits::SerDes;
 // PRF and XOF on page 16                                   --> helpers.rs
 // Three hash functions: G, H, J on page 17                 --> helpers.rs
 // Compress and Decompress on page 18                       --> helpers.rs
+// Vectorized kernels for the above (Algorithms 4-10)        --> backend.rs selects (e.g. neon.rs)
 //
 // The three parameter sets are modules in this file with injected macro code
 // that connects them into the functionality in ml_kem.rs. Some of the strange
 // coding style is driven by clippy pedantic.
 
+mod backend;
 mod byte_fns;
+#[cfg(all(feature = "armv7em-asm", not(feature = "portable-simd"), target_arch = "arm"))]
+mod cm4;
 mod helpers;
+#[cfg(feature = "bench-internals")]
+#[doc(hidden)]
+pub mod internals;
 mod k_pke;
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+mod k_pke_lanes;
+#[cfg(any(feature = "small-stack", feature = "small-code"))]
+mod k_pke_rt;
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+mod lanes;
 mod ml_kem;
+#[cfg(all(not(feature = "portable-simd"), target_arch = "aarch64", target_feature = "neon"))]
+mod neon;
 mod ntt;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "spki")]
+mod pkix;
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "portable-simd")]
+mod portable_simd;
+#[cfg(all(not(feature = "portable-simd"), target_arch = "riscv64", target_feature = "v"))]
+mod rvv;
 mod sampling;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(all(not(feature = "portable-simd"), target_arch = "wasm32", target_feature = "simd128"))]
+mod simd128;
 mod types;
 
 /// All functionality is covered by traits, such that consumers can utilize trait objects as desired.
 pub mod traits;
 
+/// Counts of NTTs, Keccak permutations and modular reductions, for attributing optimizations.
+#[cfg(feature = "counters")]
+pub mod counters;
+
+#[cfg(feature = "jose")]
+pub mod jose;
+
+#[cfg(feature = "cose")]
+pub mod cose;
+
+#[cfg(feature = "compat")]
+pub mod compat;
+
+#[cfg(feature = "std")]
+pub use crate::pool::KeyPool;
+
+#[cfg(feature = "pkcs8")]
+pub use crate::pkix::PrivateKeyForm;
+
+#[cfg(feature = "pkcs8-encryption")]
+pub use crate::pkix::{decrypt_private_key, encrypt_private_key, PasswordCipher, PasswordKdf};
+
+/// Re-exported for its `EncodePrivateKey` and `DecodePrivateKey` traits.
+#[cfg(feature = "pkcs8")]
+pub use pkcs8;
+/// Re-exported for its `EncodePublicKey` and `DecodePublicKey` traits.
+#[cfg(feature = "spki")]
+pub use spki;
+
 // Relevant to all parameter sets
 const _N: u32 = 256;
 const Q: u32 = 3329;
@@ -72,6 +140,13 @@ impl SerDes for SharedSecretKey {
     }
 }
 
+/// A zero-filled placeholder, e.g. for the output buffers of the batch functions.
+impl Default for SharedSecretKey {
+    fn default() -> Self { SharedSecretKey([0u8; SSK_LEN]) }
+}
+
+#[cfg(feature = "serde")]
+crate::serialization::impl_serde!(SharedSecretKey);
 
 // Conservative (constant-time) paranoia...
 impl PartialEq for SharedSecretKey {
@@ -85,15 +160,92 @@ impl PartialEq for SharedSecretKey {
 }
 
 
+/// The identity of an encapsulation key: `H(ek)`, the SHA3-256 digest that ML-KEM already binds
+/// into every shared secret (see `EncapsKey::fingerprint()`). Displays as colon-separated hex, or
+/// as unseparated hex with `{:x}`; `short_id()` gives an abbreviated form for showing to users.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Fingerprint([u8; 32]);
+
+impl Fingerprint {
+    /// Returns the 32 digest bytes.
+    #[must_use]
+    pub fn as_bytes(&self) -> &[u8; 32] { &self.0 }
+
+    /// Returns an abbreviated rendering of the first 8 bytes as four dash-separated groups of four
+    /// hex digits, e.g. `3f2a-91c0-7b44-e5d1`. This is for recognizing keys, not for verifying them.
+    #[must_use]
+    pub fn short_id(&self) -> ShortId {
+        let mut id = [0u8; 8];
+        id.copy_from_slice(&self.0[..8]);
+        ShortId(id)
+    }
+}
+
+impl AsRef<[u8]> for Fingerprint {
+    fn as_ref(&self) -> &[u8] { &self.0 }
+}
+
+/// Colon-separated lowercase hex, e.g. `3f:2a:91:...`.
+impl core::fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(":")?;
+            }
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Unseparated lowercase hex.
+impl core::fmt::LowerHex for Fingerprint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+    }
+}
+
+impl core::fmt::Debug for Fingerprint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Fingerprint({self:x})")
+    }
+}
+
+/// The abbreviated form of a `Fingerprint`, from `Fingerprint::short_id()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShortId([u8; 8]);
+
+/// Four dash-separated groups of four lowercase hex digits.
+impl core::fmt::Display for ShortId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, pair) in self.0.chunks_exact(2).enumerate() {
+            if i > 0 {
+                f.write_str("-")?;
+            }
+            write!(f, "{:02x}{:02x}", pair[0], pair[1])?;
+        }
+        Ok(())
+    }
+}
+
+
 // This common functionality is injected into each parameter set module
 macro_rules! functionality {
     () => {
-        const ETA1_64: usize = ETA1 * 64;  // Currently, Rust does not allow expressions involving constants...
-        const ETA2_64: usize = ETA2 * 64;  // ...so these are handled manually.
-        const J_LEN: usize = 32 + 32 * (DU * K + DV);
+        const PARAMS: Params = Params {
+            k: K,
+            eta1: ETA1 as u32,
+            eta2: ETA2 as u32,
+            du: DU as u32,
+            dv: DV as u32,
+        };
 
-        use crate::byte_fns::byte_decode;
-        use crate::ml_kem::{ml_kem_decaps, ml_kem_encaps, ml_kem_key_gen};
+        use crate::backend::byte_decode;
+        use crate::k_pke::Params;
+        use crate::ml_kem::{
+            ml_kem_decaps, ml_kem_decaps_batch, ml_kem_encaps, ml_kem_encaps_batch, ml_kem_key_gen,
+            ml_kem_key_gen_from_seed,
+        };
         use crate::traits::{Decaps, Encaps, KeyGen, SerDes};
         use crate::types::Z256;
         use crate::SharedSecretKey;
@@ -101,7 +253,8 @@ macro_rules! functionality {
         use zeroize::{Zeroize, ZeroizeOnDrop};
 
         /// Correctly sized encapsulation key specific to the target security parameter set.
-        #[derive(Clone, Zeroize, ZeroizeOnDrop)]
+        /// Equality and hashing are over the encoding, which is public, so neither is constant time.
+        #[derive(Clone, PartialEq, Eq, Hash, Zeroize, ZeroizeOnDrop)]
         pub struct EncapsKey([u8; EK_LEN]);
 
         /// Correctly sized decapsulation key specific to the target security parameter set.
@@ -112,6 +265,13 @@ macro_rules! functionality {
         #[derive(Clone, Zeroize, ZeroizeOnDrop)]
         pub struct CipherText([u8; CT_LEN]);
 
+        #[cfg(feature = "serde")]
+        crate::serialization::impl_serde!(EncapsKey);
+        #[cfg(feature = "serde")]
+        crate::serialization::impl_serde!(DecapsKey);
+        #[cfg(feature = "serde")]
+        crate::serialization::impl_serde!(CipherText);
+
         /// Per FIPS 203, the key generation algorithm `ML-KEM.KeyGen` for ML-KEM (Algorithm 15)
         /// accepts no input, utilizes randomness, and produces an encapsulation key and a
         /// decapsulation key. While the encapsulation key can be made public, the decapsulation key
@@ -128,11 +288,142 @@ macro_rules! functionality {
                 rng: &mut impl CryptoRngCore,
             ) -> Result<(EncapsKey, DecapsKey), &'static str> {
                 let (mut ek, mut dk) = ([0u8; EK_LEN], [0u8; DK_LEN]);
-                ml_kem_key_gen::<K, ETA1, ETA1_64>(rng, &mut ek, &mut dk)?;
+                ml_kem_key_gen(PARAMS, rng, &mut ek, &mut dk)?;
                 Ok((EncapsKey(ek), DecapsKey(dk)))
             }
         }
 
+        impl KG {
+            /// Deterministically derives a key pair from the 64-byte seed `d ∥ z`, the seed form of
+            /// a PKCS#8 private key. `try_keygen_with_rng_vt()` is equivalent to drawing `z` and
+            /// then `d` from its random number generator. This function makes no (constant) timing
+            /// assurances.
+            /// # Errors
+            /// Propagates internal errors.
+            pub fn try_keygen_from_seed_vt(
+                seed: &[u8; 64],
+            ) -> Result<(EncapsKey, DecapsKey), &'static str> {
+                let (mut ek, mut dk) = ([0u8; EK_LEN], [0u8; DK_LEN]);
+                let (mut d, mut z) = ([0u8; 32], [0u8; 32]);
+                d.copy_from_slice(&seed[..32]);
+                z.copy_from_slice(&seed[32..]);
+                let result = ml_kem_key_gen_from_seed(PARAMS, &d, &z, &mut ek, &mut dk);
+                d.zeroize();
+                z.zeroize();
+                result?;
+                Ok((EncapsKey(ek), DecapsKey(dk)))
+            }
+        }
+
+        #[cfg(feature = "pkcs8")]
+        impl KG {
+            /// Encodes the private key derived from `seed` (see `try_keygen_from_seed_vt()`) as a
+            /// PKCS#8 DER document, with the `ML-KEM-PrivateKey` CHOICE alternative given by `form`
+            /// (available with the `pkcs8` feature).
+            /// # Errors
+            /// Propagates key derivation and encoding errors.
+            pub fn seed_to_pkcs8_der(
+                seed: &[u8; 64], form: crate::PrivateKeyForm,
+            ) -> pkcs8::Result<pkcs8::SecretDocument> {
+                use crate::PrivateKeyForm;
+                let dk = match form {
+                    PrivateKeyForm::Seed => None,
+                    PrivateKeyForm::ExpandedKey | PrivateKeyForm::Both => Some(
+                        KG::try_keygen_from_seed_vt(seed)
+                            .map_err(|_| pkcs8::Error::KeyMalformed)?
+                            .1,
+                    ),
+                };
+                let seed = (form != PrivateKeyForm::ExpandedKey).then_some(seed);
+                crate::pkix::encode_private_key(OID, seed, dk.as_ref().map(|dk| &dk.0[..]))
+            }
+
+            /// Encodes the private key derived from `seed` as a PKCS#8 PEM document (`PRIVATE KEY`),
+            /// as `seed_to_pkcs8_der()` (available with the `pkcs8` feature).
+            /// # Errors
+            /// Propagates key derivation and encoding errors.
+            pub fn seed_to_pkcs8_pem(
+                seed: &[u8; 64], form: crate::PrivateKeyForm, line_ending: pkcs8::LineEnding,
+            ) -> pkcs8::Result<zeroize::Zeroizing<alloc::string::String>> {
+                Ok(Self::seed_to_pkcs8_der(seed, form)?.to_pem("PRIVATE KEY", line_ending)?)
+            }
+        }
+
+        #[cfg(feature = "jose")]
+        impl KG {
+            /// Encodes the key pair derived from `seed` (see `try_keygen_from_seed_vt()`) as a
+            /// private AKP JWK, with the seed as `priv` (available with the `jose` feature).
+            /// # Errors
+            /// Propagates key derivation errors.
+            pub fn seed_to_jwk(
+                seed: &[u8; 64],
+            ) -> Result<zeroize::Zeroizing<alloc::string::String>, &'static str> {
+                let (ek, _dk) = KG::try_keygen_from_seed_vt(seed)?;
+                Ok(zeroize::Zeroizing::new(crate::jose::encode_jwk(JOSE_ALG, &ek.0, Some(seed))))
+            }
+
+            /// Decodes a private AKP JWK and derives its key pair from the `priv` seed (available
+            /// with the `jose` feature).
+            /// # Errors
+            /// Returns an error when the JWK is malformed, is for another parameter set, has no
+            /// `priv` member or has a `pub` member that does not match the seed.
+            pub fn try_keygen_from_jwk(jwk: &str) -> Result<(EncapsKey, DecapsKey), &'static str> {
+                let (ek, seed) = crate::jose::decode_jwk(JOSE_ALG, jwk, EK_LEN)?;
+                let seed = seed.ok_or("JWK: missing priv")?;
+                let (ek_seed, dk) = KG::try_keygen_from_seed_vt(&seed)?;
+                crate::helpers::ensure!(ek == ek_seed.0, "JWK: pub does not match priv");
+                Ok((ek_seed, dk))
+            }
+        }
+
+        #[cfg(feature = "cose")]
+        impl KG {
+            /// Encodes the key pair derived from `seed` (see `try_keygen_from_seed_vt()`) as a
+            /// private AKP `COSE_Key` into `out`, with the seed as `priv`, returning the length
+            /// (available with the `cose` feature). `out` should have room for the encapsulation
+            /// key and seed plus 16 bytes.
+            /// # Errors
+            /// Returns an error when `out` is too small, and propagates key derivation errors.
+            pub fn seed_to_cose_key(seed: &[u8; 64], out: &mut [u8]) -> Result<usize, &'static str> {
+                let (ek, _dk) = KG::try_keygen_from_seed_vt(seed)?;
+                crate::cose::encode_key(COSE_ALG, &ek.0, Some(seed), out)
+            }
+
+            /// Decodes a private AKP `COSE_Key` and derives its key pair from the `priv` seed
+            /// (available with the `cose` feature).
+            /// # Errors
+            /// Returns an error when the key is malformed, is for another parameter set, has no
+            /// `priv` parameter or has a `pub` parameter that does not match the seed.
+            pub fn try_keygen_from_cose_key(
+                key: &[u8],
+            ) -> Result<(EncapsKey, DecapsKey), &'static str> {
+                let (ek, seed) = crate::cose::decode_key(COSE_ALG, key, EK_LEN)?;
+                let seed = seed.ok_or("COSE_Key: missing priv")?;
+                let (ek_seed, dk) = KG::try_keygen_from_seed_vt(&seed)?;
+                crate::helpers::ensure!(ek == ek_seed.0, "COSE_Key: pub does not match priv");
+                Ok((ek_seed, dk))
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        impl KG {
+            /// Generates `n` key pairs in parallel (available with the `rayon` feature). <br>
+            /// A 32-byte seed is drawn from `rng` for each key pair in order, and `rng_factory` turns
+            /// each seed into the independent random number generator used for that key pair (e.g.
+            /// `ChaCha20Rng::from_seed`). The output is therefore identical for a given `rng` state
+            /// regardless of the number of worker threads, so seeded test fixtures reproduce. This
+            /// function makes no (constant) timing assurances.
+            /// # Errors
+            /// Returns an error when a random number generator fails; propagates internal errors.
+            pub fn par_keygen<R: CryptoRngCore>(
+                n: usize, rng: &mut impl CryptoRngCore, rng_factory: impl Fn([u8; 32]) -> R + Sync,
+            ) -> Result<alloc::vec::Vec<(EncapsKey, DecapsKey)>, &'static str> {
+                crate::par::par_keygen(n, rng, rng_factory, |item_rng: &mut R| {
+                    KG::try_keygen_with_rng_vt(item_rng)
+                })
+            }
+        }
+
         impl Encaps for EncapsKey {
             type CipherText = CipherText;
             type SharedSecretKey = SharedSecretKey;
@@ -141,9 +432,7 @@ macro_rules! functionality {
                 &self, rng: &mut impl CryptoRngCore,
             ) -> Result<(Self::SharedSecretKey, Self::CipherText), &'static str> {
                 let mut ct = [0u8; CT_LEN];
-                let ssk = ml_kem_encaps::<K, ETA1, ETA1_64, ETA2, ETA2_64, DU, DV>(
-                    rng, &self.0, &mut ct,
-                )?;
+                let ssk = ml_kem_encaps(PARAMS, rng, &self.0, &mut ct)?;
                 Ok((ssk, CipherText(ct)))
             }
         }
@@ -153,10 +442,321 @@ macro_rules! functionality {
             type SharedSecretKey = SharedSecretKey;
 
             fn try_decaps_vt(&self, ct: &CipherText) -> Result<SharedSecretKey, &'static str> {
-                let ssk = ml_kem_decaps::<K, ETA1, ETA1_64, ETA2, ETA2_64, DU, DV, J_LEN, CT_LEN>(
-                    &self.0, &ct.0,
-                );
-                ssk
+                ml_kem_decaps(PARAMS, &self.0, &ct.0)
+            }
+        }
+
+        #[cfg(feature = "rayon")]
+        impl DecapsKey {
+            /// Decapsulates each of the `cts` in parallel (available with the `rayon` feature),
+            /// returning the shared secrets in the same order. This function makes no (constant)
+            /// timing assurances.
+            /// # Errors
+            /// Propagates internal errors.
+            pub fn par_decaps(
+                &self, cts: &[CipherText],
+            ) -> Result<alloc::vec::Vec<SharedSecretKey>, &'static str> {
+                use rayon::prelude::*;
+                cts.par_iter().map(|ct| self.try_decaps_vt(ct)).collect()
+            }
+        }
+
+
+        /// A zero-filled placeholder, e.g. for the output buffer of `encaps_batch()`.
+        impl Default for CipherText {
+            fn default() -> Self { CipherText([0u8; CT_LEN]) }
+        }
+
+
+        /// Encapsulates against each of the `eks`, writing the resulting shared secrets into `ssks` and
+        /// ciphertexts into `cts` (which must be the same length as `eks`). Groups of four items
+        /// share their Keccak permutations and NTT work, which raises throughput over single calls
+        /// (except with the `small-stack` or `small-code` features, which process items one at a
+        /// time), and the results match calling `try_encaps_with_rng_vt()` on each key in turn with
+        /// the same `rng` exactly. This function makes no (constant) timing assurances.
+        /// # Errors
+        /// Returns an error when the slice lengths differ, any key fails validation (in which case no
+        /// randomness is consumed) or the `rng` fails; propagates internal errors. On error, `ssks`
+        /// and `cts` are zeroed.
+        pub fn encaps_batch(
+            rng: &mut impl CryptoRngCore, eks: &[EncapsKey], ssks: &mut [SharedSecretKey],
+            cts: &mut [CipherText],
+        ) -> Result<(), &'static str> {
+            ml_kem_encaps_batch(PARAMS, rng, eks, |ek| &ek.0, ssks, cts, |ct| &mut ct.0)
+        }
+
+
+        /// Decapsulates each of the `cts` with the corresponding key in `dks`, writing the resulting
+        /// shared secrets into `ssks` (all three slices must be the same length). Groups of four
+        /// items share their work as in `encaps_batch()`, and the results match calling
+        /// `try_decaps_vt()` on each pair exactly. This function makes no (constant) timing
+        /// assurances.
+        /// # Errors
+        /// Returns an error when the slice lengths differ; propagates internal errors, in which case
+        /// `ssks` is zeroed.
+        pub fn decaps_batch(
+            dks: &[DecapsKey], cts: &[CipherText], ssks: &mut [SharedSecretKey],
+        ) -> Result<(), &'static str> {
+            ml_kem_decaps_batch(PARAMS, dks, |dk| &dk.0, cts, |ct| &ct.0, ssks)
+        }
+
+
+        #[cfg(feature = "jose")]
+        impl EncapsKey {
+            /// Encodes the encapsulation key as a public AKP JWK (available with the `jose`
+            /// feature).
+            #[must_use]
+            pub fn to_jwk(&self) -> alloc::string::String {
+                crate::jose::encode_jwk(JOSE_ALG, &self.0, None)
+            }
+
+            /// Decodes the `pub` member of an AKP JWK (available with the `jose` feature). As
+            /// `try_from_bytes()`, rejects coefficients not below q.
+            /// # Errors
+            /// Returns an error when the JWK is malformed or is for another parameter set.
+            pub fn try_from_jwk(jwk: &str) -> Result<Self, &'static str> {
+                let (ek, _seed) = crate::jose::decode_jwk(JOSE_ALG, jwk, EK_LEN)?;
+                let ek = ek.try_into().map_err(|_| "JWK: pub has the wrong length")?;
+                EncapsKey::try_from_bytes(ek)
+            }
+
+            /// Establishes a JWE content encryption key for `enc` with this recipient key, as
+            /// draft-ietf-jose-pqc-kem direct key agreement (available with the `jose` feature).
+            /// The ciphertext is sent as the base64url `ek` header parameter, and `apu`/`apv` are
+            /// the decoded header parameters of the same names (empty when absent).
+            /// # Errors
+            /// Returns an error when the random number generator fails.
+            pub fn jwe_encaps(
+                &self, rng: &mut impl CryptoRngCore, enc: crate::jose::ContentEncryption,
+                apu: &[u8], apv: &[u8],
+            ) -> Result<(zeroize::Zeroizing<alloc::vec::Vec<u8>>, CipherText), &'static str> {
+                let (ssk, ct) = self.try_encaps_with_rng_vt(rng)?;
+                Ok((crate::jose::derive_cek(enc, &ssk, apu, apv), ct))
+            }
+
+            /// Encrypts `plaintext` to this recipient key as a JWE in the compact serialization
+            /// (available with the `jose` feature).
+            /// # Errors
+            /// Returns an error when the random number generator fails.
+            pub fn jwe_encrypt_compact(
+                &self, rng: &mut impl CryptoRngCore, enc: crate::jose::ContentEncryption,
+                plaintext: &[u8],
+            ) -> Result<alloc::string::String, &'static str> {
+                let (cek, ct) = self.jwe_encaps(rng, enc, b"", b"")?;
+                crate::jose::encrypt(rng, JOSE_ALG, enc, &cek, &ct.0, plaintext, None)
+            }
+
+            /// Encrypts `plaintext` to this recipient key as a JWE in the flattened JSON
+            /// serialization, also authenticating `aad` (omitted when empty) (available with the
+            /// `jose` feature).
+            /// # Errors
+            /// Returns an error when the random number generator fails.
+            pub fn jwe_encrypt_json(
+                &self, rng: &mut impl CryptoRngCore, enc: crate::jose::ContentEncryption,
+                plaintext: &[u8], aad: &[u8],
+            ) -> Result<alloc::string::String, &'static str> {
+                let (cek, ct) = self.jwe_encaps(rng, enc, b"", b"")?;
+                crate::jose::encrypt(rng, JOSE_ALG, enc, &cek, &ct.0, plaintext, Some(aad))
+            }
+        }
+
+
+        #[cfg(feature = "cose")]
+        impl EncapsKey {
+            /// Encodes the encapsulation key as a public AKP `COSE_Key` into `out`, returning the
+            /// length (available with the `cose` feature). `out` should have room for the
+            /// encapsulation key plus 16 bytes.
+            /// # Errors
+            /// Returns an error when `out` is too small.
+            pub fn to_cose_key(&self, out: &mut [u8]) -> Result<usize, &'static str> {
+                crate::cose::encode_key(COSE_ALG, &self.0, None, out)
+            }
+
+            /// Decodes the `pub` parameter of an AKP `COSE_Key` (available with the `cose`
+            /// feature).
+            /// # Errors
+            /// Returns an error when the key is malformed or is for another parameter set.
+            pub fn try_from_cose_key(key: &[u8]) -> Result<Self, &'static str> {
+                let (ek, _seed) = crate::cose::decode_key(COSE_ALG, key, EK_LEN)?;
+                let ek = ek.try_into().map_err(|_| "COSE_Key: pub has the wrong length")?;
+                EncapsKey::try_from_bytes(ek)
+            }
+
+            /// Encrypts `plaintext` to this recipient key as a tagged `COSE_Encrypt0` whose `ek`
+            /// header parameter carries the ciphertext, also authenticating `external_aad`, into
+            /// `out` and returns the length (available with the `cose` feature). `out` should have
+            /// room for the ciphertext and plaintext plus `cose::OVERHEAD` bytes.
+            /// # Errors
+            /// Returns an error when `out` is too small, `external_aad` is longer than
+            /// `cose::MAX_EXTERNAL_AAD_LEN` or the random number generator fails.
+            pub fn cose_encrypt0(
+                &self, rng: &mut impl CryptoRngCore, alg: crate::cose::ContentAlgorithm,
+                plaintext: &[u8], external_aad: &[u8], out: &mut [u8],
+            ) -> Result<usize, &'static str> {
+                let (ssk, ct) = self.try_encaps_with_rng_vt(rng)?;
+                crate::cose::encrypt(rng, None, alg, &ssk, &ct.0, plaintext, external_aad, out)
+            }
+
+            /// Encrypts `plaintext` to this recipient key as a tagged `COSE_Encrypt` with a single
+            /// direct key agreement recipient carrying the ciphertext, also authenticating
+            /// `external_aad`, into `out` and returns the length (available with the `cose`
+            /// feature). `out` should have room for the ciphertext and plaintext plus
+            /// `cose::OVERHEAD` bytes.
+            /// # Errors
+            /// Returns an error when `out` is too small, `external_aad` is longer than
+            /// `cose::MAX_EXTERNAL_AAD_LEN` or the random number generator fails.
+            pub fn cose_encrypt(
+                &self, rng: &mut impl CryptoRngCore, alg: crate::cose::ContentAlgorithm,
+                plaintext: &[u8], external_aad: &[u8], out: &mut [u8],
+            ) -> Result<usize, &'static str> {
+                let (ssk, ct) = self.try_encaps_with_rng_vt(rng)?;
+                let kem_alg = Some(COSE_ALG);
+                crate::cose::encrypt(rng, kem_alg, alg, &ssk, &ct.0, plaintext, external_aad, out)
+            }
+        }
+
+
+        #[cfg(feature = "jose")]
+        impl DecapsKey {
+            /// Recovers the JWE content encryption key for `enc` from the `ek` ciphertext, as
+            /// `EncapsKey::jwe_encaps()` (available with the `jose` feature). This function makes
+            /// no (constant) timing assurances.
+            /// # Errors
+            /// Propagates internal errors.
+            pub fn jwe_decaps(
+                &self, ct: &CipherText, enc: crate::jose::ContentEncryption, apu: &[u8], apv: &[u8],
+            ) -> Result<zeroize::Zeroizing<alloc::vec::Vec<u8>>, &'static str> {
+                let ssk = self.try_decaps_vt(ct)?;
+                Ok(crate::jose::derive_cek(enc, &ssk, apu, apv))
+            }
+
+            /// Decrypts a JWE in the compact serialization (available with the `jose` feature).
+            /// # Errors
+            /// Returns an error when the JWE is malformed, is not for this parameter set, uses an
+            /// unsupported algorithm or fails authentication.
+            pub fn jwe_decrypt_compact(
+                &self, jwe: &str,
+            ) -> Result<zeroize::Zeroizing<alloc::vec::Vec<u8>>, &'static str> {
+                self.jwe_decrypt(crate::jose::Jwe::try_from_compact(jwe)?)
+            }
+
+            /// Decrypts a JWE in the flattened JSON serialization, or the general one with a
+            /// single recipient (available with the `jose` feature).
+            /// # Errors
+            /// Returns an error when the JWE is malformed, is not for this parameter set, uses an
+            /// unsupported algorithm or fails authentication.
+            pub fn jwe_decrypt_json(
+                &self, jwe: &str,
+            ) -> Result<zeroize::Zeroizing<alloc::vec::Vec<u8>>, &'static str> {
+                self.jwe_decrypt(crate::jose::Jwe::try_from_json(jwe)?)
+            }
+
+            fn jwe_decrypt(
+                &self, jwe: crate::jose::Jwe,
+            ) -> Result<zeroize::Zeroizing<alloc::vec::Vec<u8>>, &'static str> {
+                let (enc, ek) = jwe.key_agreement(JOSE_ALG)?;
+                let ct = CipherText::try_from_bytes(
+                    ek.as_slice().try_into().map_err(|_| "JWE: ek has the wrong length")?,
+                )?;
+                let (apu, apv) = jwe.party_info()?;
+                let cek = self.jwe_decaps(&ct, enc, &apu, &apv)?;
+                jwe.decrypt(enc, &cek)
+            }
+        }
+
+
+        #[cfg(feature = "cose")]
+        impl DecapsKey {
+            /// Decrypts a `COSE_Encrypt0` (tagged or not) from `EncapsKey::cose_encrypt0()` into
+            /// `out`, returning the plaintext length (available with the `cose` feature). `out`
+            /// should have room for the content, which is at most the message length. This
+            /// function makes no (constant) timing assurances.
+            /// # Errors
+            /// Returns an error when the message is malformed, uses an unsupported algorithm or
+            /// fails authentication (whereupon `out` is zeroized), or when `out` is too small.
+            pub fn cose_decrypt0(
+                &self, message: &[u8], external_aad: &[u8], out: &mut [u8],
+            ) -> Result<usize, &'static str> {
+                self.cose_decrypt_message(message, None, external_aad, out)
+            }
+
+            /// Decrypts a `COSE_Encrypt` (tagged or not) with a single recipient for this
+            /// parameter set, as from `EncapsKey::cose_encrypt()`, into `out`, returning the
+            /// plaintext length (available with the `cose` feature). This function makes no
+            /// (constant) timing assurances.
+            /// # Errors
+            /// Returns an error when the message is malformed, is not for this parameter set, uses
+            /// an unsupported algorithm or fails authentication (whereupon `out` is zeroized), or
+            /// when `out` is too small.
+            pub fn cose_decrypt(
+                &self, message: &[u8], external_aad: &[u8], out: &mut [u8],
+            ) -> Result<usize, &'static str> {
+                self.cose_decrypt_message(message, Some(COSE_ALG), external_aad, out)
+            }
+
+            fn cose_decrypt_message(
+                &self, message: &[u8], kem_alg: Option<i64>, external_aad: &[u8], out: &mut [u8],
+            ) -> Result<usize, &'static str> {
+                let (message, ek) = crate::cose::Message::parse(message, kem_alg)?;
+                let ct = CipherText::try_from_bytes(
+                    ek.try_into().map_err(|_| "COSE: ek has the wrong length")?,
+                )?;
+                let ssk = self.try_decaps_vt(&ct)?;
+                message.decrypt(&ssk, external_aad, out)
+            }
+        }
+
+
+        /// Encodes the expanded decapsulation key as a PKCS#8 `OneAsymmetricKey` (available with
+        /// the `pkcs8` feature). Use `KG::seed_to_pkcs8_der()` for the seed forms.
+        #[cfg(feature = "pkcs8")]
+        impl pkcs8::EncodePrivateKey for DecapsKey {
+            fn to_pkcs8_der(&self) -> pkcs8::Result<pkcs8::SecretDocument> {
+                crate::pkix::encode_private_key(OID, None, Some(&self.0))
+            }
+        }
+
+
+        /// Decodes a PKCS#8 `OneAsymmetricKey` in any of the seed, expanded key or both forms
+        /// (available with the `pkcs8` feature). Seeds are expanded; a `both` encoding whose
+        /// expanded key does not match its seed is rejected.
+        #[cfg(feature = "pkcs8")]
+        impl TryFrom<pkcs8::PrivateKeyInfo<'_>> for DecapsKey {
+            type Error = pkcs8::Error;
+
+            fn try_from(private_key_info: pkcs8::PrivateKeyInfo<'_>) -> pkcs8::Result<Self> {
+                crate::pkix::decode_private_key(
+                    OID,
+                    &private_key_info,
+                    DK_LEN,
+                    |seed| KG::try_keygen_from_seed_vt(seed).map(|(_ek, dk)| dk),
+                    |dk| Ok(DecapsKey(dk.try_into().map_err(|_| "dk len not DK_LEN")?)),
+                    |dk| &dk.0,
+                )
+            }
+        }
+
+
+        /// Encodes the encapsulation key as an X.509 `SubjectPublicKeyInfo` (available with the
+        /// `spki` feature).
+        #[cfg(feature = "spki")]
+        impl spki::EncodePublicKey for EncapsKey {
+            fn to_public_key_der(&self) -> spki::Result<spki::Document> {
+                crate::pkix::encode_public_key(OID, &self.0)
+            }
+        }
+
+
+        /// Decodes an X.509 `SubjectPublicKeyInfo` (available with the `spki` feature). Rejects an
+        /// OID for another parameter set and, as `try_from_bytes()`, coefficients not below q.
+        #[cfg(feature = "spki")]
+        impl TryFrom<spki::SubjectPublicKeyInfoRef<'_>> for EncapsKey {
+            type Error = spki::Error;
+
+            fn try_from(spki: spki::SubjectPublicKeyInfoRef<'_>) -> spki::Result<Self> {
+                let ek = crate::pkix::decode_public_key(OID, &spki)?;
+                let ek = ek.try_into().map_err(|_| spki::Error::KeyMalformed)?;
+                EncapsKey::try_from_bytes(ek).map_err(|_| spki::Error::KeyMalformed)
             }
         }
 
@@ -165,10 +765,7 @@ macro_rules! functionality {
             type ByteArray = [u8; EK_LEN];
 
             fn try_from_bytes(ek: Self::ByteArray) -> Result<Self, &'static str> {
-                let mut ek_hat = [Z256(0); 256];
-                for i in 0..K {
-                    byte_decode(12, &ek[384 * i..384 * (i + 1)], &mut ek_hat)?;
-                }
+                EncapsKey::check(&ek)?;
                 Ok(EncapsKey(ek))
             }
 
@@ -197,6 +794,134 @@ macro_rules! functionality {
 
             fn into_bytes(self) -> Self::ByteArray { self.0 }
         }
+
+
+        impl EncapsKey {
+            /// The length of the encoded encapsulation key in bytes.
+            pub const LEN: usize = EK_LEN;
+
+            /// Returns the fingerprint `H(ek)` of the encapsulation key, which identifies it e.g. as
+            /// a hash map key or, rendered with `Display` or `short_id()`, to users.
+            #[must_use]
+            pub fn fingerprint(&self) -> crate::Fingerprint {
+                crate::Fingerprint(crate::helpers::h(&self.0))
+            }
+
+            /// Writes the encoded encapsulation key into the start of `out`, returning its length.
+            /// # Errors
+            /// Returns an error when `out` is shorter than `EncapsKey::LEN`.
+            pub fn write_to(&self, out: &mut [u8]) -> Result<usize, &'static str> {
+                crate::helpers::ensure!(out.len() >= EK_LEN, "output buffer shorter than EK_LEN");
+                out[..EK_LEN].copy_from_slice(&self.0);
+                Ok(EK_LEN)
+            }
+
+            // Every coefficient must be below q
+            fn check(ek: &[u8; EK_LEN]) -> Result<(), &'static str> {
+                let mut ek_hat = [Z256(0); 256];
+                for i in 0..K {
+                    byte_decode(12, &ek[384 * i..384 * (i + 1)], &mut ek_hat)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl DecapsKey {
+            /// The length of the encoded decapsulation key in bytes.
+            pub const LEN: usize = DK_LEN;
+
+            /// Returns the fingerprint of the corresponding encapsulation key, the `H(ek)` that key
+            /// generation stores in the decapsulation key (so nothing is hashed here).
+            #[must_use]
+            pub fn encaps_key_fingerprint(&self) -> crate::Fingerprint {
+                let mut h_ek = [0u8; 32];
+                h_ek.copy_from_slice(&self.0[DK_LEN - 64..DK_LEN - 32]); // dk = dk_pke ∥ ek ∥ H(ek) ∥ z
+                crate::Fingerprint(h_ek)
+            }
+
+            /// Writes the encoded decapsulation key into the start of `out`, returning its length.
+            /// # Errors
+            /// Returns an error when `out` is shorter than `DecapsKey::LEN`.
+            pub fn write_to(&self, out: &mut [u8]) -> Result<usize, &'static str> {
+                crate::helpers::ensure!(out.len() >= DK_LEN, "output buffer shorter than DK_LEN");
+                out[..DK_LEN].copy_from_slice(&self.0);
+                Ok(DK_LEN)
+            }
+        }
+
+        impl CipherText {
+            /// The length of the encoded ciphertext in bytes.
+            pub const LEN: usize = CT_LEN;
+
+            /// Writes the encoded ciphertext into the start of `out`, returning its length.
+            /// # Errors
+            /// Returns an error when `out` is shorter than `CipherText::LEN`.
+            pub fn write_to(&self, out: &mut [u8]) -> Result<usize, &'static str> {
+                crate::helpers::ensure!(out.len() >= CT_LEN, "output buffer shorter than CT_LEN");
+                out[..CT_LEN].copy_from_slice(&self.0);
+                Ok(CT_LEN)
+            }
+        }
+
+
+        /// Decodes an encapsulation key directly from a slice of exactly `EncapsKey::LEN` bytes,
+        /// validated as by `SerDes::try_from_bytes()`.
+        impl TryFrom<&[u8]> for EncapsKey {
+            type Error = &'static str;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                crate::helpers::ensure!(bytes.len() >= EK_LEN, "ek shorter than EK_LEN");
+                crate::helpers::ensure!(bytes.len() <= EK_LEN, "ek longer than EK_LEN");
+                let mut ek = EncapsKey([0u8; EK_LEN]);
+                ek.0.copy_from_slice(bytes);
+                EncapsKey::check(&ek.0)?;
+                Ok(ek)
+            }
+        }
+
+        /// Decodes a decapsulation key directly from a slice of exactly `DecapsKey::LEN` bytes,
+        /// validated as by `SerDes::try_from_bytes()`.
+        impl TryFrom<&[u8]> for DecapsKey {
+            type Error = &'static str;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                crate::helpers::ensure!(bytes.len() >= DK_LEN, "dk shorter than DK_LEN");
+                crate::helpers::ensure!(bytes.len() <= DK_LEN, "dk longer than DK_LEN");
+                let mut dk = DecapsKey([0u8; DK_LEN]);
+                dk.0.copy_from_slice(bytes);
+                Ok(dk)
+            }
+        }
+
+        /// Decodes a ciphertext directly from a slice of exactly `CipherText::LEN` bytes, validated
+        /// as by `SerDes::try_from_bytes()`.
+        impl TryFrom<&[u8]> for CipherText {
+            type Error = &'static str;
+
+            fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+                crate::helpers::ensure!(bytes.len() >= CT_LEN, "ct shorter than CT_LEN");
+                crate::helpers::ensure!(bytes.len() <= CT_LEN, "ct longer than CT_LEN");
+                let mut ct = CipherText([0u8; CT_LEN]);
+                ct.0.copy_from_slice(bytes);
+                Ok(ct)
+            }
+        }
+
+
+        /// Borrows the encoded encapsulation key, as returned by `SerDes::into_bytes()`.
+        impl AsRef<[u8]> for EncapsKey {
+            fn as_ref(&self) -> &[u8] { &self.0 }
+        }
+
+        /// Borrows the encoded decapsulation key, as returned by `SerDes::into_bytes()`.
+        impl AsRef<[u8]> for DecapsKey {
+            fn as_ref(&self) -> &[u8] { &self.0 }
+        }
+
+        /// Borrows the encoded ciphertext, as returned by `SerDes::into_bytes()`.
+        impl AsRef<[u8]> for CipherText {
+            fn as_ref(&self) -> &[u8] { &self.0 }
+        }
     };
 }
 
@@ -226,6 +951,12 @@ pub mod ml_kem_512 {
     const EK_LEN: usize = 800;
     const DK_LEN: usize = 1632;
     const CT_LEN: usize = 768;
+    #[cfg(feature = "spki")]
+    const OID: spki::ObjectIdentifier = crate::pkix::ID_ALG_ML_KEM_512;
+    #[cfg(feature = "jose")]
+    const JOSE_ALG: &str = "ML-KEM-512";
+    #[cfg(feature = "cose")]
+    const COSE_ALG: i64 = crate::cose::ALG_ML_KEM_512;
 
     functionality!();
 }
@@ -256,6 +987,12 @@ pub mod ml_kem_768 {
     const EK_LEN: usize = 1184;
     const DK_LEN: usize = 2400;
     const CT_LEN: usize = 1088;
+    #[cfg(feature = "spki")]
+    const OID: spki::ObjectIdentifier = crate::pkix::ID_ALG_ML_KEM_768;
+    #[cfg(feature = "jose")]
+    const JOSE_ALG: &str = "ML-KEM-768";
+    #[cfg(feature = "cose")]
+    const COSE_ALG: i64 = crate::cose::ALG_ML_KEM_768;
 
     functionality!();
 }
@@ -285,6 +1022,12 @@ pub mod ml_kem_1024 {
     const EK_LEN: usize = 1568;
     const DK_LEN: usize = 3168;
     const CT_LEN: usize = 1568;
+    #[cfg(feature = "spki")]
+    const OID: spki::ObjectIdentifier = crate::pkix::ID_ALG_ML_KEM_1024;
+    #[cfg(feature = "jose")]
+    const JOSE_ALG: &str = "ML-KEM-1024";
+    #[cfg(feature = "cose")]
+    const COSE_ALG: i64 = crate::cose::ALG_ML_KEM_1024;
 
     functionality!();
 }
@@ -1,31 +1,60 @@
 use rand_core::CryptoRngCore;
+use zeroize::Zeroize;
 
-use crate::byte_fns::{byte_decode, byte_encode};
+use crate::backend::{byte_decode, byte_encode};
 use crate::helpers::{ensure, g, h, j};
-use crate::k_pke::k_pke_decrypt;
+use crate::k_pke::{self, Params};
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+use crate::k_pke_lanes::{k_pke_decrypt_lanes, k_pke_encrypt_lanes};
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+use crate::lanes::{self, LANES};
 use crate::types::Z256;
 use crate::SharedSecretKey;
 
-use super::k_pke::{k_pke_encrypt, k_pke_key_gen};
+// The functions below take the parameter set as the runtime value `p`, so they are compiled once
+// for all parameter sets; `k_pke.rs` selects the K-PKE implementation for `p`.
+
+/// Largest ciphertext, that of ML-KEM-1024, sizing the re-encryption buffer of `ml_kem_decaps()`.
+const CT_LEN_MAX: usize = 1568;
+
 
 /// Algorithm 15 `ML-KEM.KeyGen()` on page 29.
 /// Generates an encapsulation key and a corresponding decapsulation key.
-pub(crate) fn ml_kem_key_gen<const K: usize, const ETA1: usize, const ETA1_64: usize>(
-    rng: &mut impl CryptoRngCore, ek: &mut [u8], dk: &mut [u8],
+pub(crate) fn ml_kem_key_gen(
+    p: Params, rng: &mut impl CryptoRngCore, ek: &mut [u8], dk: &mut [u8],
 ) -> Result<(), &'static str> {
-    // Output: Encapsulation key ek ∈ B^{384k+32}
-    // Output: Decapsulation key dk ∈ B^{768k+96}
-    ensure!(ek.len() == 384 * K + 32, "Alg15: ek len not 384 * K + 32");
-    ensure!(dk.len() == 768 * K + 96, "Alg15: dk len not 768 * K + 96");
-
     // 1: z ←− B32         ▷ z is 32 random bytes (see Section 3.3)
     let mut z = [0u8; 32];
     rng.try_fill_bytes(&mut z)
         .map_err(|_| "Alg15: Random number generator failed")?;
 
+    // Algorithm 12 step 1: d ←− B^{32}, drawn here so that the remainder is deterministic
+    let mut d = [0u8; 32];
+    rng.try_fill_bytes(&mut d)
+        .map_err(|_| "Alg12: random number generator failed")?;
+
+    let result = ml_kem_key_gen_from_seed(p, &d, &z, ek, dk);
+    d.zeroize();
+    z.zeroize();
+    result
+}
+
+
+/// Algorithm 15 `ML-KEM.KeyGen()` on page 29, given the random bytes `d` (for K-PKE) and `z`.
+/// The 64-byte `d ∥ z` is the seed form of a private key (see `pkix.rs`).
+pub(crate) fn ml_kem_key_gen_from_seed(
+    p: Params, d: &[u8; 32], z: &[u8; 32], ek: &mut [u8], dk: &mut [u8],
+) -> Result<(), &'static str> {
+    // Output: Encapsulation key ek ∈ B^{384k+32}
+    // Output: Decapsulation key dk ∈ B^{768k+96}
+    ensure!(ek.len() == 384 * p.k + 32, "Alg15: ek len not 384 * K + 32");
+    ensure!(dk.len() == 768 * p.k + 96, "Alg15: dk len not 768 * K + 96");
+
+    // 1: z ←− B32         ▷ z is 32 random bytes (drawn by `ml_kem_key_gen()`)
+
     // 2: (ek_{PKE}, dk_{PKE}) ← K-PKE.KeyGen()     ▷ run key generation for K-PKE
-    let p1 = 384 * K;
-    k_pke_key_gen::<K, ETA1, ETA1_64>(rng, ek, &mut dk[..p1])?; // 3: ek ← ekPKE
+    let p1 = 384 * p.k;
+    k_pke::key_gen(p, d, ek, &mut dk[..p1])?; // 3: ek ← ekPKE
 
     // 4: dk ← (dkPKE ∥ek∥H(ek)∥z)  (first concat element is done above alongside ek)
     let h_ek = h(ek);
@@ -33,7 +62,7 @@ pub(crate) fn ml_kem_key_gen<const K: usize, const ETA1: usize, const ETA1_64: u
     let p3 = p2 + h_ek.len();
     dk[p1..p2].copy_from_slice(ek);
     dk[p2..p3].copy_from_slice(&h_ek);
-    dk[p3..].copy_from_slice(&z);
+    dk[p3..].copy_from_slice(z);
 
     // 5: return (ek, dk)
     Ok(())
@@ -42,46 +71,54 @@ pub(crate) fn ml_kem_key_gen<const K: usize, const ETA1: usize, const ETA1_64: u
 
 /// Algorithm 16 `ML-KEM.Encaps(ek)` on page 30.
 /// Uses the encapsulation key to generate a shared key and an associated ciphertext.
-pub(crate) fn ml_kem_encaps<
-    const K: usize,
-    const ETA1: usize,
-    const ETA1_64: usize,
-    const ETA2: usize,
-    const ETA2_64: usize,
-    const DU: usize,
-    const DV: usize,
->(
-    rng: &mut impl CryptoRngCore, ek: &[u8], ct: &mut [u8],
+pub(crate) fn ml_kem_encaps(
+    p: Params, rng: &mut impl CryptoRngCore, ek: &[u8], ct: &mut [u8],
 ) -> Result<SharedSecretKey, &'static str> {
     // Validated input: encapsulation key ek ∈ B^{384k+32}
     // Output: shared key K ∈ B^{32}
     // Output: ciphertext c ∈ B^{32(du k+dv)}
-    ensure!(ek.len() == 384 * K + 32, "Alg16: ek len not 384 * K + 32"); // type check: array of length 384k + 32
+    ek_check(p, ek)?;
+    encaps_checked(p, rng, ek, ct)
+}
+
+
+/// Type and modulus check on the encapsulation key input to Algorithm 16 `ML-KEM.Encaps(ek)` on
+/// page 30: confirms that `ek` is `384k + 32` bytes and that
+/// `ek[0 : 384k] = ByteEncode12(ByteDecode12(ek[0 : 384k]))`.
+fn ek_check(p: Params, ek: &[u8]) -> Result<(), &'static str> {
+    ensure!(ek.len() == 384 * p.k + 32, "Alg16: ek len not 384 * K + 32"); // type check: array of length 384k + 32
 
     // modulus check: perform the computation ek ← ByteEncode12 (ByteDecode12(ek_tidle)
-    // note: after checking, we run with the original input (due to const array allocation); the last 32 bytes is rho  // TODO: revisit
+    // note: after checking, we run with the original input; the last 32 bytes is rho
     let mut ek_hat = [Z256(0); 256];
-    for i in 0..K {
+    for i in 0..p.k {
         let mut ek_tilde = [0u8; 384];
         byte_decode(12, &ek[384 * i..384 * (i + 1)], &mut ek_hat)?;
         byte_encode(12, &ek_hat, &mut ek_tilde)?;
         ensure!(ek_tilde == ek[384 * i..384 * (i + 1)], "Alg16: len check!");
     }
+    Ok(())
+}
+
 
+/// Algorithm 16 `ML-KEM.Encaps(ek)` on page 30, for an `ek` that has passed `ek_check()`.
+fn encaps_checked(
+    p: Params, rng: &mut impl CryptoRngCore, ek: &[u8], ct: &mut [u8],
+) -> Result<SharedSecretKey, &'static str> {
     // 1: m ←− B32          ▷ m is 32 random bytes (see Section 3.3)
     let mut m = [0u8; 32];
-    rng.fill_bytes(&mut m); //random::<[u8; 32]>();
+    rng.try_fill_bytes(&mut m)
+        .map_err(|_| "Alg16: random number generator failed")?;
 
     // 2: (K, r) ← G(m∥H(ek))       ▷ derive shared secret key K and randomness r
     let h_ek = h(ek);
-    // let mut g_input = [0u8; 64];
-    // g_input[0..32].copy_from_slice(&m);
-    // g_input[32..64].copy_from_slice(&h_ek);
-    // let (k, r) = g(&g_input);
-    let (k, r) = g(&[&m, &h_ek]);
+    let (k, mut r) = g(&[&m, &h_ek]);
 
-    // 3: 3: c ← K-PKE.Encrypt(ek, m, r)        ▷ encrypt m using K-PKE with randomness r
-    k_pke_encrypt::<K, ETA1, ETA1_64, ETA2, ETA2_64, DU, DV>(ek, &m, &r, ct)?;
+    // 3: c ← K-PKE.Encrypt(ek, m, r)        ▷ encrypt m using K-PKE with randomness r
+    let result = k_pke::encrypt(p, ek, &m, &r, ct);
+    m.zeroize();
+    r.zeroize();
+    result?;
 
     // 4: return (K, c)  (note: ct is mutable input)
     Ok(SharedSecretKey(k))
@@ -91,26 +128,20 @@ pub(crate) fn ml_kem_encaps<
 /// Algorithm 17 `ML-KEM.Decaps(c, dk)` on page 32.
 /// Uses the decapsulation key to produce a shared key from a ciphertext.
 #[allow(clippy::similar_names)]
-pub(crate) fn ml_kem_decaps<
-    const K: usize,
-    const ETA1: usize,
-    const ETA1_64: usize,
-    const ETA2: usize,
-    const ETA2_64: usize,
-    const DU: usize,
-    const DV: usize,
-    const J_LEN: usize,
-    const CT_LEN: usize,
->(
-    dk: &[u8], ct: &[u8],
+pub(crate) fn ml_kem_decaps(
+    p: Params, dk: &[u8], ct: &[u8],
 ) -> Result<SharedSecretKey, &'static str> {
+    let k = p.k;
     // Validated input: ciphertext c ∈ B^{32(du k+dv )}
     // Validated input: decapsulation key dk ∈ B^{768k+96}
     // Output: shared key K ∈ B^{32}
     // These length checks are a bit redundant...but present for completeness and paranoia
-    ensure!(ct.len() == 32 * (DU * K + DV), "Alg17: ct len not 32 * ...");
+    ensure!(
+        ct.len() == 32 * (p.du as usize * k + p.dv as usize),
+        "Alg17: ct len not 32 * ..."
+    );
     // Ciphertext type check
-    ensure!(dk.len() == 768 * K + 96, "Alg17: dk len not 768 ..."); // Decapsulation key type check
+    ensure!(dk.len() == 768 * k + 96, "Alg17: dk len not 768 ..."); // Decapsulation key type check
 
     // 1019 For some applications, further validation of the decapsulation key dk_tilde may be appropriate. For
     // 1020 instance, in cases where dk_tilde was generated by a third party, users may want to ensure that the four
@@ -121,44 +152,251 @@ pub(crate) fn ml_kem_decaps<
     // functionality. This limits the amount of validation that is appropriate/necessary.
 
     // 1: dkPKE ← dk[0 : 384k]              ▷ extract (from KEM decaps key) the PKE decryption key
-    let dk_pke = &dk[0..384 * K];
+    let dk_pke = &dk[0..384 * k];
 
     // 2: ekPKE ← dk[384k : 768k + 32]      ▷ extract PKE encryption key
-    let ek_pke = &dk[384 * K..768 * K + 32];
+    let ek_pke = &dk[384 * k..768 * k + 32];
 
     // 3: h ← dk[768k + 32 : 768k + 64]     ▷ extract hash of PKE encryption key
-    let h = &dk[768 * K + 32..768 * K + 64];
+    let h = &dk[768 * k + 32..768 * k + 64];
 
     // 4: z ← dk[768k + 64 : 768k + 96]     ▷ extract implicit rejection value
-    let z = &dk[768 * K + 64..768 * K + 96];
+    let z = &dk[768 * k + 64..768 * k + 96];
 
     // 5: m′ ← K-PKE.Decrypt(dkPKE,c)
-    let m_prime = k_pke_decrypt::<K, DU, DV>(dk_pke, ct)?;
+    let mut m_prime = k_pke::decrypt(p, dk_pke, ct)?;
 
     // 6: (K′, r′) ← G(m′ ∥ h)
-    // let mut g_input = [0u8; 32 + 32];
-    // g_input[0..32].copy_from_slice(&m_prime);
-    // g_input[32..64].copy_from_slice(h);
-    let (mut k_prime, r_prime) = g(&[&m_prime, &h]);
+    let (mut k_prime, mut r_prime) = g(&[&m_prime, &h]);
 
     // 7: K̄ ← J(z∥c, 32)
-    let mut j_input = [0u8; J_LEN];
-    debug_assert_eq!(j_input.len(), 32 + ct.len());
-    j_input[0..32].copy_from_slice(z);
-    j_input[32..32 + ct.len()].copy_from_slice(ct);
-    let k_bar = j(&[&z, &ct]);
+    let mut k_bar = j(&[&z, &ct]);
 
     // 8: c′ ← K-PKE.Encrypt(ekPKE , m′ , r′ )      ▷ re-encrypt using the derived randomness r′
-    let mut c_prime = [0u8; CT_LEN];
-    k_pke_encrypt::<K, ETA1, ETA1_64, ETA2, ETA2_64, DU, DV>(
-        ek_pke,
-        &m_prime,
-        &r_prime,
-        &mut c_prime[0..ct.len()],
-    )?;
+    let mut c_prime = [0u8; CT_LEN_MAX];
+    let result = k_pke::encrypt(p, ek_pke, &m_prime, &r_prime, &mut c_prime[0..ct.len()]);
     if *ct != c_prime[0..ct.len()] {
         k_prime = k_bar;
     };
+    m_prime.zeroize();
+    r_prime.zeroize();
+    k_bar.zeroize();
+    result?;
 
     Ok(SharedSecretKey(k_prime))
 }
+
+
+// The batch functions below produce exactly the same outputs as repeated calls to `ml_kem_encaps()`
+// and `ml_kem_decaps()`. Each full group of `LANES` items runs side by side through
+// `encaps_lanes()` or `decaps_lanes()`, which share the Keccak permutations and NTT work of the
+// group (see `lanes.rs`); any remaining items, and every item with the `small-stack` or
+// `small-code` features, take the single-item path. On error, every shared secret and ciphertext
+// written so far is wiped.
+
+
+/// Batched Algorithm 16 `ML-KEM.Encaps(ek)` on page 30.
+/// Encapsulates against each key in `eks`, writing the shared keys to `ssks` and the ciphertexts
+/// (via `ct_bytes`) to `cts`. The rng is consumed in the same order as sequential single calls.
+pub(crate) fn ml_kem_encaps_batch<EK, CT>(
+    p: Params, rng: &mut impl CryptoRngCore, eks: &[EK], ek_bytes: impl Fn(&EK) -> &[u8],
+    ssks: &mut [SharedSecretKey], cts: &mut [CT], ct_bytes: impl Fn(&mut CT) -> &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!(eks.len() == ssks.len(), "Alg16: batch ssks len not eks len");
+    ensure!(eks.len() == cts.len(), "Alg16: batch cts len not eks len");
+
+    // Type and modulus check every key up front, so that a bad key consumes no randomness
+    for ek in eks {
+        ek_check(p, ek_bytes(ek))?;
+    }
+
+    let result = encaps_batch_checked(p, rng, eks, &ek_bytes, ssks, cts, &ct_bytes);
+    if result.is_err() {
+        ssks.iter_mut().for_each(Zeroize::zeroize);
+        for ct in cts.iter_mut() {
+            ct_bytes(ct).zeroize();
+        }
+    }
+    result
+}
+
+
+/// The body of `ml_kem_encaps_batch()`, for keys that have passed `ek_check()`.
+fn encaps_batch_checked<EK, CT>(
+    p: Params, rng: &mut impl CryptoRngCore, eks: &[EK], ek_bytes: &impl Fn(&EK) -> &[u8],
+    ssks: &mut [SharedSecretKey], cts: &mut [CT], ct_bytes: &impl Fn(&mut CT) -> &mut [u8],
+) -> Result<(), &'static str> {
+    #[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+    let grouped = {
+        let grouped = eks.len() - eks.len() % LANES;
+        let groups = eks[..grouped].chunks_exact(LANES);
+        for ((eks, ssks), cts) in groups
+            .zip(ssks.chunks_exact_mut(LANES))
+            .zip(cts.chunks_exact_mut(LANES))
+        {
+            let mut cts = cts.iter_mut();
+            let cts = core::array::from_fn(|_| ct_bytes(cts.next().unwrap()));
+            let ks = encaps_lanes(p, rng, core::array::from_fn(|l| ek_bytes(&eks[l])), cts)?;
+            for (ssk, k) in ssks.iter_mut().zip(ks) {
+                *ssk = k;
+            }
+        }
+        grouped
+    };
+    #[cfg(any(feature = "small-stack", feature = "small-code"))]
+    let grouped = 0;
+
+    for ((ek, ssk), ct) in eks[grouped..]
+        .iter()
+        .zip(&mut ssks[grouped..])
+        .zip(&mut cts[grouped..])
+    {
+        *ssk = encaps_checked(p, rng, ek_bytes(ek), ct_bytes(ct))?;
+    }
+    Ok(())
+}
+
+
+/// Algorithm 16 `ML-KEM.Encaps(ek)` on page 30 for `LANES` keys that have passed `ek_check()`,
+/// drawing each `m` from the rng in turn.
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+fn encaps_lanes(
+    p: Params, rng: &mut impl CryptoRngCore, eks: [&[u8]; LANES], cts: [&mut [u8]; LANES],
+) -> Result<[SharedSecretKey; LANES], &'static str> {
+    // 1: m ←− B32          ▷ m is 32 random bytes (see Section 3.3)
+    let mut ms = [[0u8; 32]; LANES];
+    for m in &mut ms {
+        if rng.try_fill_bytes(m).is_err() {
+            ms.zeroize();
+            return Err("Alg16: random number generator failed");
+        }
+    }
+
+    // 2: (K, r) ← G(m∥H(ek))       ▷ derive shared secret key K and randomness r
+    let h_eks = lanes::h(eks);
+    let mut krs = lanes::g(core::array::from_fn(|l| [&ms[l][..], &h_eks[l][..]]));
+    let mut rs = krs.map(|(_, r)| r);
+
+    // 3: c ← K-PKE.Encrypt(ek, m, r)        ▷ encrypt m using K-PKE with randomness r
+    let result = k_pke_encrypt_lanes(p, eks, &ms, &rs, cts);
+    let ks = krs.map(|(k, _)| SharedSecretKey(k));
+    ms.zeroize();
+    krs.zeroize();
+    rs.zeroize();
+    result?;
+
+    // 4: return (K, c)  (note: ct is mutable input)
+    Ok(ks)
+}
+
+
+/// Batched Algorithm 17 `ML-KEM.Decaps(c, dk)` on page 32.
+/// Decapsulates each ciphertext in `cts` (via `ct_bytes`) with the corresponding key in `dks` (via
+/// `dk_bytes`), writing the shared keys to `ssks`.
+pub(crate) fn ml_kem_decaps_batch<DK, CT>(
+    p: Params, dks: &[DK], dk_bytes: impl Fn(&DK) -> &[u8], cts: &[CT],
+    ct_bytes: impl Fn(&CT) -> &[u8], ssks: &mut [SharedSecretKey],
+) -> Result<(), &'static str> {
+    ensure!(dks.len() == cts.len(), "Alg17: batch cts len not dks len");
+    ensure!(dks.len() == ssks.len(), "Alg17: batch ssks len not dks len");
+    for (dk, ct) in dks.iter().zip(cts) {
+        ensure!(
+            ct_bytes(ct).len() == 32 * (p.du as usize * p.k + p.dv as usize),
+            "Alg17: ct len not 32 * ..."
+        );
+        ensure!(dk_bytes(dk).len() == 768 * p.k + 96, "Alg17: dk len not 768 ...");
+    }
+
+    let result = decaps_batch_checked(p, dks, &dk_bytes, cts, &ct_bytes, ssks);
+    if result.is_err() {
+        ssks.iter_mut().for_each(Zeroize::zeroize);
+    }
+    result
+}
+
+
+/// The body of `ml_kem_decaps_batch()`, for inputs of the right lengths.
+fn decaps_batch_checked<DK, CT>(
+    p: Params, dks: &[DK], dk_bytes: &impl Fn(&DK) -> &[u8], cts: &[CT],
+    ct_bytes: &impl Fn(&CT) -> &[u8], ssks: &mut [SharedSecretKey],
+) -> Result<(), &'static str> {
+    #[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+    let grouped = {
+        let grouped = dks.len() - dks.len() % LANES;
+        let groups = dks[..grouped].chunks_exact(LANES);
+        for ((dks, cts), ssks) in groups
+            .zip(cts.chunks_exact(LANES))
+            .zip(ssks.chunks_exact_mut(LANES))
+        {
+            let dks = core::array::from_fn(|l| dk_bytes(&dks[l]));
+            let ks = decaps_lanes(p, dks, core::array::from_fn(|l| ct_bytes(&cts[l])))?;
+            for (ssk, k) in ssks.iter_mut().zip(ks) {
+                *ssk = k;
+            }
+        }
+        grouped
+    };
+    #[cfg(any(feature = "small-stack", feature = "small-code"))]
+    let grouped = 0;
+
+    for ((dk, ct), ssk) in dks[grouped..]
+        .iter()
+        .zip(&cts[grouped..])
+        .zip(&mut ssks[grouped..])
+    {
+        *ssk = ml_kem_decaps(p, dk_bytes(dk), ct_bytes(ct))?;
+    }
+    Ok(())
+}
+
+
+/// Algorithm 17 `ML-KEM.Decaps(c, dk)` on page 32 for `LANES` inputs of the right lengths.
+#[cfg(not(any(feature = "small-stack", feature = "small-code")))]
+#[allow(clippy::similar_names)]
+fn decaps_lanes(
+    p: Params, dks: [&[u8]; LANES], cts: [&[u8]; LANES],
+) -> Result<[SharedSecretKey; LANES], &'static str> {
+    let k = p.k;
+    let ct_len = cts[0].len();
+
+    // 1-4: dkPKE, ekPKE, h and z are dk[0 : 384k], dk[384k : 768k + 32], dk[768k + 32 : 768k + 64]
+    // and dk[768k + 64 : 768k + 96] respectively
+
+    // 5: m′ ← K-PKE.Decrypt(dkPKE,c)
+    let mut m_primes = k_pke_decrypt_lanes(p, dks.map(|dk| &dk[0..384 * k]), cts)?;
+
+    // 6: (K′, r′) ← G(m′ ∥ h)
+    let mut krs = lanes::g(core::array::from_fn(|l| {
+        [&m_primes[l][..], &dks[l][768 * k + 32..768 * k + 64]]
+    }));
+    let mut r_primes = krs.map(|(_, r)| r);
+
+    // 7: K̄ ← J(z∥c, 32)
+    let mut k_bars =
+        lanes::j(core::array::from_fn(|l| [&dks[l][768 * k + 64..768 * k + 96], cts[l]]));
+
+    // 8: c′ ← K-PKE.Encrypt(ekPKE , m′ , r′ )      ▷ re-encrypt using the derived randomness r′
+    let mut c_primes = [[0u8; CT_LEN_MAX]; LANES];
+    let mut c_prime_slices = c_primes.iter_mut();
+    let result = k_pke_encrypt_lanes(
+        p,
+        dks.map(|dk| &dk[384 * k..768 * k + 32]),
+        &m_primes,
+        &r_primes,
+        core::array::from_fn(|_| &mut c_prime_slices.next().unwrap()[0..ct_len]),
+    );
+    let ks = core::array::from_fn(|l| {
+        if *cts[l] == c_primes[l][0..ct_len] {
+            SharedSecretKey(krs[l].0)
+        } else {
+            SharedSecretKey(k_bars[l])
+        }
+    });
+    m_primes.zeroize();
+    krs.zeroize();
+    r_primes.zeroize();
+    k_bars.zeroize();
+    result?;
+
+    Ok(ks)
+}
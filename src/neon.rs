@@ -0,0 +1,569 @@
+use core::arch::aarch64::{
+    int16x8_t, uint16x8_t, uint32x4_t, vaddq_s16, vaddq_u16, vaddq_u32, vaddq_u8, vandq_s16,
+    vandq_u32, vandq_u8, vdupq_n_s16, vdupq_n_s32, vdupq_n_u16, vdupq_n_u32, vdupq_n_u8,
+    vget_low_s8, vget_low_u16, vget_low_u32, vld1q_u16, vld1q_u32, vld1q_u8, vminq_u16, vminq_u32,
+    vmlal_high_u16, vmlal_u16, vmlsq_u32, vmovl_high_s8, vmovl_high_u16, vmovl_s8, vmovl_u16,
+    vmovn_high_s32, vmovn_high_u32, vmovn_s32, vmovn_u32, vmull_high_u16, vmull_high_u32,
+    vmull_u16, vmull_u32, vqtbl1q_u8, vreinterpretq_s32_u32,
+    vreinterpretq_s8_u8, vreinterpretq_u16_s16, vreinterpretq_u16_u32, vreinterpretq_u16_u64,
+    vreinterpretq_u32_u16, vreinterpretq_u32_u64, vreinterpretq_u32_u8, vreinterpretq_u64_u16,
+    vreinterpretq_u64_u32, vshlq_u32, vshrn_high_n_u64, vshrn_n_u64, vshrq_n_s16, vshrq_n_u32,
+    vshrq_n_u64, vshrq_n_u8, vst1q_u16, vst1q_u32, vsubq_u16, vsubq_u32, vsubq_u8, vuzp1q_u16,
+    vuzp1q_u32, vuzp2q_u16, vuzp2q_u32, vzip1q_u32, vzip1q_u64, vzip1q_u8, vzip2q_u32, vzip2q_u64,
+    vzip2q_u8,
+};
+
+use crate::helpers::{count, ensure};
+use crate::ntt::{gen_zeta_table, ZETA_TABLE};
+use crate::sampling;
+use crate::types::Z256;
+use crate::Q;
+
+// AArch64 NEON implementations of the NTT, inverse NTT, base multiplication, Barrett reduction,
+// CBD sampling and Compress, selected by `backend.rs` on `aarch64` (where NEON is always
+// present). Each function mirrors its scalar counterpart (same signature, same output) while
+// operating on eight 16-bit coefficients per vector. As in `simd128.rs`, products by a known zeta
+// use Shoup multiplication with a precomputed `⌊ζ · 2^16 / q⌋`.
+//
+// The intrinsics are `unsafe` only because they are `#[target_feature(enable = "neon")]`
+// functions; NEON is part of the `aarch64` baseline, so the sole obligation left to the unsafe
+// blocks below is the in-bounds access of the raw-pointer loads and stores.
+
+#[allow(clippy::cast_possible_truncation)]
+const Q16: u16 = Q as u16;
+
+/// The Shoup companions `⌊ζ · 2^16 / q⌋` of each entry in `ZETA_TABLE`.
+static ZETA_SHOUP: [u16; 256] = gen_shoup_table();
+
+/// The NTT⁻¹ scaling factor `128^{-1} mod q` and its Shoup companion.
+const F: u16 = 3303;
+#[allow(clippy::cast_possible_truncation)]
+const F_SHOUP: u16 = (((F as u32) << 16) / Q) as u16;
+
+/// Barrett constant `⌊2^32 / q⌋`, as in `Z256::reduce_u32()`.
+#[allow(clippy::cast_possible_truncation)]
+const BARRETT_M: u32 = ((1u64 << 32) / Q as u64) as u32;
+
+/// `⌈2^38 / 2q⌉`; `⌊n / 2q⌋ = (n · COMPRESS_M) >> 38` exactly for every `n < 2^25`.
+#[allow(clippy::cast_possible_truncation)]
+const COMPRESS_M: u32 = (((1u64 << 38) + 2 * Q as u64 - 1) / (2 * Q as u64)) as u32;
+
+
+#[allow(clippy::cast_possible_truncation)]
+const fn gen_shoup_table() -> [u16; 256] {
+    let zetas = gen_zeta_table();
+    let mut result = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        result[i] = (((zetas[i] as u32) << 16) / Q) as u16;
+        i += 1;
+    }
+    result
+}
+
+
+/// Loads eight coefficients.
+#[inline]
+fn load(lanes: &[Z256]) -> uint16x8_t {
+    assert!(lanes.len() >= 8);
+    // SAFETY: eight `Z256` (transparent over `u16`) are readable; `vld1q_u16` needs 2-byte alignment
+    unsafe { vld1q_u16(lanes.as_ptr().cast::<u16>()) }
+}
+
+
+/// Stores eight coefficients.
+#[inline]
+fn store(lanes: &mut [Z256], v: uint16x8_t) {
+    assert!(lanes.len() >= 8);
+    // SAFETY: eight `Z256` (transparent over `u16`) are writable; `vst1q_u16` needs 2-byte alignment
+    unsafe { vst1q_u16(lanes.as_mut_ptr().cast::<u16>(), v) }
+}
+
+
+/// Loads four accumulator words.
+#[inline]
+fn load_u32(lanes: &[u32]) -> uint32x4_t {
+    assert!(lanes.len() >= 4);
+    // SAFETY: four `u32` are readable
+    unsafe { vld1q_u32(lanes.as_ptr()) }
+}
+
+
+/// Stores four accumulator words.
+#[inline]
+fn store_u32(lanes: &mut [u32], v: uint32x4_t) {
+    assert!(lanes.len() >= 4);
+    // SAFETY: four `u32` are writable
+    unsafe { vst1q_u32(lanes.as_mut_ptr(), v) }
+}
+
+
+/// Maps each lane in `[0, 2q)` to `[0, q)`. When `x < q`, `x − q` wraps to a larger value.
+#[inline]
+fn reduce_once(x: uint16x8_t) -> uint16x8_t {
+    // SAFETY: NEON is always available on aarch64
+    unsafe { vminq_u16(x, vsubq_u16(x, vdupq_n_u16(Q16))) }
+}
+
+
+#[inline]
+fn add_mod(a: uint16x8_t, b: uint16x8_t) -> uint16x8_t {
+    // SAFETY: NEON is always available on aarch64
+    reduce_once(unsafe { vaddq_u16(a, b) })
+}
+
+
+#[inline]
+fn sub_mod(a: uint16x8_t, b: uint16x8_t) -> uint16x8_t {
+    // SAFETY: NEON is always available on aarch64
+    reduce_once(unsafe { vaddq_u16(a, vsubq_u16(vdupq_n_u16(Q16), b)) })
+}
+
+
+/// Lane-wise `b · ζ mod q` given `ζ` and its Shoup companion; `b` must be below `2^16`.
+#[inline]
+fn mul_shoup(b: uint16x8_t, zeta: uint16x8_t, zeta_shoup: uint16x8_t) -> uint16x8_t {
+    // SAFETY: NEON is always available on aarch64
+    unsafe {
+        let q = vdupq_n_u32(Q);
+        let quot = vshrq_n_u32::<16>(vmull_u16(vget_low_u16(b), vget_low_u16(zeta_shoup)));
+        let lo = vmlsq_u32(vmull_u16(vget_low_u16(b), vget_low_u16(zeta)), quot, q);
+        let quot = vshrq_n_u32::<16>(vmull_high_u16(b, zeta_shoup));
+        let hi = vmlsq_u32(vmull_high_u16(b, zeta), quot, q);
+        reduce_once(vmovn_high_u32(vmovn_u32(lo), hi)) // Both halves are in [0, 2q)
+    }
+}
+
+
+/// Per-lane zetas (and Shoup companions) for the final two layers, where one vector spans
+/// several butterfly groups; `ks[l]` is the `k` of the group that lane `l` belongs to.
+#[inline]
+fn zeta_lanes(ks: [usize; 8]) -> (uint16x8_t, uint16x8_t) {
+    let zeta = ks.map(|k| Z256(ZETA_TABLE[k << 1]));
+    let zeta_shoup = ks.map(|k| Z256(ZETA_SHOUP[k << 1]));
+    (load(&zeta), load(&zeta_shoup))
+}
+
+
+/// Splits two vectors holding four (4 lower, 4 upper) groups into the lower and upper halves.
+#[inline]
+fn split_4(v0: uint16x8_t, v1: uint16x8_t) -> (uint16x8_t, uint16x8_t) {
+    // SAFETY: NEON is always available on aarch64
+    unsafe {
+        let (v0, v1) = (vreinterpretq_u64_u16(v0), vreinterpretq_u64_u16(v1));
+        (
+            vreinterpretq_u16_u64(vzip1q_u64(v0, v1)),
+            vreinterpretq_u16_u64(vzip2q_u64(v0, v1)),
+        )
+    }
+}
+
+
+/// Splits two vectors holding four (2 lower, 2 upper) groups into the lower and upper halves.
+#[inline]
+fn split_2(v0: uint16x8_t, v1: uint16x8_t) -> (uint16x8_t, uint16x8_t) {
+    // SAFETY: NEON is always available on aarch64
+    unsafe {
+        let (v0, v1) = (vreinterpretq_u32_u16(v0), vreinterpretq_u32_u16(v1));
+        (
+            vreinterpretq_u16_u32(vuzp1q_u32(v0, v1)),
+            vreinterpretq_u16_u32(vuzp2q_u32(v0, v1)),
+        )
+    }
+}
+
+
+/// The inverse of `split_2()`.
+#[inline]
+fn join_2(lower: uint16x8_t, upper: uint16x8_t) -> (uint16x8_t, uint16x8_t) {
+    // SAFETY: NEON is always available on aarch64
+    unsafe {
+        let (lower, upper) = (vreinterpretq_u32_u16(lower), vreinterpretq_u32_u16(upper));
+        (
+            vreinterpretq_u16_u32(vzip1q_u32(lower, upper)),
+            vreinterpretq_u16_u32(vzip2q_u32(lower, upper)),
+        )
+    }
+}
+
+
+/// Algorithm 8 `NTT(f)` on page 22, vectorized.
+#[must_use]
+pub(crate) fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt);
+    count!(reductions, 7 * 128);
+    let mut f_hat = *array_f;
+    let mut k = 1;
+
+    // len ≥ 8: every butterfly group covers whole vectors and shares one zeta
+    for len in [128, 64, 32, 16, 8] {
+        for start in (0..256).step_by(2 * len) {
+            // SAFETY: NEON is always available on aarch64
+            let zeta = unsafe { vdupq_n_u16(ZETA_TABLE[k << 1]) };
+            let zeta_shoup = unsafe { vdupq_n_u16(ZETA_SHOUP[k << 1]) };
+            k += 1;
+            for j in (start..(start + len)).step_by(8) {
+                let t = mul_shoup(load(&f_hat[j + len..]), zeta, zeta_shoup);
+                let f_j = load(&f_hat[j..]);
+                store(&mut f_hat[j + len..], sub_mod(f_j, t));
+                store(&mut f_hat[j..], add_mod(f_j, t));
+            }
+        }
+    }
+
+    // len = 4: two vectors hold four groups of (4 lower, 4 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k, k, k + 1, k + 1, k + 1, k + 1]);
+        k += 2;
+        let (f_j, f_jl) = split_4(load(&f_hat[start..]), load(&f_hat[start + 8..]));
+        let t = mul_shoup(f_jl, zeta, zeta_shoup);
+        let (v0, v1) = split_4(add_mod(f_j, t), sub_mod(f_j, t)); // split_4 is its own inverse
+        store(&mut f_hat[start..], v0);
+        store(&mut f_hat[start + 8..], v1);
+    }
+
+    // len = 2: two vectors hold four groups of (2 lower, 2 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k + 1, k + 1, k + 2, k + 2, k + 3, k + 3]);
+        k += 4;
+        let (f_j, f_jl) = split_2(load(&f_hat[start..]), load(&f_hat[start + 8..]));
+        let t = mul_shoup(f_jl, zeta, zeta_shoup);
+        let (v0, v1) = join_2(add_mod(f_j, t), sub_mod(f_j, t));
+        store(&mut f_hat[start..], v0);
+        store(&mut f_hat[start + 8..], v1);
+    }
+
+    f_hat
+}
+
+
+/// Algorithm 9 `NTTinv(f)` on page 23, vectorized.
+#[must_use]
+pub(crate) fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt_inv);
+    count!(reductions, 7 * 128 + 256);
+    let mut f = *f_hat;
+    let mut k = 127;
+
+    // len = 2: two vectors hold four groups of (2 lower, 2 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k - 1, k - 1, k - 2, k - 2, k - 3, k - 3]);
+        k -= 4;
+        let (t, f_jl) = split_2(load(&f[start..]), load(&f[start + 8..]));
+        let (lower, upper) = (add_mod(t, f_jl), mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+        let (v0, v1) = join_2(lower, upper);
+        store(&mut f[start..], v0);
+        store(&mut f[start + 8..], v1);
+    }
+
+    // len = 4: two vectors hold four groups of (4 lower, 4 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k, k, k - 1, k - 1, k - 1, k - 1]);
+        k -= 2;
+        let (t, f_jl) = split_4(load(&f[start..]), load(&f[start + 8..]));
+        let (lower, upper) = (add_mod(t, f_jl), mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+        let (v0, v1) = split_4(lower, upper);
+        store(&mut f[start..], v0);
+        store(&mut f[start + 8..], v1);
+    }
+
+    // len ≥ 8: every butterfly group covers whole vectors and shares one zeta
+    for len in [8, 16, 32, 64, 128] {
+        for start in (0..256).step_by(2 * len) {
+            // SAFETY: NEON is always available on aarch64
+            let zeta = unsafe { vdupq_n_u16(ZETA_TABLE[k << 1]) };
+            let zeta_shoup = unsafe { vdupq_n_u16(ZETA_SHOUP[k << 1]) };
+            k -= 1;
+            for j in (start..(start + len)).step_by(8) {
+                let t = load(&f[j..]);
+                let f_jl = load(&f[j + len..]);
+                store(&mut f[j..], add_mod(t, f_jl));
+                store(&mut f[j + len..], mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+            }
+        }
+    }
+
+    // f ← f · 3303 mod q
+    // SAFETY: NEON is always available on aarch64
+    let (scale, scale_shoup) = unsafe { (vdupq_n_u16(F), vdupq_n_u16(F_SHOUP)) };
+    for j in (0..256).step_by(8) {
+        let f_j = load(&f[j..]);
+        store(&mut f[j..], mul_shoup(f_j, scale, scale_shoup));
+    }
+
+    f
+}
+
+
+/// Vectorized `multiply_ntts_acc()`: eight degree-one products per iteration, with the even and
+/// odd coefficients split apart on load and re-interleaved into the accumulator.
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut [u32; 256], f_hat: &[Z256; 256], g_hat: &[Z256; 256], g_cache: &[Z256; 128],
+) {
+    for i in (0..128).step_by(8) {
+        let (f0, f1) = (load(&f_hat[2 * i..]), load(&f_hat[2 * i + 8..]));
+        let (g0, g1) = (load(&g_hat[2 * i..]), load(&g_hat[2 * i + 8..]));
+        let b1_gamma = load(&g_cache[i..]);
+        // SAFETY: NEON is always available on aarch64
+        let products = unsafe {
+            let (a0, a1) = (vuzp1q_u16(f0, f1), vuzp2q_u16(f0, f1));
+            let (b0, b1) = (vuzp1q_u16(g0, g1), vuzp2q_u16(g0, g1));
+            let (a0_lo, a1_lo) = (vget_low_u16(a0), vget_low_u16(a1));
+            let (b0_lo, b1_lo) = (vget_low_u16(b0), vget_low_u16(b1));
+
+            // c0 ← a0 · b0 + a1 · b1 · γ   and   c1 ← a0 · b1 + a1 · b0   (reduction deferred)
+            let c0_lo = vmlal_u16(vmull_u16(a0_lo, b0_lo), a1_lo, vget_low_u16(b1_gamma));
+            let c0_hi = vmlal_high_u16(vmull_high_u16(a0, b0), a1, b1_gamma);
+            let c1_lo = vmlal_u16(vmull_u16(a0_lo, b1_lo), a1_lo, b0_lo);
+            let c1_hi = vmlal_high_u16(vmull_high_u16(a0, b1), a1, b0);
+            [
+                vzip1q_u32(c0_lo, c1_lo),
+                vzip2q_u32(c0_lo, c1_lo),
+                vzip1q_u32(c0_hi, c1_hi),
+                vzip2q_u32(c0_hi, c1_hi),
+            ]
+        };
+        for (n, product) in products.into_iter().enumerate() {
+            let lanes = &mut acc[2 * i + 4 * n..];
+            // SAFETY: NEON is always available on aarch64
+            store_u32(lanes, unsafe { vaddq_u32(load_u32(lanes), product) });
+        }
+    }
+}
+
+
+/// Barrett reduction of four wide accumulator words to `[0, q)`, as in `Z256::reduce_u32()`.
+#[inline]
+fn barrett_u32(wide: uint32x4_t) -> uint32x4_t {
+    // SAFETY: NEON is always available on aarch64
+    unsafe {
+        let m = vdupq_n_u32(BARRETT_M);
+        let quot = vshrn_n_u64::<32>(vmull_u32(vget_low_u32(wide), vget_low_u32(m)));
+        let quot = vshrn_high_n_u64::<32>(quot, vmull_high_u32(wide, m));
+        let rem = vmlsq_u32(wide, quot, vdupq_n_u32(Q)); // In [0, 2q)
+        vminq_u32(rem, vsubq_u32(rem, vdupq_n_u32(Q)))
+    }
+}
+
+
+/// Vectorized `reduce_acc()`: Barrett-reduces a wide accumulator once per coefficient.
+#[must_use]
+pub(crate) fn reduce_acc(acc: &[u32; 256]) -> [Z256; 256] {
+    count!(reductions, 256);
+    let mut h_hat = [Z256(0); 256];
+    for i in (0..256).step_by(8) {
+        let (lo, hi) = (barrett_u32(load_u32(&acc[i..])), barrett_u32(load_u32(&acc[i + 4..])));
+        // SAFETY: NEON is always available on aarch64
+        store(&mut h_hat[i..], unsafe { vmovn_high_u32(vmovn_u32(lo), hi) });
+    }
+    h_hat
+}
+
+
+/// Compress<d> from page 18 (4.5), vectorized.
+/// x → ⌈(2^d/q) · x⌋, computed as `⌊(2^{d+1} · x + q) / 2q⌋` with a multiply-high by the
+/// reciprocal of `2q`. Like the scalar code, the result is not reduced mod `2^d`.
+pub(crate) fn compress(d: u32, inout: &mut [Z256]) {
+    let mut chunks = inout.chunks_exact_mut(8);
+    for chunk in &mut chunks {
+        // SAFETY: NEON is always available on aarch64
+        let compressed = unsafe {
+            let coeffs = load(chunk);
+            let shift = vdupq_n_s32(i32::try_from(d + 1).unwrap());
+            let recip = vdupq_n_u32(COMPRESS_M);
+            let halves = [vmovl_u16(vget_low_u16(coeffs)), vmovl_high_u16(coeffs)].map(|half| {
+                let num = vaddq_u32(vshlq_u32(half, shift), vdupq_n_u32(Q));
+                let lo = vshrq_n_u64::<38>(vmull_u32(vget_low_u32(num), vget_low_u32(recip)));
+                let hi = vshrq_n_u64::<38>(vmull_high_u32(num, recip));
+                vuzp1q_u32(vreinterpretq_u32_u64(lo), vreinterpretq_u32_u64(hi))
+            });
+            vmovn_high_u32(vmovn_u32(halves[0]), halves[1])
+        };
+        store(chunk, compressed);
+    }
+    let remainder = chunks.into_remainder();
+    if !remainder.is_empty() {
+        crate::helpers::compress(d, remainder);
+    }
+}
+
+
+/// Maps signed 16-bit lanes in `(−q, q)` to `[0, q)`.
+#[inline]
+fn to_z256(c: int16x8_t) -> uint16x8_t {
+    // SAFETY: NEON is always available on aarch64
+    unsafe {
+        let q = vdupq_n_s16(i16::try_from(Q).unwrap());
+        vreinterpretq_u16_s16(vaddq_s16(c, vandq_s16(vshrq_n_s16::<15>(c), q)))
+    }
+}
+
+
+/// Algorithm 7 `SamplePolyCBDη(B)` on page 20, vectorized for η = 2 and η = 3.
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn sample_poly_cbd(eta: u32, byte_array_b: &[u8]) -> Result<[Z256; 256], &'static str> {
+    match eta {
+        2 => {
+            ensure!(byte_array_b.len() == 128, "Alg7: bytes len not 64 * eta");
+            Ok(cbd2(byte_array_b))
+        }
+        3 => {
+            ensure!(byte_array_b.len() == 192, "Alg7: bytes len not 64 * eta");
+            Ok(cbd3(byte_array_b))
+        }
+        _ => sampling::sample_poly_cbd(eta, byte_array_b),
+    }
+}
+
+
+/// η = 2: each byte holds two coefficients as (x, y) pairs of 2-bit fields.
+fn cbd2(byte_array_b: &[u8]) -> [Z256; 256] {
+    let mut array_f = [Z256(0); 256];
+    for (c, bytes) in byte_array_b.chunks_exact(16).enumerate() {
+        // SAFETY: `bytes` is exactly 16 readable bytes; NEON is always available on aarch64
+        let coeffs = unsafe {
+            let v = vld1q_u8(bytes.as_ptr());
+            let (m55, m3) = (vdupq_n_u8(0x55), vdupq_n_u8(0x03));
+            // Count the set bits in each 2-bit field
+            let t = vaddq_u8(vandq_u8(v, m55), vandq_u8(vshrq_n_u8::<1>(v), m55));
+            // Coefficient 2i from the low nibble of byte i, 2i + 1 from the high nibble
+            let lo = vsubq_u8(vandq_u8(t, m3), vandq_u8(vshrq_n_u8::<2>(t), m3));
+            let hi = vsubq_u8(vandq_u8(vshrq_n_u8::<4>(t), m3), vshrq_n_u8::<6>(t));
+            let (c0, c1) =
+                (vreinterpretq_s8_u8(vzip1q_u8(lo, hi)), vreinterpretq_s8_u8(vzip2q_u8(lo, hi)));
+            [
+                vmovl_s8(vget_low_s8(c0)),
+                vmovl_high_s8(c0),
+                vmovl_s8(vget_low_s8(c1)),
+                vmovl_high_s8(c1),
+            ]
+        };
+        for (n, coeff) in coeffs.into_iter().enumerate() {
+            store(&mut array_f[32 * c + 8 * n..], to_z256(coeff));
+        }
+    }
+    array_f
+}
+
+
+/// η = 3: each 3-byte group holds four coefficients as (x, y) pairs of 3-bit fields.
+fn cbd3(byte_array_b: &[u8]) -> [Z256; 256] {
+    // Lane i takes bytes 3i, 3i + 1 and 3i + 2; out-of-range table indices produce zero
+    const IDX: [u8; 16] = [0, 1, 2, 0xFF, 3, 4, 5, 0xFF, 6, 7, 8, 0xFF, 9, 10, 11, 0xFF];
+    let mut array_f = [Z256(0); 256];
+    let mut buf = [0u8; 16];
+    for (c, bytes) in byte_array_b.chunks_exact(12).enumerate() {
+        buf[0..12].copy_from_slice(bytes);
+        // SAFETY: `buf` and `IDX` are 16 readable bytes; NEON is always available on aarch64
+        let coeffs = unsafe {
+            let v =
+                vreinterpretq_u32_u8(vqtbl1q_u8(vld1q_u8(buf.as_ptr()), vld1q_u8(IDX.as_ptr())));
+            // Count the set bits in each 3-bit field
+            let m249 = vdupq_n_u32(0x0024_9249);
+            let t = vaddq_u32(
+                vaddq_u32(vandq_u32(v, m249), vandq_u32(vshrq_n_u32::<1>(v), m249)),
+                vandq_u32(vshrq_n_u32::<2>(v), m249),
+            );
+            // cj[i] is coefficient 4i + j
+            let cj = [0, 1, 2, 3].map(|j| {
+                let m7 = vdupq_n_u32(0x07);
+                let x = vandq_u32(vshlq_u32(t, vdupq_n_s32(-6 * j)), m7);
+                let y = vandq_u32(vshlq_u32(t, vdupq_n_s32(-6 * j - 3)), m7);
+                vsubq_u32(x, y)
+            });
+            // Transpose so that the four coefficients from each lane are adjacent
+            let (t0, t1) = (vzip1q_u32(cj[0], cj[1]), vzip2q_u32(cj[0], cj[1]));
+            let (t2, t3) = (vzip1q_u32(cj[2], cj[3]), vzip2q_u32(cj[2], cj[3]));
+            let (t0, t1) = (vreinterpretq_u64_u32(t0), vreinterpretq_u64_u32(t1));
+            let (t2, t3) = (vreinterpretq_u64_u32(t2), vreinterpretq_u64_u32(t3));
+            let o = [
+                vzip1q_u64(t0, t2),
+                vzip2q_u64(t0, t2),
+                vzip1q_u64(t1, t3),
+                vzip2q_u64(t1, t3),
+            ]
+            .map(|o| vreinterpretq_s32_u32(vreinterpretq_u32_u64(o)));
+            [
+                vmovn_high_s32(vmovn_s32(o[0]), o[1]),
+                vmovn_high_s32(vmovn_s32(o[2]), o[3]),
+            ]
+        };
+        store(&mut array_f[16 * c..], to_z256(coeffs[0]));
+        store(&mut array_f[16 * c + 8..], to_z256(coeffs[1]));
+    }
+    array_f
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, RngCore, SeedableRng};
+
+    use crate::ntt::mul_cache;
+    use crate::types::Z256;
+    use crate::{helpers, neon, ntt, sampling};
+
+    fn random_poly(rng: &mut impl Rng) -> [Z256; 256] {
+        core::array::from_fn(|_| Z256(rng.gen_range(0..3329)))
+    }
+
+    fn same(a: &[Z256], b: &[Z256]) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.get_u16() == y.get_u16())
+    }
+
+    #[test]
+    fn test_neon_matches_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(64);
+        for _i in 0..100 {
+            let (f, g) = (random_poly(&mut rng), random_poly(&mut rng));
+            assert!(same(&neon::ntt(&f), &ntt::ntt(&f)));
+            assert!(same(&neon::ntt_inv(&f), &ntt::ntt_inv(&f)));
+
+            let (mut acc_neon, mut acc_scalar) = ([0u32; 256], [0u32; 256]);
+            for _j in 0..4 {
+                neon::multiply_ntts_acc(&mut acc_neon, &f, &g, &mul_cache(&g));
+                ntt::multiply_ntts_acc(&mut acc_scalar, &f, &g, &mul_cache(&g));
+            }
+            assert_eq!(acc_neon, acc_scalar);
+            assert!(same(&neon::reduce_acc(&acc_neon), &ntt::reduce_acc(&acc_scalar)));
+
+            for d in [1, 4, 5, 10, 11] {
+                let (mut x_neon, mut x_scalar) = (f, f);
+                neon::compress(d, &mut x_neon);
+                helpers::compress(d, &mut x_scalar);
+                assert!(same(&x_neon, &x_scalar));
+            }
+
+            for eta in [2, 3] {
+                let mut bytes = [0u8; 192];
+                rng.fill_bytes(&mut bytes);
+                let bytes = &bytes[0..64 * eta as usize];
+                assert!(same(
+                    &neon::sample_poly_cbd(eta, bytes).unwrap(),
+                    &sampling::sample_poly_cbd(eta, bytes).unwrap()
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_neon_compress_exhaustive() {
+        for d in [1, 4, 5, 10, 11] {
+            let (mut x_neon, mut x_scalar) = ([Z256(0); 3336], [Z256(0); 3336]);
+            for (i, (a, b)) in x_neon
+                .iter_mut()
+                .zip(x_scalar.iter_mut())
+                .enumerate()
+                .take(3329)
+            {
+                let x = u16::try_from(i).unwrap();
+                (*a, *b) = (Z256(x), Z256(x));
+            }
+            neon::compress(d, &mut x_neon);
+            helpers::compress(d, &mut x_scalar);
+            assert!(same(&x_neon, &x_scalar));
+        }
+    }
+}
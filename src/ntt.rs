@@ -1,3 +1,4 @@
+use crate::helpers::count;
 use crate::types::Z256;
 use crate::{Q, ZETA};
 
@@ -5,9 +6,21 @@ use crate::{Q, ZETA};
 /// Computes the NTT representation `f_hat` of the given polynomial f ∈ `R_q`.
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
+#[cfg_attr(
+    any(
+        feature = "portable-simd",
+        all(target_arch = "aarch64", target_feature = "neon"),
+        all(target_arch = "riscv64", target_feature = "v"),
+        all(target_arch = "wasm32", target_feature = "simd128"),
+        all(feature = "armv7em-asm", target_arch = "arm")
+    ),
+    allow(dead_code)
+)]
 pub fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
     // Input: array f ∈ Z^{256}_q           ▷ the coeffcients of the input polynomial
     // Output: array f_hat ∈ Z^{256}_q      ▷ the coeffcients of the NTT of the input polynomial
+    count!(ntt);
+
     // 1: f_hat ← f                         ▷ will compute NTT in-place on a copy of input array
     let mut f_hat = [Z256(0); 256];
     f_hat.copy_from_slice(array_f);
@@ -52,9 +65,20 @@ pub fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
 /// Computes the polynomial f ∈ `R_q` corresponding to the given NTT representation `f_hat` ∈ `T_q`.
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
+#[cfg_attr(
+    any(
+        feature = "portable-simd",
+        all(target_arch = "aarch64", target_feature = "neon"),
+        all(target_arch = "riscv64", target_feature = "v"),
+        all(target_arch = "wasm32", target_feature = "simd128"),
+        all(feature = "armv7em-asm", target_arch = "arm")
+    ),
+    allow(dead_code)
+)]
 pub fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
     // Input: array f_hat ∈ Z^{256}     ▷ the coeffcients of input NTT representation
     // Output: array f ∈ Z^{256}        ▷ the coeffcients of the inverse-NTT of the input
+    count!(ntt_inv);
 
     // 1: f ← f_hat                     ▷ will compute in-place on a copy of input array
     let mut f: [Z256; 256] = [Z256(0); 256];
@@ -101,8 +125,11 @@ pub fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
 
 /// Algorithm 10 `MultiplyNTTs(f, g)` on page 24.
 /// Computes the product (in the ring Tq ) of two NTT representations.
+///
+/// Retained as the reference for the fused kernels below, which are used in the matrix-vector
+/// products instead.
 #[must_use]
-#[allow(clippy::cast_possible_truncation)]
+#[allow(dead_code, clippy::cast_possible_truncation)]
 pub fn multiply_ntts(f_hat: &[Z256; 256], g_hat: &[Z256; 256]) -> [Z256; 256] {
     // Input: Two arrays f_hat ∈ Z^{256}_q and g_hat ∈ Z^{256}_q        ▷ the coeffcients of two NTT representations
     // Output: An array h_hat ∈ Z^{256}_q                               ▷ the coeffcients of the product of the inputs
@@ -130,6 +157,7 @@ pub fn multiply_ntts(f_hat: &[Z256; 256], g_hat: &[Z256; 256]) -> [Z256; 256] {
 /// Algorithm 11 `BaseCaseMultiply(a0, a1, b0, b1, gamma)` on page 24.
 /// Computes the product of two degree-one polynomials with respect to a quadratic modulus.
 #[must_use]
+#[allow(dead_code)]
 pub fn base_case_multiply(a0: Z256, a1: Z256, b0: Z256, b1: Z256, gamma: Z256) -> (Z256, Z256) {
     // Input: a0 , a1 , b0 , b1 ∈ Z_q               ▷ the coefficients of a0 + a1 X and b0 + b1 X
     // Input: γ ∈ Z_q                               ▷ the modulus is X^2 − γ
@@ -145,6 +173,71 @@ pub fn base_case_multiply(a0: Z256, a1: Z256, b0: Z256, b1: Z256, gamma: Z256) -
 }
 
 
+/// Precomputes `b1 · γ` for each of the 128 degree-one factors of `g_hat` (a "mulcache"). When the
+/// same operand, such as `s_hat` or `r_hat`, is multiplied by several polynomials, this removes
+/// one of the five multiplications in every subsequent `BaseCaseMultiply`.
+#[must_use]
+pub(crate) fn mul_cache(g_hat: &[Z256; 256]) -> [Z256; 128] {
+    let mut g_cache = [Z256(0); 128];
+    for i in 0..128 {
+        g_cache[i] = g_hat[2 * i + 1].mul(Z256(ZETA_TABLE[i ^ 0x80]));
+    }
+    g_cache
+}
+
+
+/// Fused multiply-accumulate variant of Algorithm 10 `MultiplyNTTs(f, g)` on page 24.
+/// Adds the (unreduced) product of `f_hat` and `g_hat` into the wide accumulator `acc`, using the
+/// precomputed `g_cache` from `mul_cache()` for the `b1 · γ` terms. Each call adds less than
+/// `2 · q^2 < 2^25` per coefficient, so well over 100 products can be summed before `reduce_acc()`.
+#[cfg_attr(
+    any(
+        feature = "portable-simd",
+        all(target_arch = "aarch64", target_feature = "neon"),
+        all(target_arch = "riscv64", target_feature = "v"),
+        all(target_arch = "wasm32", target_feature = "simd128"),
+        all(feature = "armv7em-asm", target_arch = "arm")
+    ),
+    allow(dead_code)
+)]
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut [u32; 256], f_hat: &[Z256; 256], g_hat: &[Z256; 256], g_cache: &[Z256; 128],
+) {
+    for i in 0..128 {
+        let a0 = u32::from(f_hat[2 * i].get_u16());
+        let a1 = u32::from(f_hat[2 * i + 1].get_u16());
+        let b0 = u32::from(g_hat[2 * i].get_u16());
+        let b1 = u32::from(g_hat[2 * i + 1].get_u16());
+        let b1_gamma = u32::from(g_cache[i].get_u16());
+
+        // c0 ← a0 · b0 + a1 · b1 · γ   and   c1 ← a0 · b1 + a1 · b0   (reduction deferred)
+        acc[2 * i] += a0 * b0 + a1 * b1_gamma;
+        acc[2 * i + 1] += a0 * b1 + a1 * b0;
+    }
+}
+
+
+/// Reduces a wide accumulator from `multiply_ntts_acc()` once per coefficient.
+#[must_use]
+#[cfg_attr(
+    all(
+        not(feature = "portable-simd"),
+        any(
+            all(target_arch = "aarch64", target_feature = "neon"),
+            all(feature = "armv7em-asm", target_arch = "arm")
+        )
+    ),
+    allow(dead_code)
+)]
+pub(crate) fn reduce_acc(acc: &[u32; 256]) -> [Z256; 256] {
+    let mut h_hat = [Z256(0); 256];
+    for i in 0..256 {
+        h_hat[i] = Z256::reduce_u32(acc[i]);
+    }
+    h_hat
+}
+
+
 /// HAC Algorithm 14.76 Right-to-left binary exponentiation mod Q.
 #[must_use]
 #[allow(clippy::cast_possible_truncation)]
@@ -166,9 +259,8 @@ const fn pow_mod_q(g: u32, e: u8) -> u16 {
     result as u16
 }
 
-#[allow(dead_code)]
 #[allow(clippy::cast_possible_truncation)]
-const fn gen_zeta_table() -> [u16; 256] {
+pub(crate) const fn gen_zeta_table() -> [u16; 256] {
     let mut result = [0u16; 256];
     let mut i = 0;
     while i < 256u16 {
@@ -180,3 +272,39 @@ const fn gen_zeta_table() -> [u16; 256] {
 
 #[allow(dead_code)]
 pub(crate) static ZETA_TABLE: [u16; 256] = gen_zeta_table();
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use crate::ntt::{mul_cache, multiply_ntts, multiply_ntts_acc, reduce_acc};
+    use crate::types::Z256;
+
+    #[test]
+    fn test_fused_multiply_accumulate() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+        for _i in 0..100 {
+            let mut f_hats = [[Z256(0); 256]; 4];
+            let mut g_hats = [[Z256(0); 256]; 4];
+            for (f_hat, g_hat) in f_hats.iter_mut().zip(g_hats.iter_mut()) {
+                *f_hat = core::array::from_fn(|_| Z256(rng.gen_range(0..3329)));
+                *g_hat = core::array::from_fn(|_| Z256(rng.gen_range(0..3329)));
+            }
+            let mut expected = [Z256(0); 256];
+            let mut acc = [0u32; 256];
+            for j in 0..4 {
+                let product = multiply_ntts(&f_hats[j], &g_hats[j]);
+                for k in 0..256 {
+                    expected[k] = expected[k].add(product[k]);
+                }
+                multiply_ntts_acc(&mut acc, &f_hats[j], &g_hats[j], &mul_cache(&g_hats[j]));
+            }
+            let actual = reduce_acc(&acc);
+            assert!(expected
+                .iter()
+                .zip(actual.iter())
+                .all(|(e, a)| e.get_u16() == a.get_u16()));
+        }
+    }
+}
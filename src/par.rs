@@ -0,0 +1,31 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand_core::CryptoRngCore;
+use rayon::prelude::*;
+use zeroize::Zeroize;
+
+/// Runs `keygen` `n` times in parallel. Each invocation gets its own random number generator,
+/// built by `rng_factory` from a 32-byte seed drawn (sequentially) from the master `rng`. As no
+/// generator is shared between threads and the seeds do not depend on scheduling, the output is
+/// deterministic for a given master `rng` state.
+pub(crate) fn par_keygen<EK: Send, DK: Send, R: CryptoRngCore>(
+    n: usize, rng: &mut impl CryptoRngCore, rng_factory: impl Fn([u8; 32]) -> R + Sync,
+    keygen: impl Fn(&mut R) -> Result<(EK, DK), &'static str> + Sync,
+) -> Result<Vec<(EK, DK)>, &'static str> {
+    let mut seeds = vec![[0u8; 32]; n];
+    for seed in &mut seeds {
+        rng.try_fill_bytes(seed)
+            .map_err(|_| "par_keygen: random number generator failed")?;
+    }
+    let result = seeds
+        .par_iter_mut()
+        .map(|seed| {
+            let mut item_rng = rng_factory(*seed);
+            seed.zeroize();
+            keygen(&mut item_rng)
+        })
+        .collect();
+    seeds.zeroize(); // Covers any seeds left unused after an error
+    result
+}
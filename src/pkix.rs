@@ -0,0 +1,56 @@
+use spki::der::asn1::BitStringRef;
+use spki::{AlgorithmIdentifierRef, Document, ObjectIdentifier, SubjectPublicKeyInfoRef};
+
+#[cfg(feature = "pkcs8-encryption")]
+mod encrypted;
+#[cfg(feature = "pkcs8")]
+mod private_key;
+
+#[cfg(feature = "pkcs8-encryption")]
+pub use encrypted::{decrypt_private_key, encrypt_private_key, PasswordCipher, PasswordKdf};
+#[cfg(feature = "pkcs8")]
+pub use private_key::PrivateKeyForm;
+#[cfg(feature = "pkcs8")]
+pub(crate) use private_key::{decode_private_key, encode_private_key};
+
+// X.509 (RFC 5280 `SubjectPublicKeyInfo`) and, with the `pkcs8` feature, PKCS#8 encodings of
+// ML-KEM keys per draft-ietf-lamps-kyber-certificates. The per-parameter-set `functionality!()`
+// code in `lib.rs` supplies the OID and the key conversions to the functions here.
+
+/// id-alg-ml-kem-512 from the NIST Computer Security Objects Register.
+#[cfg(feature = "ml-kem-512")]
+pub(crate) const ID_ALG_ML_KEM_512: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.1");
+
+/// id-alg-ml-kem-768 from the NIST Computer Security Objects Register.
+#[cfg(feature = "ml-kem-768")]
+pub(crate) const ID_ALG_ML_KEM_768: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.2");
+
+/// id-alg-ml-kem-1024 from the NIST Computer Security Objects Register.
+#[cfg(feature = "ml-kem-1024")]
+pub(crate) const ID_ALG_ML_KEM_1024: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.4.3");
+
+
+/// Encodes a `SubjectPublicKeyInfo` whose `subjectPublicKey` BIT STRING is the raw `ek`, with
+/// absent algorithm parameters.
+pub(crate) fn encode_public_key(oid: ObjectIdentifier, ek: &[u8]) -> spki::Result<Document> {
+    let algorithm = AlgorithmIdentifierRef { oid, parameters: None };
+    let subject_public_key = BitStringRef::from_bytes(ek)?;
+    Document::try_from(SubjectPublicKeyInfoRef { algorithm, subject_public_key })
+}
+
+
+/// Returns the raw `ek` of a `SubjectPublicKeyInfo` for `oid`, which the caller still validates.
+pub(crate) fn decode_public_key<'a>(
+    oid: ObjectIdentifier, spki: &SubjectPublicKeyInfoRef<'a>,
+) -> spki::Result<&'a [u8]> {
+    spki.algorithm.assert_algorithm_oid(oid)?;
+    if spki.algorithm.parameters.is_some() {
+        return Err(spki::Error::KeyMalformed);
+    }
+    spki.subject_public_key
+        .as_bytes()
+        .ok_or(spki::Error::KeyMalformed)
+}
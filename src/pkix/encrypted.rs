@@ -0,0 +1,241 @@
+use aes_gcm::aead::AeadInPlace;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce, Tag};
+use der::asn1::{AnyRef, ObjectIdentifier, OctetStringRef};
+use der::{Decode, Encode, Sequence};
+use pkcs8::pkcs5::pbes2::{self, Kdf, Pbkdf2Params, Pbkdf2Prf, ScryptParams};
+use pkcs8::pkcs5::scrypt;
+use pkcs8::{AlgorithmIdentifierRef, EncryptedPrivateKeyInfo, PrivateKeyInfo, SecretDocument};
+use rand_core::CryptoRngCore;
+use zeroize::Zeroizing;
+
+// Password-based encryption of PKCS#8 documents (RFC 5958 `EncryptedPrivateKeyInfo`) with PBES2
+// (RFC 8018). AES-256-CBC is handled by the `pkcs5` crate and interoperates with e.g. `openssl
+// pkcs8 -topk8 -v2 aes-256-cbc`. `pkcs5` does not support AEAD ciphers, so AES-256-GCM with the
+// RFC 5084 `GCMParameters` is implemented here on top of the same KDF encodings. (OpenSSL also
+// refuses AEAD ciphers in PKCS#8.) The plaintext `OneAsymmetricKey` is only ever held in a
+// `SecretDocument` or `Zeroizing` buffer.
+
+/// aes256-GCM from RFC 5084.
+const AES_256_GCM_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.1.46");
+const SALT_LEN: usize = 16;
+const GCM_NONCE_LEN: usize = 12;
+const GCM_TAG_LEN: usize = 16;
+
+
+/// The password-based key derivation function for an encrypted PKCS#8 document (available with
+/// the `pkcs8-encryption` feature).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasswordKdf {
+    /// PBKDF2 with HMAC-SHA256 and the given iteration count.
+    Pbkdf2Sha256 {
+        /// Iteration count; e.g. 600,000 or more.
+        iterations: u32,
+    },
+    /// scrypt with CPU/memory cost `2^log_n`, block size `r` and parallelization `p`.
+    Scrypt {
+        /// Base-2 logarithm of the cost parameter N; e.g. 17.
+        log_n: u8,
+        /// Block size; e.g. 8.
+        r: u32,
+        /// Parallelization; e.g. 1.
+        p: u32,
+    },
+}
+
+/// The cipher for an encrypted PKCS#8 document (available with the `pkcs8-encryption` feature).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PasswordCipher {
+    /// AES-256-CBC, as widely supported (including by OpenSSL).
+    Aes256Cbc,
+    /// AES-256-GCM (RFC 5084) with a 16-byte tag, which also authenticates the document.
+    Aes256Gcm,
+}
+
+
+#[derive(Sequence)]
+struct EncryptedPrivateKeyInfoRef<'a> {
+    encryption_algorithm: AlgorithmIdentifierRef<'a>,
+    encrypted_data: OctetStringRef<'a>,
+}
+
+#[derive(Sequence)]
+struct Pbes2ParamsRef<'a> {
+    key_derivation_func: Kdf<'a>,
+    encryption_scheme: AlgorithmIdentifierRef<'a>,
+}
+
+#[derive(Sequence)]
+struct GcmParamsRef<'a> {
+    nonce: OctetStringRef<'a>,
+    icv_len: u8, // DEFAULT 12, but always 16 here
+}
+
+
+/// Encrypts the PKCS#8 `document` under `password`, drawing the salt and IV (or nonce) from `rng`,
+/// and returns the DER `EncryptedPrivateKeyInfo` (available with the `pkcs8-encryption`
+/// feature). Combine with `KG::seed_to_pkcs8_der()` or `DecapsKey::to_pkcs8_der()` to choose
+/// the private key form, and with `to_pem("ENCRYPTED PRIVATE KEY", ..)` for PEM.
+/// # Errors
+/// Returns an error when `document` is not a PKCS#8 document, the KDF parameters are invalid or
+/// the random number generator fails.
+pub fn encrypt_private_key(
+    rng: &mut impl CryptoRngCore, password: impl AsRef<[u8]>, kdf: PasswordKdf,
+    cipher: PasswordCipher, document: &SecretDocument,
+) -> pkcs8::Result<SecretDocument> {
+    let private_key_info: PrivateKeyInfo<'_> = document.decode_msg()?;
+    let mut salt = [0u8; SALT_LEN];
+    fill(rng, &mut salt)?;
+
+    match cipher {
+        PasswordCipher::Aes256Cbc => {
+            let mut iv = [0u8; 16];
+            fill(rng, &mut iv)?;
+            let params = match kdf {
+                PasswordKdf::Pbkdf2Sha256 { iterations } => {
+                    pbes2::Parameters::pbkdf2_sha256_aes256cbc(iterations, &salt, &iv)?
+                }
+                PasswordKdf::Scrypt { log_n, r, p } => {
+                    pbes2::Parameters::scrypt_aes256cbc(scrypt_params(log_n, r, p)?, &salt, &iv)?
+                }
+            };
+            private_key_info.encrypt_with_params(params, password)
+        }
+        PasswordCipher::Aes256Gcm => {
+            let mut nonce = [0u8; GCM_NONCE_LEN];
+            fill(rng, &mut nonce)?;
+            let key_derivation_func = match kdf {
+                PasswordKdf::Pbkdf2Sha256 { iterations } => {
+                    Kdf::from(Pbkdf2Params::hmac_with_sha256(iterations, &salt)?)
+                }
+                PasswordKdf::Scrypt { log_n, r, p } => Kdf::from(
+                    ScryptParams::from_params_and_salt(scrypt_params(log_n, r, p)?, &salt)?,
+                ),
+            };
+            let key = derive_key(&key_derivation_func, password.as_ref())?;
+
+            // The plaintext is encrypted in place, so this buffer holds ciphertext once encrypted
+            let mut buffer = Zeroizing::new(document.as_bytes().to_vec());
+            let tag = Aes256Gcm::new(key.as_ref().into())
+                .encrypt_in_place_detached(Nonce::from_slice(&nonce), b"", &mut buffer)
+                .map_err(|_| {
+                    pkcs8::Error::EncryptedPrivateKey(pkcs8::pkcs5::Error::EncryptFailed)
+                })?;
+            buffer.extend_from_slice(&tag);
+
+            let gcm_params = GcmParamsRef {
+                nonce: OctetStringRef::new(&nonce)?,
+                icv_len: GCM_TAG_LEN as u8,
+            }
+            .to_der()?;
+            let pbes2_params = Pbes2ParamsRef {
+                key_derivation_func,
+                encryption_scheme: AlgorithmIdentifierRef {
+                    oid: AES_256_GCM_OID,
+                    parameters: Some(AnyRef::try_from(gcm_params.as_slice())?),
+                },
+            }
+            .to_der()?;
+            let encrypted = EncryptedPrivateKeyInfoRef {
+                encryption_algorithm: AlgorithmIdentifierRef {
+                    oid: pbes2::PBES2_OID,
+                    parameters: Some(AnyRef::try_from(pbes2_params.as_slice())?),
+                },
+                encrypted_data: OctetStringRef::new(&buffer)?,
+            };
+            Ok(SecretDocument::encode_msg(&encrypted)?)
+        }
+    }
+}
+
+
+/// Decrypts a DER `EncryptedPrivateKeyInfo` produced by `encrypt_private_key()` or another
+/// PBES2 implementation (e.g. OpenSSL) and returns the PKCS#8 document, for
+/// `DecapsKey::from_pkcs8_der()` (available with the `pkcs8-encryption` feature).
+/// # Errors
+/// Returns an error on a wrong password, an unsupported scheme or a malformed document.
+pub fn decrypt_private_key(
+    password: impl AsRef<[u8]>, encrypted: &[u8],
+) -> pkcs8::Result<SecretDocument> {
+    let encrypted_info = EncryptedPrivateKeyInfoRef::from_der(encrypted)?;
+    let gcm_scheme = match encrypted_info.encryption_algorithm.parameters {
+        Some(params) if encrypted_info.encryption_algorithm.oid == pbes2::PBES2_OID => {
+            let params: Pbes2ParamsRef<'_> = params.decode_as()?;
+            (params.encryption_scheme.oid == AES_256_GCM_OID).then_some(params)
+        }
+        _ => None,
+    };
+    let Some(params) = gcm_scheme else {
+        // AES-CBC (and anything else `pkcs5` supports)
+        return EncryptedPrivateKeyInfo::try_from(encrypted)?.decrypt(password);
+    };
+
+    let gcm_params: GcmParamsRef<'_> = params
+        .encryption_scheme
+        .parameters
+        .ok_or(pkcs8::Error::KeyMalformed)?
+        .decode_as()?;
+    let encrypted_data = encrypted_info.encrypted_data.as_bytes();
+    let (ciphertext, tag) =
+        encrypted_data.split_at(encrypted_data.len().saturating_sub(GCM_TAG_LEN));
+    if gcm_params.nonce.as_bytes().len() != GCM_NONCE_LEN
+        || usize::from(gcm_params.icv_len) != GCM_TAG_LEN
+        || tag.len() != GCM_TAG_LEN
+    {
+        return Err(pkcs8::Error::KeyMalformed);
+    }
+    let key = derive_key(&params.key_derivation_func, password.as_ref())?;
+
+    let mut buffer = Zeroizing::new(ciphertext.to_vec());
+    Aes256Gcm::new(key.as_ref().into())
+        .decrypt_in_place_detached(
+            Nonce::from_slice(gcm_params.nonce.as_bytes()),
+            b"",
+            &mut buffer,
+            Tag::from_slice(tag),
+        )
+        .map_err(|_| pkcs8::Error::EncryptedPrivateKey(pkcs8::pkcs5::Error::DecryptFailed))?;
+    Ok(SecretDocument::try_from(buffer.as_slice())?)
+}
+
+
+fn scrypt_params(log_n: u8, r: u32, p: u32) -> pkcs8::Result<scrypt::Params> {
+    scrypt::Params::new(log_n, r, p, 32).map_err(|_| pkcs8::Error::KeyMalformed)
+}
+
+
+// Draws the salt, IV or nonce, reporting a failing random number generator as an encryption
+// failure rather than blaming the key
+fn fill(rng: &mut impl CryptoRngCore, bytes: &mut [u8]) -> pkcs8::Result<()> {
+    rng.try_fill_bytes(bytes)
+        .map_err(|_| pkcs8::Error::EncryptedPrivateKey(pkcs8::pkcs5::Error::EncryptFailed))
+}
+
+
+/// Derives the 32-byte AES-256 key for the GCM path; the CBC path is left to `pkcs5`.
+fn derive_key(kdf: &Kdf<'_>, password: &[u8]) -> pkcs8::Result<Zeroizing<[u8; 32]>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    if kdf
+        .key_length()
+        .is_some_and(|len| usize::from(len) != key.len())
+    {
+        return Err(pkcs8::Error::KeyMalformed);
+    }
+    match kdf {
+        Kdf::Pbkdf2(params) if params.prf == Pbkdf2Prf::HmacWithSha256 => {
+            pbkdf2::pbkdf2_hmac::<sha2::Sha256>(
+                password,
+                params.salt,
+                params.iteration_count,
+                key.as_mut(),
+            );
+        }
+        Kdf::Scrypt(params) => {
+            let scrypt_params =
+                scrypt::Params::try_from(params).map_err(|_| pkcs8::Error::KeyMalformed)?;
+            scrypt::scrypt(password, params.salt, &scrypt_params, key.as_mut())
+                .map_err(|_| pkcs8::Error::KeyMalformed)?;
+        }
+        _ => return Err(pkcs8::Error::KeyMalformed), // Other PBKDF2 PRFs are not supported
+    }
+    Ok(key)
+}
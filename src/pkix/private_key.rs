@@ -0,0 +1,138 @@
+use der::asn1::OctetStringRef;
+use der::{Choice, Decode, Encode, Sequence};
+use pkcs8::{AlgorithmIdentifierRef, ObjectIdentifier, PrivateKeyInfo, SecretDocument};
+use zeroize::Zeroizing;
+
+use crate::helpers::h;
+
+// PKCS#8 (RFC 5958 `OneAsymmetricKey`) encoding of ML-KEM private keys per
+// draft-ietf-lamps-kyber-certificates. The algorithm identifier has absent parameters, and the
+// `privateKey` OCTET STRING wraps the DER encoding of:
+//
+//   ML-KEM-PrivateKey ::= CHOICE {
+//     seed [0] IMPLICIT OCTET STRING (SIZE (64)),
+//     expandedKey OCTET STRING,
+//     both SEQUENCE { seed OCTET STRING (SIZE (64)), expandedKey OCTET STRING } }
+//
+// The seed is `d ∥ z`, from which `KG::try_keygen_from_seed_vt()` derives the expanded
+// (decapsulation) key.
+
+const SEED_LEN: usize = 64;
+
+
+/// The alternatives of the `ML-KEM-PrivateKey` CHOICE that can be written into a PKCS#8 document
+/// (available with the `pkcs8` feature).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrivateKeyForm {
+    /// Only the 64-byte seed `d ∥ z`; the smallest encoding, expanded again on decode.
+    Seed,
+    /// Only the expanded decapsulation key.
+    ExpandedKey,
+    /// Both, which decoders check against each other.
+    Both,
+}
+
+
+#[derive(Sequence)]
+struct BothRef<'a> {
+    seed: OctetStringRef<'a>,
+    expanded_key: OctetStringRef<'a>,
+}
+
+#[derive(Choice)]
+enum PrivateKeyRef<'a> {
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT")]
+    Seed(OctetStringRef<'a>),
+    ExpandedKey(OctetStringRef<'a>),
+    Both(BothRef<'a>),
+}
+
+
+/// Encodes a `OneAsymmetricKey` holding the `seed` (if any) and/or the `expanded_key` (if any).
+pub(crate) fn encode_private_key(
+    oid: ObjectIdentifier, seed: Option<&[u8; SEED_LEN]>, expanded_key: Option<&[u8]>,
+) -> pkcs8::Result<SecretDocument> {
+    let private_key = match (seed, expanded_key) {
+        (Some(seed), None) => PrivateKeyRef::Seed(OctetStringRef::new(seed)?),
+        (None, Some(expanded_key)) => {
+            PrivateKeyRef::ExpandedKey(OctetStringRef::new(expanded_key)?)
+        }
+        (Some(seed), Some(expanded_key)) => PrivateKeyRef::Both(BothRef {
+            seed: OctetStringRef::new(seed)?,
+            expanded_key: OctetStringRef::new(expanded_key)?,
+        }),
+        (None, None) => return Err(pkcs8::Error::KeyMalformed),
+    };
+    let private_key = Zeroizing::new(private_key.to_der()?);
+    let algorithm = AlgorithmIdentifierRef { oid, parameters: None };
+    SecretDocument::try_from(PrivateKeyInfo::new(algorithm, &private_key))
+}
+
+
+/// Decodes a `OneAsymmetricKey` for `oid` in any of the three forms into a decapsulation key.
+/// A seed is expanded with `from_seed()`; when both are present they must agree, and an expanded
+/// key must carry the correct `H(ek)`. An optional `publicKey` must match the embedded `ek`.
+pub(crate) fn decode_private_key<DK>(
+    oid: ObjectIdentifier, private_key_info: &PrivateKeyInfo<'_>, dk_len: usize,
+    from_seed: impl Fn(&[u8; SEED_LEN]) -> Result<DK, &'static str>,
+    from_expanded_key: impl Fn(&[u8]) -> Result<DK, &'static str>, dk_bytes: impl Fn(&DK) -> &[u8],
+) -> pkcs8::Result<DK> {
+    private_key_info.algorithm.assert_algorithm_oid(oid)?;
+    if private_key_info.algorithm.parameters.is_some() {
+        return Err(pkcs8::Error::KeyMalformed);
+    }
+    let malformed = |_| pkcs8::Error::KeyMalformed;
+
+    let dk = match PrivateKeyRef::from_der(private_key_info.private_key)? {
+        PrivateKeyRef::Seed(seed) => from_seed(seed_of(&seed)?).map_err(malformed)?,
+        PrivateKeyRef::ExpandedKey(expanded_key) => {
+            let expanded_key = expanded_key.as_bytes();
+            if expanded_key.len() != dk_len || !hash_matches(expanded_key) {
+                return Err(pkcs8::Error::KeyMalformed);
+            }
+            from_expanded_key(expanded_key).map_err(malformed)?
+        }
+        PrivateKeyRef::Both(both) => {
+            let dk = from_seed(seed_of(&both.seed)?).map_err(malformed)?;
+            if !ct_eq(dk_bytes(&dk), both.expanded_key.as_bytes()) {
+                return Err(pkcs8::Error::KeyMalformed);
+            }
+            dk
+        }
+    };
+
+    if let Some(public_key) = private_key_info.public_key {
+        if public_key != ek_of(dk_bytes(&dk)) {
+            return Err(pkcs8::Error::KeyMalformed);
+        }
+    }
+    Ok(dk)
+}
+
+
+/// The seed as an array, if it is the right length.
+fn seed_of<'a>(seed: &OctetStringRef<'a>) -> pkcs8::Result<&'a [u8; SEED_LEN]> {
+    seed.as_bytes()
+        .try_into()
+        .map_err(|_| pkcs8::Error::KeyMalformed)
+}
+
+
+/// The `ek` within `dk = dkPKE ∥ ek ∥ H(ek) ∥ z`, where `dk` is `768k + 96` bytes.
+fn ek_of(dk: &[u8]) -> &[u8] {
+    let k = (dk.len() - 96) / 768;
+    &dk[384 * k..768 * k + 32]
+}
+
+
+/// Checks the `H(ek)` stored in an expanded key, which catches corrupted or mismatched keys.
+fn hash_matches(dk: &[u8]) -> bool {
+    let k = (dk.len() - 96) / 768;
+    h(ek_of(dk)) == dk[768 * k + 32..768 * k + 64]
+}
+
+
+// Conservative (constant-time) paranoia, as the expanded keys are secret
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,266 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use rand_core::CryptoRngCore;
+#[cfg(feature = "default-rng")]
+use rand_core::OsRng;
+use zeroize::Zeroize;
+
+use crate::helpers::ensure;
+use crate::traits::KeyGen;
+
+/// A bounded queue of key pairs for parameter set `P` (e.g. `ml_kem_768::KG`), refilled on a
+/// worker thread so that ephemeral keys are taken off the request path (available with the `std`
+/// feature). Each pair is handed out exactly once; pairs still queued when the pool is dropped are
+/// zeroized.
+///
+/// # Examples
+/// ```rust
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// use fips203::ml_kem_768;
+/// use fips203::traits::{Decaps, Encaps};
+/// use fips203::KeyPool;
+///
+/// let pool = KeyPool::<ml_kem_768::KG>::try_new(16)?; // Worker starts filling 16 slots
+/// let (ek, dk) = pool.take()?; // Waits only if the pool is empty
+/// let (ssk1, ct) = ek.try_encaps_vt()?;
+/// assert_eq!(ssk1, dk.try_decaps_vt(&ct)?);
+/// # Ok(())}
+/// ```
+pub struct KeyPool<P>
+where
+    P: KeyGen,
+    P::EncapsKey: Zeroize,
+    P::DecapsKey: Zeroize,
+{
+    shared: Arc<Shared<P::EncapsKey, P::DecapsKey>>,
+    worker: Option<JoinHandle<()>>,
+    capacity: usize,
+    _params: PhantomData<fn() -> P>,
+}
+
+struct Shared<EK, DK> {
+    state: Mutex<State<EK, DK>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+struct State<EK, DK> {
+    pairs: VecDeque<(EK, DK)>,
+    shutdown: bool,
+    // Set when key generation fails (e.g. the random number generator), which stops the worker
+    error: Option<&'static str>,
+}
+
+impl<EK, DK> Shared<EK, DK> {
+    // The state is only modified by single push/pop operations, so it remains consistent even if
+    // a thread panicked while holding the lock
+    fn lock(&self) -> MutexGuard<'_, State<EK, DK>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<P> KeyPool<P>
+where
+    P: KeyGen + 'static,
+    P::EncapsKey: Zeroize + Send + 'static,
+    P::DecapsKey: Zeroize + Send + 'static,
+{
+    /// Starts a worker thread that keeps up to `capacity` key pairs ready, generated with the OS
+    /// default random number generator.
+    /// # Errors
+    /// Returns an error when `capacity` is zero or the worker thread cannot be spawned.
+    #[cfg(feature = "default-rng")]
+    pub fn try_new(capacity: usize) -> Result<Self, &'static str> {
+        Self::try_new_with_rng_factory(capacity, || OsRng)
+    }
+
+    /// Starts a worker thread that keeps up to `capacity` key pairs ready. The worker calls
+    /// `rng_factory` once, on its own thread, and generates every key pair with the returned
+    /// random number generator, so the generator itself need not be `Send`.
+    /// # Errors
+    /// Returns an error when `capacity` is zero or the worker thread cannot be spawned.
+    pub fn try_new_with_rng_factory<R: CryptoRngCore>(
+        capacity: usize, rng_factory: impl FnOnce() -> R + Send + 'static,
+    ) -> Result<Self, &'static str> {
+        ensure!(capacity > 0, "KeyPool: capacity must be nonzero");
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                // Never grows past `capacity`, so no reallocation leaves copies of pairs behind
+                pairs: VecDeque::with_capacity(capacity),
+                shutdown: false,
+                error: None,
+            }),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        });
+        let worker_shared = Arc::clone(&shared);
+        let worker = thread::Builder::new()
+            .name("fips203-key-pool".into())
+            .spawn(move || fill::<P, R>(&worker_shared, capacity, rng_factory))
+            .map_err(|_| "KeyPool: failed to spawn worker thread")?;
+        Ok(Self { shared, worker: Some(worker), capacity, _params: PhantomData })
+    }
+}
+
+impl<P> KeyPool<P>
+where
+    P: KeyGen,
+    P::EncapsKey: Zeroize + Clone,
+    P::DecapsKey: Zeroize + Clone,
+{
+    /// Removes the oldest key pair, waiting for the worker to generate one if the pool is empty.
+    /// # Errors
+    /// Returns an error when the pool is empty and the worker has stopped because key generation
+    /// failed (e.g. the random number generator).
+    pub fn take(&self) -> Result<(P::EncapsKey, P::DecapsKey), &'static str> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(pair) = take_front::<P>(&mut state.pairs) {
+                self.shared.not_full.notify_one();
+                return Ok(pair);
+            }
+            if let Some(error) = state.error {
+                return Err(error);
+            }
+            state = self
+                .shared
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Removes the oldest key pair if one is ready, without waiting. A caller with a strict
+    /// latency budget can fall back to `KG::try_keygen_vt()` on `None`.
+    #[must_use]
+    pub fn take_ready(&self) -> Option<(P::EncapsKey, P::DecapsKey)> {
+        let pair = take_front::<P>(&mut self.shared.lock().pairs);
+        if pair.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        pair
+    }
+
+    /// Returns the number of key pairs ready to be taken.
+    #[must_use]
+    pub fn len(&self) -> usize { self.shared.lock().pairs.len() }
+
+    /// Returns `true` when no key pair is ready.
+    #[must_use]
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Returns the maximum number of key pairs kept ready.
+    #[must_use]
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+/// Signals the worker to stop, waits for it (at most one key generation) and zeroizes the key
+/// pairs that were never taken.
+impl<P> Drop for KeyPool<P>
+where
+    P: KeyGen,
+    P::EncapsKey: Zeroize,
+    P::DecapsKey: Zeroize,
+{
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.not_full.notify_all();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        // In place, as moving a pair out of the queue would leave its bytes in the ring buffer
+        let mut state = self.shared.lock();
+        for (ek, dk) in &mut state.pairs {
+            ek.zeroize();
+            dk.zeroize();
+        }
+        state.pairs.clear();
+    }
+}
+
+/// The worker loop: generates key pairs with the generator from `rng_factory` while there is room,
+/// until shutdown or error.
+fn fill<P, R: CryptoRngCore>(
+    shared: &Shared<P::EncapsKey, P::DecapsKey>, capacity: usize, rng_factory: impl FnOnce() -> R,
+) where
+    P: KeyGen,
+    P::EncapsKey: Zeroize,
+    P::DecapsKey: Zeroize,
+{
+    let _guard = PanicGuard(shared);
+    let mut rng = rng_factory();
+    loop {
+        let mut state = shared.lock();
+        while state.pairs.len() >= capacity && !state.shutdown {
+            state = shared
+                .not_full
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        if state.shutdown {
+            return;
+        }
+        drop(state); // Generate without holding the lock, so `take()` is never blocked behind it
+
+        let result = P::try_keygen_with_rng_vt(&mut rng);
+        let mut state = shared.lock();
+        match result {
+            Ok(pair) if state.shutdown => {
+                zeroize_pair::<P>(pair);
+                return;
+            }
+            Ok(pair) => state.pairs.push_back(pair),
+            Err(error) => {
+                state.error = Some(error);
+                shared.not_empty.notify_all();
+                return;
+            }
+        }
+        shared.not_empty.notify_one();
+    }
+}
+
+// Reports a panic in `rng_factory` or key generation as an error, so that `take()` returns rather
+// than waiting for a worker that is gone
+struct PanicGuard<'a, EK, DK>(&'a Shared<EK, DK>);
+
+impl<EK, DK> Drop for PanicGuard<'_, EK, DK> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.lock().error = Some("KeyPool: worker thread panicked");
+            self.0.not_empty.notify_all();
+        }
+    }
+}
+
+// Hands out the oldest pair as a copy and wipes its slot, since popping it would only move its
+// bytes out of the ring buffer without clearing them
+fn take_front<P>(
+    pairs: &mut VecDeque<(P::EncapsKey, P::DecapsKey)>,
+) -> Option<(P::EncapsKey, P::DecapsKey)>
+where
+    P: KeyGen,
+    P::EncapsKey: Zeroize + Clone,
+    P::DecapsKey: Zeroize + Clone,
+{
+    let (ek, dk) = pairs.front_mut()?;
+    let pair = (ek.clone(), dk.clone());
+    ek.zeroize();
+    dk.zeroize();
+    pairs.pop_front();
+    Some(pair)
+}
+
+fn zeroize_pair<P>((mut ek, mut dk): (P::EncapsKey, P::DecapsKey))
+where
+    P: KeyGen,
+    P::EncapsKey: Zeroize,
+    P::DecapsKey: Zeroize,
+{
+    ek.zeroize();
+    dk.zeroize();
+}
@@ -0,0 +1,450 @@
+use core::simd::prelude::*;
+use core::simd::simd_swizzle;
+
+use sha3::digest::XofReader;
+
+use crate::helpers::{count, ensure};
+use crate::ntt::{gen_zeta_table, ZETA_TABLE};
+use crate::types::Z256;
+use crate::Q;
+
+// `core::simd` implementations of the `ntt.rs`, `sampling.rs` and `byte_fns.rs` kernels, selected
+// by `backend.rs` with the nightly-only `portable-simd` feature. The same source then vectorizes
+// for whichever SIMD extension the target enables (SSE/AVX, NEON, RVV, SIMD128...). Each function
+// mirrors the signature and output of its scalar counterpart, which the differential tests below
+// compare against. Products by a known zeta use Shoup multiplication (see `simd128.rs`).
+
+#[allow(clippy::cast_possible_truncation)]
+const Q16: u16 = Q as u16;
+
+/// The Shoup companions `⌊ζ · 2^16 / q⌋` of each entry in `ZETA_TABLE`.
+static ZETA_SHOUP: [u16; 256] = gen_shoup_table();
+
+/// The NTT⁻¹ scaling factor `128^{-1} mod q` and its Shoup companion.
+const F: u16 = 3303;
+#[allow(clippy::cast_possible_truncation)]
+const F_SHOUP: u16 = (((F as u32) << 16) / Q) as u16;
+
+
+#[allow(clippy::cast_possible_truncation)]
+const fn gen_shoup_table() -> [u16; 256] {
+    let zetas = gen_zeta_table();
+    let mut result = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        result[i] = (((zetas[i] as u32) << 16) / Q) as u16;
+        i += 1;
+    }
+    result
+}
+
+
+#[inline]
+fn load(f: &[Z256]) -> u16x8 { u16x8::from_array(core::array::from_fn(|i| f[i].get_u16())) }
+
+
+#[inline]
+fn store(f: &mut [Z256], v: u16x8) {
+    f.iter_mut()
+        .zip(v.to_array())
+        .for_each(|(x, y)| *x = Z256(y));
+}
+
+
+/// Maps each lane in `[0, 2q)` to `[0, q)`. When `x < q`, `x − q` wraps to a larger value.
+#[inline]
+fn reduce_once(x: u16x8) -> u16x8 { x.simd_min(x - u16x8::splat(Q16)) }
+
+
+#[inline]
+fn add_mod(a: u16x8, b: u16x8) -> u16x8 { reduce_once(a + b) }
+
+
+#[inline]
+fn sub_mod(a: u16x8, b: u16x8) -> u16x8 { reduce_once(a + (u16x8::splat(Q16) - b)) }
+
+
+/// Lane-wise `b · ζ mod q` given `ζ` and its Shoup companion; `b` must be below `2^16`.
+#[inline]
+fn mul_shoup(b: u16x8, zeta: u16x8, zeta_shoup: u16x8) -> u16x8 {
+    let b: u32x8 = b.cast();
+    let quot = (b * zeta_shoup.cast()) >> 16;
+    reduce_once((b * zeta.cast() - quot * u32x8::splat(Q)).cast())
+}
+
+
+/// Per-lane zetas (and Shoup companions) for the final two layers, where one vector spans
+/// several butterfly groups; `ks[l]` is the `k` of the group that lane `l` belongs to.
+#[inline]
+fn zeta_lanes(ks: [usize; 8]) -> (u16x8, u16x8) {
+    (
+        u16x8::from_array(ks.map(|k| ZETA_TABLE[k << 1])),
+        u16x8::from_array(ks.map(|k| ZETA_SHOUP[k << 1])),
+    )
+}
+
+
+/// Algorithm 8 `NTT(f)` on page 22, vectorized.
+#[must_use]
+pub(crate) fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt);
+    count!(reductions, 7 * 128);
+    let mut f_hat = *array_f;
+    let mut k = 1;
+
+    // len ≥ 8: every butterfly group covers whole vectors and shares one zeta
+    for len in [128, 64, 32, 16, 8] {
+        for start in (0..256).step_by(2 * len) {
+            let zeta = u16x8::splat(ZETA_TABLE[k << 1]);
+            let zeta_shoup = u16x8::splat(ZETA_SHOUP[k << 1]);
+            k += 1;
+            for j in (start..(start + len)).step_by(8) {
+                let t = mul_shoup(load(&f_hat[j + len..]), zeta, zeta_shoup);
+                let f_j = load(&f_hat[j..]);
+                store(&mut f_hat[j + len..j + len + 8], sub_mod(f_j, t));
+                store(&mut f_hat[j..j + 8], add_mod(f_j, t));
+            }
+        }
+    }
+
+    // len = 4: two vectors hold four groups of (4 lower, 4 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k, k, k + 1, k + 1, k + 1, k + 1]);
+        k += 2;
+        let (v0, v1) = (load(&f_hat[start..]), load(&f_hat[start + 8..]));
+        let f_j = simd_swizzle!(v0, v1, [0, 1, 2, 3, 8, 9, 10, 11]);
+        let t = mul_shoup(simd_swizzle!(v0, v1, [4, 5, 6, 7, 12, 13, 14, 15]), zeta, zeta_shoup);
+        let (lower, upper) = (add_mod(f_j, t), sub_mod(f_j, t));
+        store(
+            &mut f_hat[start..start + 8],
+            simd_swizzle!(lower, upper, [0, 1, 2, 3, 8, 9, 10, 11]),
+        );
+        store(
+            &mut f_hat[start + 8..start + 16],
+            simd_swizzle!(lower, upper, [4, 5, 6, 7, 12, 13, 14, 15]),
+        );
+    }
+
+    // len = 2: two vectors hold four groups of (2 lower, 2 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k + 1, k + 1, k + 2, k + 2, k + 3, k + 3]);
+        k += 4;
+        let (v0, v1) = (load(&f_hat[start..]), load(&f_hat[start + 8..]));
+        let f_j = simd_swizzle!(v0, v1, [0, 1, 4, 5, 8, 9, 12, 13]);
+        let t = mul_shoup(simd_swizzle!(v0, v1, [2, 3, 6, 7, 10, 11, 14, 15]), zeta, zeta_shoup);
+        let (lower, upper) = (add_mod(f_j, t), sub_mod(f_j, t));
+        store(
+            &mut f_hat[start..start + 8],
+            simd_swizzle!(lower, upper, [0, 1, 8, 9, 2, 3, 10, 11]),
+        );
+        store(
+            &mut f_hat[start + 8..start + 16],
+            simd_swizzle!(lower, upper, [4, 5, 12, 13, 6, 7, 14, 15]),
+        );
+    }
+
+    f_hat
+}
+
+
+/// Algorithm 9 `NTTinv(f)` on page 23, vectorized.
+#[must_use]
+pub(crate) fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt_inv);
+    count!(reductions, 7 * 128 + 256);
+    let mut f = *f_hat;
+    let mut k = 127;
+
+    // len = 2: two vectors hold four groups of (2 lower, 2 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k - 1, k - 1, k - 2, k - 2, k - 3, k - 3]);
+        k -= 4;
+        let (v0, v1) = (load(&f[start..]), load(&f[start + 8..]));
+        let t = simd_swizzle!(v0, v1, [0, 1, 4, 5, 8, 9, 12, 13]);
+        let f_jl = simd_swizzle!(v0, v1, [2, 3, 6, 7, 10, 11, 14, 15]);
+        let (lower, upper) = (add_mod(t, f_jl), mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+        store(
+            &mut f[start..start + 8],
+            simd_swizzle!(lower, upper, [0, 1, 8, 9, 2, 3, 10, 11]),
+        );
+        store(
+            &mut f[start + 8..start + 16],
+            simd_swizzle!(lower, upper, [4, 5, 12, 13, 6, 7, 14, 15]),
+        );
+    }
+
+    // len = 4: two vectors hold four groups of (4 lower, 4 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k, k, k - 1, k - 1, k - 1, k - 1]);
+        k -= 2;
+        let (v0, v1) = (load(&f[start..]), load(&f[start + 8..]));
+        let t = simd_swizzle!(v0, v1, [0, 1, 2, 3, 8, 9, 10, 11]);
+        let f_jl = simd_swizzle!(v0, v1, [4, 5, 6, 7, 12, 13, 14, 15]);
+        let (lower, upper) = (add_mod(t, f_jl), mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+        store(
+            &mut f[start..start + 8],
+            simd_swizzle!(lower, upper, [0, 1, 2, 3, 8, 9, 10, 11]),
+        );
+        store(
+            &mut f[start + 8..start + 16],
+            simd_swizzle!(lower, upper, [4, 5, 6, 7, 12, 13, 14, 15]),
+        );
+    }
+
+    // len ≥ 8: every butterfly group covers whole vectors and shares one zeta
+    for len in [8, 16, 32, 64, 128] {
+        for start in (0..256).step_by(2 * len) {
+            let zeta = u16x8::splat(ZETA_TABLE[k << 1]);
+            let zeta_shoup = u16x8::splat(ZETA_SHOUP[k << 1]);
+            k -= 1;
+            for j in (start..(start + len)).step_by(8) {
+                let t = load(&f[j..]);
+                let f_jl = load(&f[j + len..]);
+                store(&mut f[j..j + 8], add_mod(t, f_jl));
+                store(&mut f[j + len..j + len + 8], mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+            }
+        }
+    }
+
+    // f ← f · 3303 mod q
+    let (scale, scale_shoup) = (u16x8::splat(F), u16x8::splat(F_SHOUP));
+    for j in (0..256).step_by(8) {
+        let f_j = load(&f[j..]);
+        store(&mut f[j..j + 8], mul_shoup(f_j, scale, scale_shoup));
+    }
+
+    f
+}
+
+
+/// Vectorized `multiply_ntts_acc()`: eight degree-one products per iteration, with the even and
+/// odd coefficients split apart on load and re-interleaved into the accumulator.
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut [u32; 256], f_hat: &[Z256; 256], g_hat: &[Z256; 256], g_cache: &[Z256; 128],
+) {
+    for i in (0..128).step_by(8) {
+        let (a0, a1) = load(&f_hat[2 * i..]).deinterleave(load(&f_hat[2 * i + 8..]));
+        let (b0, b1) = load(&g_hat[2 * i..]).deinterleave(load(&g_hat[2 * i + 8..]));
+        let (a0, a1, b0, b1): (u32x8, u32x8, u32x8, u32x8) =
+            (a0.cast(), a1.cast(), b0.cast(), b1.cast());
+        let b1_gamma: u32x8 = load(&g_cache[i..]).cast();
+
+        // c0 ← a0 · b0 + a1 · b1 · γ   and   c1 ← a0 · b1 + a1 · b0   (reduction deferred)
+        let (lo, hi) = (a0 * b0 + a1 * b1_gamma).interleave(a0 * b1 + a1 * b0);
+        let (acc_lo, acc_hi) = acc[2 * i..2 * i + 16].split_at_mut(8);
+        (u32x8::from_slice(acc_lo) + lo).copy_to_slice(acc_lo);
+        (u32x8::from_slice(acc_hi) + hi).copy_to_slice(acc_hi);
+    }
+}
+
+
+/// Algorithm 6 `SampleNTT(B)` on page 20, vectorized.
+/// Draws one SHAKE128 block at a time and parses sixteen 12-bit candidates per step; only the
+/// rejection (compaction) of candidates `≥ q` remains scalar.
+#[must_use]
+pub(crate) fn sample_ntt(mut byte_stream_b: impl XofReader) -> [Z256; 256] {
+    let mut array_a_hat = [Z256(0); 256];
+    let mut block = [0u8; 168];
+    // Candidate 2t spans bytes 3t and 3t + 1, candidate 2t + 1 spans bytes 3t + 1 and 3t + 2
+    let lo_idx = usizex16::from_array(core::array::from_fn(|l| 3 * (l / 2) + (l % 2)));
+    let even = mask16x16::from_array(core::array::from_fn(|l| l % 2 == 0));
+    let mut j = 0;
+    while j < 256 {
+        byte_stream_b.read(&mut block);
+        for bytes in block.chunks_exact(24) {
+            let lo: u16x16 = u8x16::gather_or_default(bytes, lo_idx).cast();
+            let hi: u16x16 = u8x16::gather_or_default(bytes, lo_idx + usizex16::splat(1)).cast();
+            let pair = lo | (hi << 8);
+            // d1 ← B[i] + 256 · (B[i + 1] mod 16)   and   d2 ← ⌊B[i + 1]/16⌋ + 16 · B[i + 2]
+            let d = even.select(pair & u16x16::splat(0x0FFF), pair >> 4);
+            let accept = d.simd_lt(u16x16::splat(Q16)).to_bitmask();
+            for (l, d_l) in d.to_array().into_iter().enumerate() {
+                if (accept >> l) & 1 == 1 && j < 256 {
+                    array_a_hat[j] = Z256(d_l);
+                    j += 1;
+                }
+            }
+        }
+    }
+    array_a_hat
+}
+
+
+/// Algorithm 7 `SamplePolyCBDη(B)` on page 20, vectorized.
+/// Each lane gathers the (up to two) bytes holding its `2η`-bit field, then takes the difference
+/// of the population counts of the two `η`-bit halves.
+pub(crate) fn sample_poly_cbd(eta: u32, byte_array_b: &[u8]) -> Result<[Z256; 256], &'static str> {
+    ensure!((1..=4).contains(&eta), "Alg7: eta out of range");
+    ensure!(byte_array_b.len() == 64 * eta as usize, "Alg7: bytes len not 64 * eta");
+    let mut array_f = [Z256(0); 256];
+    let (field, eta16) = (2 * eta as usize, u16x16::splat(u16::try_from(eta).unwrap()));
+    let mask = u16x16::splat((1 << eta) - 1);
+    // Sixteen coefficients occupy 2η bytes, so the per-lane offsets repeat every iteration
+    let byte_idx = usizex16::from_array(core::array::from_fn(|l| l * field / 8));
+    let shift = u16x16::from_array(core::array::from_fn(|l| (l * field % 8) as u16));
+    for (c, bytes) in byte_array_b.chunks_exact(2 * field).enumerate() {
+        let b0: u16x16 = u8x16::gather_or_default(bytes, byte_idx).cast();
+        let b1: u16x16 = u8x16::gather_or_default(bytes, byte_idx + usizex16::splat(1)).cast();
+        let w = (b0 | (b1 << 8)) >> shift;
+        // x ← ∑ b[2iη + j]   and   y ← ∑ b[2iη + η + j]   then   f[i] ← x − y mod q
+        let x = (w & mask).count_ones();
+        let y = ((w >> eta16) & mask).count_ones();
+        let f = u16x16::splat(Q16) + x - y;
+        let f = f.simd_min(f - u16x16::splat(Q16));
+        array_f[16 * c..16 * c + 16]
+            .iter_mut()
+            .zip(f.to_array())
+            .for_each(|(a, b)| *a = Z256(b));
+    }
+    Ok(array_f)
+}
+
+
+/// Algorithm 4 `ByteEncode<d>(F)` on page 19, vectorized.
+/// Each group of eight d-bit integers fills exactly d bytes; lanes are merged pairwise (d, then
+/// 2d, then 4d bits wide) and the resulting `8d`-bit value is written out little-endian.
+pub(crate) fn byte_encode(
+    d: u32, integers_f: &[Z256; 256], bytes_b: &mut [u8],
+) -> Result<(), &'static str> {
+    ensure!((1..=12).contains(&d), "Alg4: d out of range");
+    ensure!(bytes_b.len() == 32 * d as usize, "Alg4: bytes len not 32 * d");
+    let m = u64x8::splat(if d < 12 { 1 << d } else { u64::from(Q) });
+    let mask = u64x8::splat((1 << d) - 1);
+    let (d_u64, d_usize) = (u64::from(d), d as usize);
+    for (coeffs, bytes) in integers_f
+        .chunks_exact(8)
+        .zip(bytes_b.chunks_exact_mut(d_usize))
+    {
+        let c: u64x8 = load(coeffs).cast();
+        ensure!(c.simd_le(m).all(), "Alg4: Coeff out of range");
+        let c = c & mask;
+        let pairs = simd_swizzle!(c, [0, 2, 4, 6]) | (simd_swizzle!(c, [1, 3, 5, 7]) << d_u64);
+        let quads = simd_swizzle!(pairs, [0, 2]) | (simd_swizzle!(pairs, [1, 3]) << (2 * d_u64));
+        let all = u128::from(quads[0]) | (u128::from(quads[1]) << (4 * d));
+        bytes.copy_from_slice(&all.to_le_bytes()[..d_usize]);
+    }
+    Ok(())
+}
+
+
+/// Algorithm 5 `ByteDecode<d>(B)` on page 19, vectorized.
+/// The inverse of `byte_encode()`: each group of d bytes is split into two `4d`-bit halves, then
+/// four `2d`-bit quarters, then eight d-bit integers.
+pub(crate) fn byte_decode(
+    d: u32, bytes_b: &[u8], integers_f: &mut [Z256; 256],
+) -> Result<(), &'static str> {
+    ensure!((1..=12).contains(&d), "Alg5: d out of range");
+    ensure!(bytes_b.len() == 32 * d as usize, "Alg5: bytes len not 32 * d");
+    let d_usize = d as usize;
+    let (d_u64, mask_4d, mask_2d) = (u64::from(d), (1u64 << (4 * d)) - 1, (1u64 << (2 * d)) - 1);
+    let max = u16x8::splat(if d < 12 { 1 << d } else { Q16 });
+    let mut in_range = mask16x8::splat(true);
+    for (bytes, coeffs) in bytes_b
+        .chunks_exact(d_usize)
+        .zip(integers_f.chunks_exact_mut(8))
+    {
+        let mut le_bytes = [0u8; 16];
+        le_bytes[..d_usize].copy_from_slice(bytes);
+        let all = u128::from_le_bytes(le_bytes);
+        #[allow(clippy::cast_possible_truncation)]
+        let halves = u64x2::from_array([all as u64 & mask_4d, (all >> (4 * d)) as u64]);
+        let quads =
+            simd_swizzle!(halves, [0, 0, 1, 1]) >> u64x4::from_array([0, 2 * d_u64, 0, 2 * d_u64]);
+        let quads = quads & u64x4::splat(mask_2d);
+        let shift = u64x8::from_array([0, d_u64, 0, d_u64, 0, d_u64, 0, d_u64]);
+        let ints =
+            (simd_swizzle!(quads, [0, 0, 1, 1, 2, 2, 3, 3]) >> shift) & u64x8::splat((1 << d) - 1);
+        let ints: u16x8 = ints.cast();
+        in_range &= ints.simd_lt(max);
+        store(coeffs, ints);
+    }
+    ensure!(in_range.all(), "Alg5: integers out of range");
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+
+    use alloc::vec;
+
+    use rand::{Rng, RngCore, SeedableRng};
+
+    use crate::helpers::SeededXof;
+    use crate::ntt::mul_cache;
+    use crate::types::Z256;
+    use crate::{byte_fns, ntt, portable_simd, sampling};
+
+    fn random_poly(rng: &mut impl Rng, m: u16) -> [Z256; 256] {
+        core::array::from_fn(|_| Z256(rng.gen_range(0..m)))
+    }
+
+    fn same(a: &[Z256], b: &[Z256]) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.get_u16() == y.get_u16())
+    }
+
+    #[test]
+    fn test_ntt_matches_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(32);
+        for _i in 0..200 {
+            let (f, g) = (random_poly(&mut rng, 3329), random_poly(&mut rng, 3329));
+            assert!(same(&portable_simd::ntt(&f), &ntt::ntt(&f)));
+            assert!(same(&portable_simd::ntt_inv(&f), &ntt::ntt_inv(&f)));
+
+            let (mut acc_simd, mut acc_scalar) = ([0u32; 256], [0u32; 256]);
+            portable_simd::multiply_ntts_acc(&mut acc_simd, &f, &g, &mul_cache(&g));
+            ntt::multiply_ntts_acc(&mut acc_scalar, &f, &g, &mul_cache(&g));
+            assert_eq!(acc_simd, acc_scalar);
+        }
+    }
+
+    #[test]
+    fn test_sampling_matches_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(33);
+        for _i in 0..200 {
+            let rho: [u8; 32] = rng.gen();
+            let (i, j) = (rng.gen(), rng.gen());
+            let rho_sponge = SeededXof::new(&rho);
+            assert!(same(
+                &portable_simd::sample_ntt(rho_sponge.xof(i, j)),
+                &sampling::sample_ntt(rho_sponge.xof(i, j))
+            ));
+
+            for eta in [2, 3] {
+                let mut bytes = vec![0u8; 64 * eta as usize];
+                rng.fill_bytes(&mut bytes);
+                assert!(same(
+                    &portable_simd::sample_poly_cbd(eta, &bytes).unwrap(),
+                    &sampling::sample_poly_cbd(eta, &bytes).unwrap()
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn test_byte_fns_match_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(34);
+        for _i in 0..200 {
+            for d in [1, 4, 5, 10, 11, 12] {
+                let m = if d < 12 { 1 << d } else { 3329 };
+                let f = random_poly(&mut rng, m);
+                let (mut simd, mut scalar) =
+                    (vec![0u8; 32 * d as usize], vec![0u8; 32 * d as usize]);
+                portable_simd::byte_encode(d, &f, &mut simd).unwrap();
+                byte_fns::byte_encode(d, &f, &mut scalar).unwrap();
+                assert_eq!(simd, scalar);
+
+                // Random bytes exercise the d = 12 range check as well
+                rng.fill_bytes(&mut simd);
+                let (mut f_simd, mut f_scalar) = ([Z256(0); 256], [Z256(0); 256]);
+                let res_simd = portable_simd::byte_decode(d, &simd, &mut f_simd);
+                let res_scalar = byte_fns::byte_decode(d, &simd, &mut f_scalar);
+                assert_eq!(res_simd, res_scalar);
+                assert!(same(&f_simd, &f_scalar));
+            }
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use core::arch::asm;
+
+use crate::helpers::count;
+use crate::ntt::{gen_zeta_table, ZETA_TABLE};
+use crate::types::Z256;
+use crate::Q;
+
+// RISC-V Vector (RVV 1.0) implementations of the NTT, inverse NTT and base multiplication,
+// selected by `backend.rs` when compiling for `riscv64` with `-C target-feature=+v`. Each function
+// mirrors its scalar counterpart (same signature, same output). Rust has no stable RVV intrinsics,
+// so the kernels are inline assembly; every loop is strip-mined on the `vl` returned by `vsetvli`,
+// so the code is correct for any `VLEN` (the V extension guarantees at least 128 bits).
+//
+// The butterfly layers with `len ≥ 8` operate on contiguous runs of coefficients that share one
+// zeta. For `len = 4` and `len = 2`, a segment load places coefficient `j` of every group in
+// vector `j`, so the butterflies again pair whole vectors, and a strided load gathers the per-group
+// zetas. As in `simd128.rs`, products by a known zeta use Shoup multiplication with a precomputed
+// `⌊ζ · 2^16 / q⌋`; `vmulhu` yields the quotient estimate directly.
+//
+// Register use: v0-v7 hold coefficients, v16/v17 the zetas and their Shoup companions, v20/v21 are
+// scratch, and the base multiplication widens into v8-v15.
+
+/// The Shoup companions `⌊ζ · 2^16 / q⌋` of each entry in `ZETA_TABLE`.
+static ZETA_SHOUP: [u16; 256] = gen_shoup_table();
+
+/// The NTT⁻¹ scaling factor `128^{-1} mod q` and its Shoup companion.
+const F: u16 = 3303;
+#[allow(clippy::cast_possible_truncation)]
+const F_SHOUP: u16 = (((F as u32) << 16) / Q) as u16;
+
+
+#[allow(clippy::cast_possible_truncation)]
+const fn gen_shoup_table() -> [u16; 256] {
+    let zetas = gen_zeta_table();
+    let mut result = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        result[i] = (((zetas[i] as u32) << 16) / Q) as u16;
+        i += 1;
+    }
+    result
+}
+
+
+/// `b ← ζ · b mod q` on vector register `b`, with `ζ` in v16 and its Shoup companion in v17.
+macro_rules! mul_shoup {
+    ($b:literal) => {
+        concat!(
+            "vmulhu.vv v20, ", $b, ", v17\n",
+            "vmul.vv ", $b, ", ", $b, ", v16\n",
+            "vnmsac.vx ", $b, ", {q}, v20\n",
+            "vsub.vx v20, ", $b, ", {q}\n",
+            "vminu.vv ", $b, ", ", $b, ", v20\n",
+        )
+    };
+}
+
+
+/// Steps 8-10 of Algorithm 8: `(a, b) ← (a + ζ · b, a − ζ · b) mod q`.
+macro_rules! ct_butterfly {
+    ($a:literal, $b:literal) => {
+        concat!(
+            mul_shoup!($b),
+            "vadd.vx v20, ", $a, ", {q}\n",
+            "vsub.vv v20, v20, ", $b, "\n",
+            "vadd.vv ", $a, ", ", $a, ", ", $b, "\n",
+            "vsub.vx v21, ", $a, ", {q}\n",
+            "vminu.vv ", $a, ", ", $a, ", v21\n",
+            "vsub.vx v21, v20, {q}\n",
+            "vminu.vv ", $b, ", v20, v21\n",
+        )
+    };
+}
+
+
+/// Steps 8-10 of Algorithm 9: `(a, b) ← (a + b, ζ · (b − a)) mod q`.
+macro_rules! gs_butterfly {
+    ($a:literal, $b:literal) => {
+        concat!(
+            "vadd.vx v20, ", $b, ", {q}\n",
+            "vsub.vv v20, v20, ", $a, "\n",
+            "vadd.vv ", $a, ", ", $a, ", ", $b, "\n",
+            "vsub.vx v21, ", $a, ", {q}\n",
+            "vminu.vv ", $a, ", ", $a, ", v21\n",
+            "vsub.vx v21, v20, {q}\n",
+            "vminu.vv ", $b, ", v20, v21\n",
+            mul_shoup!($b),
+        )
+    };
+}
+
+
+/// One `len ≥ 8` layer group: butterflies between `lower[j]` and `upper[j]` with a single zeta.
+fn butterflies(lower: &mut [Z256], upper: &mut [Z256], k: usize, inverse: bool) {
+    assert_eq!(lower.len(), upper.len());
+    let (zeta, zeta_shoup) = (ZETA_TABLE[k << 1], ZETA_SHOUP[k << 1]);
+    let mut done = 0;
+    while done < lower.len() {
+        let (a, b) = (lower[done..].as_mut_ptr(), upper[done..].as_mut_ptr());
+        let avl = lower.len() - done;
+        let vl: usize;
+        if inverse {
+            // SAFETY: `vl ≤ avl`, so the loads and stores stay within `lower` and `upper`
+            unsafe {
+                asm!(
+                    "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                    "vle16.v v0, ({a})",
+                    "vle16.v v1, ({b})",
+                    "vmv.v.x v16, {zeta}",
+                    "vmv.v.x v17, {zeta_shoup}",
+                    gs_butterfly!("v0", "v1"),
+                    "vse16.v v0, ({a})",
+                    "vse16.v v1, ({b})",
+                    vl = out(reg) vl,
+                    avl = in(reg) avl,
+                    a = in(reg) a,
+                    b = in(reg) b,
+                    zeta = in(reg) zeta,
+                    zeta_shoup = in(reg) zeta_shoup,
+                    q = in(reg) Q,
+                    out("v0") _, out("v1") _, out("v16") _, out("v17") _, out("v20") _, out("v21") _,
+                    options(nostack),
+                );
+            }
+        } else {
+            // SAFETY: `vl ≤ avl`, so the loads and stores stay within `lower` and `upper`
+            unsafe {
+                asm!(
+                    "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                    "vle16.v v0, ({a})",
+                    "vle16.v v1, ({b})",
+                    "vmv.v.x v16, {zeta}",
+                    "vmv.v.x v17, {zeta_shoup}",
+                    ct_butterfly!("v0", "v1"),
+                    "vse16.v v0, ({a})",
+                    "vse16.v v1, ({b})",
+                    vl = out(reg) vl,
+                    avl = in(reg) avl,
+                    a = in(reg) a,
+                    b = in(reg) b,
+                    zeta = in(reg) zeta,
+                    zeta_shoup = in(reg) zeta_shoup,
+                    q = in(reg) Q,
+                    out("v0") _, out("v1") _, out("v16") _, out("v17") _, out("v20") _, out("v21") _,
+                    options(nostack),
+                );
+            }
+        }
+        done += vl;
+    }
+}
+
+
+/// The `len = 4` layer (32 groups of 8 coefficients, `k` from `k0` counting up for the NTT and down
+/// for the inverse). A segment load of 8 fields puts coefficient `j` of each group in `v{j}`.
+fn butterflies_len_4(f: &mut [Z256; 256], k0: usize, inverse: bool) {
+    let mut done = 0;
+    while done < 32 {
+        let p = f[8 * done..].as_mut_ptr();
+        let k = if inverse { k0 - done } else { k0 + done };
+        let zeta = ZETA_TABLE.as_ptr().wrapping_add(k << 1);
+        let zeta_shoup = ZETA_SHOUP.as_ptr().wrapping_add(k << 1);
+        // Consecutive groups use every other table entry, in reverse order for the inverse
+        let stride: isize = if inverse { -4 } else { 4 };
+        let avl = 32 - done;
+        let vl: usize;
+        if inverse {
+            // SAFETY: `vl ≤ avl` groups of `f` are accessed; the strided zeta loads read entries
+            // `2k, 2k − 2, ..` down to `2(k0 − 31) ≥ 0`
+            unsafe {
+                asm!(
+                    "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                    "vlseg8e16.v v0, ({p})",
+                    "vlse16.v v16, ({zeta}), {stride}",
+                    "vlse16.v v17, ({zeta_shoup}), {stride}",
+                    gs_butterfly!("v0", "v4"),
+                    gs_butterfly!("v1", "v5"),
+                    gs_butterfly!("v2", "v6"),
+                    gs_butterfly!("v3", "v7"),
+                    "vsseg8e16.v v0, ({p})",
+                    vl = out(reg) vl,
+                    avl = in(reg) avl,
+                    p = in(reg) p,
+                    zeta = in(reg) zeta,
+                    zeta_shoup = in(reg) zeta_shoup,
+                    stride = in(reg) stride,
+                    q = in(reg) Q,
+                    out("v0") _, out("v1") _, out("v2") _, out("v3") _,
+                    out("v4") _, out("v5") _, out("v6") _, out("v7") _,
+                    out("v16") _, out("v17") _, out("v20") _, out("v21") _,
+                    options(nostack),
+                );
+            }
+        } else {
+            // SAFETY: `vl ≤ avl` groups of `f` are accessed; the strided zeta loads read entries
+            // `2k, 2k + 2, ..` up to `2(k0 + 31) < 256`
+            unsafe {
+                asm!(
+                    "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                    "vlseg8e16.v v0, ({p})",
+                    "vlse16.v v16, ({zeta}), {stride}",
+                    "vlse16.v v17, ({zeta_shoup}), {stride}",
+                    ct_butterfly!("v0", "v4"),
+                    ct_butterfly!("v1", "v5"),
+                    ct_butterfly!("v2", "v6"),
+                    ct_butterfly!("v3", "v7"),
+                    "vsseg8e16.v v0, ({p})",
+                    vl = out(reg) vl,
+                    avl = in(reg) avl,
+                    p = in(reg) p,
+                    zeta = in(reg) zeta,
+                    zeta_shoup = in(reg) zeta_shoup,
+                    stride = in(reg) stride,
+                    q = in(reg) Q,
+                    out("v0") _, out("v1") _, out("v2") _, out("v3") _,
+                    out("v4") _, out("v5") _, out("v6") _, out("v7") _,
+                    out("v16") _, out("v17") _, out("v20") _, out("v21") _,
+                    options(nostack),
+                );
+            }
+        }
+        done += vl;
+    }
+}
+
+
+/// The `len = 2` layer (64 groups of 4 coefficients), as for `butterflies_len_4()`.
+fn butterflies_len_2(f: &mut [Z256; 256], k0: usize, inverse: bool) {
+    let mut done = 0;
+    while done < 64 {
+        let p = f[4 * done..].as_mut_ptr();
+        let k = if inverse { k0 - done } else { k0 + done };
+        let zeta = ZETA_TABLE.as_ptr().wrapping_add(k << 1);
+        let zeta_shoup = ZETA_SHOUP.as_ptr().wrapping_add(k << 1);
+        let stride: isize = if inverse { -4 } else { 4 };
+        let avl = 64 - done;
+        let vl: usize;
+        if inverse {
+            // SAFETY: `vl ≤ avl` groups of `f` are accessed; the strided zeta loads read entries
+            // `2k, 2k − 2, ..` down to `2(k0 − 63) ≥ 0`
+            unsafe {
+                asm!(
+                    "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                    "vlseg4e16.v v0, ({p})",
+                    "vlse16.v v16, ({zeta}), {stride}",
+                    "vlse16.v v17, ({zeta_shoup}), {stride}",
+                    gs_butterfly!("v0", "v2"),
+                    gs_butterfly!("v1", "v3"),
+                    "vsseg4e16.v v0, ({p})",
+                    vl = out(reg) vl,
+                    avl = in(reg) avl,
+                    p = in(reg) p,
+                    zeta = in(reg) zeta,
+                    zeta_shoup = in(reg) zeta_shoup,
+                    stride = in(reg) stride,
+                    q = in(reg) Q,
+                    out("v0") _, out("v1") _, out("v2") _, out("v3") _,
+                    out("v16") _, out("v17") _, out("v20") _, out("v21") _,
+                    options(nostack),
+                );
+            }
+        } else {
+            // SAFETY: `vl ≤ avl` groups of `f` are accessed; the strided zeta loads read entries
+            // `2k, 2k + 2, ..` up to `2(k0 + 63) < 256`
+            unsafe {
+                asm!(
+                    "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                    "vlseg4e16.v v0, ({p})",
+                    "vlse16.v v16, ({zeta}), {stride}",
+                    "vlse16.v v17, ({zeta_shoup}), {stride}",
+                    ct_butterfly!("v0", "v2"),
+                    ct_butterfly!("v1", "v3"),
+                    "vsseg4e16.v v0, ({p})",
+                    vl = out(reg) vl,
+                    avl = in(reg) avl,
+                    p = in(reg) p,
+                    zeta = in(reg) zeta,
+                    zeta_shoup = in(reg) zeta_shoup,
+                    stride = in(reg) stride,
+                    q = in(reg) Q,
+                    out("v0") _, out("v1") _, out("v2") _, out("v3") _,
+                    out("v16") _, out("v17") _, out("v20") _, out("v21") _,
+                    options(nostack),
+                );
+            }
+        }
+        done += vl;
+    }
+}
+
+
+/// Algorithm 8 `NTT(f)` on page 22, vectorized.
+#[must_use]
+pub(crate) fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt);
+    count!(reductions, 7 * 128);
+    let mut f_hat = *array_f;
+    let mut k = 1;
+    for len in [128, 64, 32, 16, 8] {
+        for start in (0..256).step_by(2 * len) {
+            let (lower, upper) = f_hat[start..start + 2 * len].split_at_mut(len);
+            butterflies(lower, upper, k, false);
+            k += 1;
+        }
+    }
+    butterflies_len_4(&mut f_hat, 32, false);
+    butterflies_len_2(&mut f_hat, 64, false);
+    f_hat
+}
+
+
+/// Algorithm 9 `NTTinv(f)` on page 23, vectorized.
+#[must_use]
+pub(crate) fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt_inv);
+    count!(reductions, 7 * 128 + 256);
+    let mut f = *f_hat;
+    butterflies_len_2(&mut f, 127, true);
+    butterflies_len_4(&mut f, 63, true);
+    let mut k = 31;
+    for len in [8, 16, 32, 64, 128] {
+        for start in (0..256).step_by(2 * len) {
+            let (lower, upper) = f[start..start + 2 * len].split_at_mut(len);
+            butterflies(lower, upper, k, true);
+            k -= 1;
+        }
+    }
+
+    // f ← f · 3303 mod q
+    let mut done = 0;
+    while done < 256 {
+        let p = f[done..].as_mut_ptr();
+        let vl: usize;
+        // SAFETY: `vl ≤ 256 − done`, so the load and store stay within `f`
+        unsafe {
+            asm!(
+                "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                "vle16.v v0, ({p})",
+                "vmv.v.x v16, {scale}",
+                "vmv.v.x v17, {scale_shoup}",
+                mul_shoup!("v0"),
+                "vse16.v v0, ({p})",
+                vl = out(reg) vl,
+                avl = in(reg) 256 - done,
+                p = in(reg) p,
+                scale = in(reg) F,
+                scale_shoup = in(reg) F_SHOUP,
+                q = in(reg) Q,
+                out("v0") _, out("v16") _, out("v17") _, out("v20") _,
+                options(nostack),
+            );
+        }
+        done += vl;
+    }
+    f
+}
+
+
+/// Vectorized `multiply_ntts_acc()`: a segment load splits the even and odd coefficients apart,
+/// the products widen to 32 bits, and a segment load/store re-interleaves them into `acc`.
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut [u32; 256], f_hat: &[Z256; 256], g_hat: &[Z256; 256], g_cache: &[Z256; 128],
+) {
+    let mut done = 0;
+    while done < 128 {
+        let vl: usize;
+        // SAFETY: `vl ≤ 128 − done` pairs are accessed in each of the four arrays
+        unsafe {
+            asm!(
+                "vsetvli {vl}, {avl}, e16, m1, ta, ma",
+                "vlseg2e16.v v0, ({f})",
+                "vlseg2e16.v v2, ({g})",
+                "vle16.v v4, ({g_cache})",
+                // c0 ← a0 · b0 + a1 · b1 · γ   and   c1 ← a0 · b1 + a1 · b0   (reduction deferred)
+                "vwmulu.vv v8, v0, v2",
+                "vwmaccu.vv v8, v1, v4",
+                "vwmulu.vv v10, v0, v3",
+                "vwmaccu.vv v10, v1, v2",
+                "vsetvli zero, {vl}, e32, m2, ta, ma",
+                "vlseg2e32.v v12, ({acc})",
+                "vadd.vv v12, v12, v8",
+                "vadd.vv v14, v14, v10",
+                "vsseg2e32.v v12, ({acc})",
+                vl = out(reg) vl,
+                avl = in(reg) 128 - done,
+                f = in(reg) f_hat[2 * done..].as_ptr(),
+                g = in(reg) g_hat[2 * done..].as_ptr(),
+                g_cache = in(reg) g_cache[done..].as_ptr(),
+                acc = in(reg) acc[2 * done..].as_mut_ptr(),
+                out("v0") _, out("v1") _, out("v2") _, out("v3") _, out("v4") _,
+                out("v8") _, out("v9") _, out("v10") _, out("v11") _,
+                out("v12") _, out("v13") _, out("v14") _, out("v15") _,
+                options(nostack),
+            );
+        }
+        done += vl;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+
+    use crate::ntt::mul_cache;
+    use crate::types::Z256;
+    use crate::{ntt, rvv};
+
+    fn random_poly(rng: &mut impl Rng) -> [Z256; 256] {
+        core::array::from_fn(|_| Z256(rng.gen_range(0..3329)))
+    }
+
+    fn same(a: &[Z256], b: &[Z256]) -> bool {
+        a.iter().zip(b.iter()).all(|(x, y)| x.get_u16() == y.get_u16())
+    }
+
+    #[test]
+    fn test_rvv_matches_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(128);
+        for _i in 0..100 {
+            let (f, g) = (random_poly(&mut rng), random_poly(&mut rng));
+            assert!(same(&rvv::ntt(&f), &ntt::ntt(&f)));
+            assert!(same(&rvv::ntt_inv(&f), &ntt::ntt_inv(&f)));
+
+            let (mut acc_rvv, mut acc_scalar) = ([0u32; 256], [0u32; 256]);
+            for _j in 0..4 {
+                rvv::multiply_ntts_acc(&mut acc_rvv, &f, &g, &mul_cache(&g));
+                ntt::multiply_ntts_acc(&mut acc_scalar, &f, &g, &mul_cache(&g));
+            }
+            assert_eq!(acc_rvv, acc_scalar);
+        }
+    }
+}
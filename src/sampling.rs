@@ -8,6 +8,7 @@ use crate::types::Z256;
 /// Algorithm 6 `SampleNTT(B)` on page 20.
 /// If the input is a stream of uniformly random bytes, the output is a uniformly random element of `T_q`.
 #[must_use]
+#[cfg_attr(feature = "portable-simd", allow(dead_code))]
 pub fn sample_ntt(mut byte_stream_b: impl XofReader) -> [Z256; 256] {
     // Input: byte stream B ∈ B^{∗}
     // Output: array a_hat ∈ Z^{256}_q              ▷ the coeffcients of the NTT of a polynomial
@@ -62,6 +63,10 @@ pub fn sample_ntt(mut byte_stream_b: impl XofReader) -> [Z256; 256] {
 /// Algorithm 7 `SamplePolyCBDη(B)` on page 20.
 /// If the input is a stream of uniformly random bytes, outputs a sample from the distribution Dη (Rq ).
 #[allow(clippy::unnecessary_wraps)]  // TODO: revisit
+#[cfg_attr(
+    any(feature = "portable-simd", all(target_arch = "wasm32", target_feature = "simd128")),
+    allow(dead_code)
+)]
 pub fn sample_poly_cbd(eta: u32, byte_array_b: &[u8]) -> Result<[Z256; 256], &'static str> {
     let mut array_f: [Z256; 256] = [Z256(0); 256];
     let mut temp = 0;
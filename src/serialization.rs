@@ -0,0 +1,128 @@
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::Serializer;
+use zeroize::Zeroize;
+
+// Keys, ciphertexts and shared secrets are hex strings in human-readable formats (e.g. JSON) and
+// byte strings otherwise (e.g. bincode, CBOR). Neither direction allocates, so the `serde` feature
+// works without `alloc`; the decoded array is handed to `SerDes::try_from_bytes()` for validation.
+
+
+// Implements `Serialize` and `Deserialize` for a `SerDes` byte array newtype
+macro_rules! impl_serde {
+    ($type:ident) => {
+        /// Hex in human-readable formats, bytes otherwise (available with the `serde` feature).
+        impl serde::Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                crate::serialization::serialize(&self.0, serializer)
+            }
+        }
+
+        /// Hex in human-readable formats, bytes otherwise, validated as by
+        /// `SerDes::try_from_bytes()` (available with the `serde` feature).
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let bytes = crate::serialization::deserialize(deserializer)?;
+                $type::try_from_bytes(bytes).map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_serde; // make available throughout crate
+
+
+pub(crate) fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(ByteArrayVisitor::<N>(PhantomData))
+    } else {
+        deserializer.deserialize_bytes(ByteArrayVisitor::<N>(PhantomData))
+    }
+}
+
+
+// Lowercase hex, written straight into the serializer
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.0 {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+
+struct ByteArrayVisitor<const N: usize>(PhantomData<[u8; N]>);
+
+impl<'de, const N: usize> Visitor<'de> for ByteArrayVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{N} bytes, or {} hex digits", 2 * N)
+    }
+
+    fn visit_str<E: de::Error>(self, hex: &str) -> Result<[u8; N], E> {
+        if hex.len() != 2 * N {
+            return Err(E::invalid_length(hex.len(), &self));
+        }
+        let mut bytes = [0u8; N];
+        for (byte, pair) in bytes.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+            let (Some(high), Some(low)) = (nibble(pair[0]), nibble(pair[1])) else {
+                bytes.zeroize();
+                // The input is not echoed, as it may be secret
+                return Err(E::invalid_value(de::Unexpected::Other("a non-hex digit"), &self));
+            };
+            *byte = high << 4 | low;
+        }
+        Ok(bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<[u8; N], E> {
+        value
+            .try_into()
+            .map_err(|_| E::invalid_length(value.len(), &self))
+    }
+
+    // For binary formats that encode bytes as a sequence
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[u8; N], A::Error> {
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let Some(value) = seq.next_element()? else {
+                bytes.zeroize();
+                return Err(de::Error::invalid_length(i, &self));
+            };
+            *byte = value;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            bytes.zeroize();
+            return Err(de::Error::invalid_length(N + 1, &self));
+        }
+        Ok(bytes)
+    }
+}
+
+
+// Decodes a hex digit of either case
+fn nibble(digit: u8) -> Option<u8> {
+    match digit {
+        b'0'..=b'9' => Some(digit - b'0'),
+        b'a'..=b'f' => Some(digit - b'a' + 10),
+        b'A'..=b'F' => Some(digit - b'A' + 10),
+        _ => None,
+    }
+}
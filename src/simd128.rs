@@ -0,0 +1,412 @@
+use core::arch::wasm32::{
+    i16x8_add, i16x8_extend_high_i8x16, i16x8_extend_low_i8x16, i16x8_narrow_i32x4, i16x8_shr,
+    i16x8_sub, i32x4_add, i32x4_mul, i32x4_shuffle, i32x4_sub, i8x16_add, i8x16_shuffle, i8x16_sub,
+    u16x8_min, u16x8_narrow_i32x4, u16x8_shuffle, u16x8_splat, u32x4_extmul_high_u16x8,
+    u32x4_extmul_low_u16x8, u32x4_shr, u32x4_splat, u8x16_shr, u8x16_splat, v128, v128_and,
+    v128_load, v128_store,
+};
+
+use crate::helpers::{count, ensure};
+use crate::ntt::{gen_zeta_table, ZETA_TABLE};
+use crate::sampling;
+use crate::types::Z256;
+use crate::Q;
+
+// WebAssembly SIMD128 implementations of the NTT, inverse NTT, base multiplication and CBD
+// sampling, selected by `backend.rs` when compiling with `-C target-feature=+simd128`. Each
+// function mirrors its scalar counterpart (same signature, same output) while operating on eight
+// 16-bit coefficients per vector. Products by a known zeta use Shoup multiplication with a
+// precomputed `⌊ζ · 2^16 / q⌋`, so the intermediates fit in 32-bit lanes and a single
+// conditional subtraction completes the reduction.
+
+#[allow(clippy::cast_possible_truncation)]
+const Q16: u16 = Q as u16;
+
+/// The Shoup companions `⌊ζ · 2^16 / q⌋` of each entry in `ZETA_TABLE`.
+static ZETA_SHOUP: [u16; 256] = gen_shoup_table();
+
+/// The NTT⁻¹ scaling factor `128^{-1} mod q` and its Shoup companion.
+const F: u16 = 3303;
+#[allow(clippy::cast_possible_truncation)]
+const F_SHOUP: u16 = (((F as u32) << 16) / Q) as u16;
+
+
+#[allow(clippy::cast_possible_truncation)]
+const fn gen_shoup_table() -> [u16; 256] {
+    let zetas = gen_zeta_table();
+    let mut result = [0u16; 256];
+    let mut i = 0;
+    while i < 256 {
+        result[i] = (((zetas[i] as u32) << 16) / Q) as u16;
+        i += 1;
+    }
+    result
+}
+
+
+/// Loads 16 bytes of lanes, e.g. eight coefficients or four accumulator words.
+#[inline]
+fn load<T: Copy>(lanes: &[T]) -> v128 {
+    assert_eq!(core::mem::size_of_val(lanes), 16);
+    // SAFETY: `lanes` is exactly 16 readable bytes; `v128_load` has no alignment requirement.
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+        v128_load(lanes.as_ptr().cast::<v128>())
+    }
+}
+
+
+/// Stores 16 bytes of lanes, e.g. eight coefficients or four accumulator words.
+#[inline]
+fn store<T: Copy>(lanes: &mut [T], v: v128) {
+    assert_eq!(core::mem::size_of_val(lanes), 16);
+    // SAFETY: `lanes` is exactly 16 writable bytes of plain integers (`Z256` is transparent over
+    // `u16`); `v128_store` has no alignment requirement.
+    #[allow(clippy::cast_ptr_alignment)]
+    unsafe {
+        v128_store(lanes.as_mut_ptr().cast::<v128>(), v);
+    }
+}
+
+
+/// Maps each lane in `[0, 2q)` to `[0, q)`. When `x < q`, `x − q` wraps to a larger value.
+#[inline]
+fn reduce_once(x: v128) -> v128 { u16x8_min(x, i16x8_sub(x, u16x8_splat(Q16))) }
+
+
+#[inline]
+fn add_mod(a: v128, b: v128) -> v128 { reduce_once(i16x8_add(a, b)) }
+
+
+#[inline]
+fn sub_mod(a: v128, b: v128) -> v128 { reduce_once(i16x8_add(a, i16x8_sub(u16x8_splat(Q16), b))) }
+
+
+/// Lane-wise `b · ζ mod q` given `ζ` and its Shoup companion; `b` must be below `2^16`.
+#[inline]
+fn mul_shoup(b: v128, zeta: v128, zeta_shoup: v128) -> v128 {
+    let q = u32x4_splat(Q);
+    let quot = u32x4_shr(u32x4_extmul_low_u16x8(b, zeta_shoup), 16);
+    let lo = i32x4_sub(u32x4_extmul_low_u16x8(b, zeta), i32x4_mul(quot, q));
+    let quot = u32x4_shr(u32x4_extmul_high_u16x8(b, zeta_shoup), 16);
+    let hi = i32x4_sub(u32x4_extmul_high_u16x8(b, zeta), i32x4_mul(quot, q));
+    reduce_once(u16x8_narrow_i32x4(lo, hi)) // Both halves are in [0, 2q), so no saturation
+}
+
+
+/// Per-lane zetas (and Shoup companions) for the final two layers, where one vector spans
+/// several butterfly groups; `ks[l]` is the `k` of the group that lane `l` belongs to.
+#[inline]
+fn zeta_lanes(ks: [usize; 8]) -> (v128, v128) {
+    let mut zeta = [0u16; 8];
+    let mut zeta_shoup = [0u16; 8];
+    for l in 0..8 {
+        zeta[l] = ZETA_TABLE[ks[l] << 1];
+        zeta_shoup[l] = ZETA_SHOUP[ks[l] << 1];
+    }
+    (load(&zeta), load(&zeta_shoup))
+}
+
+
+/// Algorithm 8 `NTT(f)` on page 22, vectorized.
+#[must_use]
+pub(crate) fn ntt(array_f: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt);
+    count!(reductions, 7 * 128);
+    let mut f_hat = *array_f;
+    let mut k = 1;
+
+    // len ≥ 8: every butterfly group covers whole vectors and shares one zeta
+    for len in [128, 64, 32, 16, 8] {
+        for start in (0..256).step_by(2 * len) {
+            let zeta = u16x8_splat(ZETA_TABLE[k << 1]);
+            let zeta_shoup = u16x8_splat(ZETA_SHOUP[k << 1]);
+            k += 1;
+            for j in (start..(start + len)).step_by(8) {
+                let t = mul_shoup(load(&f_hat[j + len..j + len + 8]), zeta, zeta_shoup);
+                let f_j = load(&f_hat[j..j + 8]);
+                store(&mut f_hat[j + len..j + len + 8], sub_mod(f_j, t));
+                store(&mut f_hat[j..j + 8], add_mod(f_j, t));
+            }
+        }
+    }
+
+    // len = 4: two vectors hold four groups of (4 lower, 4 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k, k, k + 1, k + 1, k + 1, k + 1]);
+        k += 2;
+        let (v0, v1) = (load(&f_hat[start..start + 8]), load(&f_hat[start + 8..start + 16]));
+        let f_j = u16x8_shuffle::<0, 1, 2, 3, 8, 9, 10, 11>(v0, v1);
+        let t = mul_shoup(u16x8_shuffle::<4, 5, 6, 7, 12, 13, 14, 15>(v0, v1), zeta, zeta_shoup);
+        let (lower, upper) = (add_mod(f_j, t), sub_mod(f_j, t));
+        store(
+            &mut f_hat[start..start + 8],
+            u16x8_shuffle::<0, 1, 2, 3, 8, 9, 10, 11>(lower, upper),
+        );
+        store(
+            &mut f_hat[start + 8..start + 16],
+            u16x8_shuffle::<4, 5, 6, 7, 12, 13, 14, 15>(lower, upper),
+        );
+    }
+
+    // len = 2: two vectors hold four groups of (2 lower, 2 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k + 1, k + 1, k + 2, k + 2, k + 3, k + 3]);
+        k += 4;
+        let (v0, v1) = (load(&f_hat[start..start + 8]), load(&f_hat[start + 8..start + 16]));
+        let f_j = u16x8_shuffle::<0, 1, 4, 5, 8, 9, 12, 13>(v0, v1);
+        let t = mul_shoup(u16x8_shuffle::<2, 3, 6, 7, 10, 11, 14, 15>(v0, v1), zeta, zeta_shoup);
+        let (lower, upper) = (add_mod(f_j, t), sub_mod(f_j, t));
+        store(
+            &mut f_hat[start..start + 8],
+            u16x8_shuffle::<0, 1, 8, 9, 2, 3, 10, 11>(lower, upper),
+        );
+        store(
+            &mut f_hat[start + 8..start + 16],
+            u16x8_shuffle::<4, 5, 12, 13, 6, 7, 14, 15>(lower, upper),
+        );
+    }
+
+    f_hat
+}
+
+
+/// Algorithm 9 `NTTinv(f)` on page 23, vectorized.
+#[must_use]
+pub(crate) fn ntt_inv(f_hat: &[Z256; 256]) -> [Z256; 256] {
+    count!(ntt_inv);
+    count!(reductions, 7 * 128 + 256);
+    let mut f = *f_hat;
+    let mut k = 127;
+
+    // len = 2: two vectors hold four groups of (2 lower, 2 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k - 1, k - 1, k - 2, k - 2, k - 3, k - 3]);
+        k -= 4;
+        let (v0, v1) = (load(&f[start..start + 8]), load(&f[start + 8..start + 16]));
+        let t = u16x8_shuffle::<0, 1, 4, 5, 8, 9, 12, 13>(v0, v1);
+        let f_jl = u16x8_shuffle::<2, 3, 6, 7, 10, 11, 14, 15>(v0, v1);
+        let (lower, upper) = (add_mod(t, f_jl), mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+        store(
+            &mut f[start..start + 8],
+            u16x8_shuffle::<0, 1, 8, 9, 2, 3, 10, 11>(lower, upper),
+        );
+        store(
+            &mut f[start + 8..start + 16],
+            u16x8_shuffle::<4, 5, 12, 13, 6, 7, 14, 15>(lower, upper),
+        );
+    }
+
+    // len = 4: two vectors hold four groups of (4 lower, 4 upper) coefficients
+    for start in (0..256).step_by(16) {
+        let (zeta, zeta_shoup) = zeta_lanes([k, k, k, k, k - 1, k - 1, k - 1, k - 1]);
+        k -= 2;
+        let (v0, v1) = (load(&f[start..start + 8]), load(&f[start + 8..start + 16]));
+        let t = u16x8_shuffle::<0, 1, 2, 3, 8, 9, 10, 11>(v0, v1);
+        let f_jl = u16x8_shuffle::<4, 5, 6, 7, 12, 13, 14, 15>(v0, v1);
+        let (lower, upper) = (add_mod(t, f_jl), mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+        store(
+            &mut f[start..start + 8],
+            u16x8_shuffle::<0, 1, 2, 3, 8, 9, 10, 11>(lower, upper),
+        );
+        store(
+            &mut f[start + 8..start + 16],
+            u16x8_shuffle::<4, 5, 6, 7, 12, 13, 14, 15>(lower, upper),
+        );
+    }
+
+    // len ≥ 8: every butterfly group covers whole vectors and shares one zeta
+    for len in [8, 16, 32, 64, 128] {
+        for start in (0..256).step_by(2 * len) {
+            let zeta = u16x8_splat(ZETA_TABLE[k << 1]);
+            let zeta_shoup = u16x8_splat(ZETA_SHOUP[k << 1]);
+            k -= 1;
+            for j in (start..(start + len)).step_by(8) {
+                let t = load(&f[j..j + 8]);
+                let f_jl = load(&f[j + len..j + len + 8]);
+                store(&mut f[j..j + 8], add_mod(t, f_jl));
+                store(&mut f[j + len..j + len + 8], mul_shoup(sub_mod(f_jl, t), zeta, zeta_shoup));
+            }
+        }
+    }
+
+    // f ← f · 3303 mod q
+    let (scale, scale_shoup) = (u16x8_splat(F), u16x8_splat(F_SHOUP));
+    for j in (0..256).step_by(8) {
+        let f_j = load(&f[j..j + 8]);
+        store(&mut f[j..j + 8], mul_shoup(f_j, scale, scale_shoup));
+    }
+
+    f
+}
+
+
+/// Vectorized `multiply_ntts_acc()`: eight degree-one products per iteration, with the even and
+/// odd coefficients split apart on load and re-interleaved into the accumulator.
+pub(crate) fn multiply_ntts_acc(
+    acc: &mut [u32; 256], f_hat: &[Z256; 256], g_hat: &[Z256; 256], g_cache: &[Z256; 128],
+) {
+    for i in (0..128).step_by(8) {
+        let (f0, f1) = (load(&f_hat[2 * i..2 * i + 8]), load(&f_hat[2 * i + 8..2 * i + 16]));
+        let (g0, g1) = (load(&g_hat[2 * i..2 * i + 8]), load(&g_hat[2 * i + 8..2 * i + 16]));
+        let a0 = u16x8_shuffle::<0, 2, 4, 6, 8, 10, 12, 14>(f0, f1);
+        let a1 = u16x8_shuffle::<1, 3, 5, 7, 9, 11, 13, 15>(f0, f1);
+        let b0 = u16x8_shuffle::<0, 2, 4, 6, 8, 10, 12, 14>(g0, g1);
+        let b1 = u16x8_shuffle::<1, 3, 5, 7, 9, 11, 13, 15>(g0, g1);
+        let b1_gamma = load(&g_cache[i..i + 8]);
+
+        // c0 ← a0 · b0 + a1 · b1 · γ   and   c1 ← a0 · b1 + a1 · b0   (reduction deferred)
+        let c0_lo = i32x4_add(u32x4_extmul_low_u16x8(a0, b0), u32x4_extmul_low_u16x8(a1, b1_gamma));
+        let c0_hi =
+            i32x4_add(u32x4_extmul_high_u16x8(a0, b0), u32x4_extmul_high_u16x8(a1, b1_gamma));
+        let c1_lo = i32x4_add(u32x4_extmul_low_u16x8(a0, b1), u32x4_extmul_low_u16x8(a1, b0));
+        let c1_hi = i32x4_add(u32x4_extmul_high_u16x8(a0, b1), u32x4_extmul_high_u16x8(a1, b0));
+
+        let products = [
+            i32x4_shuffle::<0, 4, 1, 5>(c0_lo, c1_lo),
+            i32x4_shuffle::<2, 6, 3, 7>(c0_lo, c1_lo),
+            i32x4_shuffle::<0, 4, 1, 5>(c0_hi, c1_hi),
+            i32x4_shuffle::<2, 6, 3, 7>(c0_hi, c1_hi),
+        ];
+        for (n, product) in products.into_iter().enumerate() {
+            let lanes = &mut acc[2 * i + 4 * n..2 * i + 4 * n + 4];
+            store(lanes, i32x4_add(load(lanes), product));
+        }
+    }
+}
+
+
+/// Maps signed 16-bit lanes in `(−q, q)` to `[0, q)`.
+#[inline]
+fn to_z256(c: v128) -> v128 { i16x8_add(c, v128_and(i16x8_shr(c, 15), u16x8_splat(Q16))) }
+
+
+/// Algorithm 7 `SamplePolyCBDη(B)` on page 20, vectorized for η = 2 and η = 3.
+#[allow(clippy::unnecessary_wraps)]
+pub(crate) fn sample_poly_cbd(eta: u32, byte_array_b: &[u8]) -> Result<[Z256; 256], &'static str> {
+    match eta {
+        2 => {
+            ensure!(byte_array_b.len() == 128, "Alg7: bytes len not 64 * eta");
+            Ok(cbd2(byte_array_b))
+        }
+        3 => {
+            ensure!(byte_array_b.len() == 192, "Alg7: bytes len not 64 * eta");
+            Ok(cbd3(byte_array_b))
+        }
+        _ => sampling::sample_poly_cbd(eta, byte_array_b),
+    }
+}
+
+
+/// η = 2: each byte holds two coefficients as (x, y) pairs of 2-bit fields.
+fn cbd2(byte_array_b: &[u8]) -> [Z256; 256] {
+    let mut array_f = [Z256(0); 256];
+    let (m55, m3) = (u8x16_splat(0x55), u8x16_splat(0x03));
+    for (c, bytes) in byte_array_b.chunks_exact(16).enumerate() {
+        let v = load(bytes);
+        // Count the set bits in each 2-bit field
+        let t = i8x16_add(v128_and(v, m55), v128_and(u8x16_shr(v, 1), m55));
+        // Coefficient 2i from the low nibble of byte i, 2i + 1 from the high nibble
+        let lo = i8x16_sub(v128_and(t, m3), v128_and(u8x16_shr(t, 2), m3));
+        let hi = i8x16_sub(v128_and(u8x16_shr(t, 4), m3), u8x16_shr(t, 6));
+        let c0 = i8x16_shuffle::<0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23>(lo, hi);
+        let c1 =
+            i8x16_shuffle::<8, 24, 9, 25, 10, 26, 11, 27, 12, 28, 13, 29, 14, 30, 15, 31>(lo, hi);
+        let out = &mut array_f[32 * c..32 * c + 32];
+        store(&mut out[0..8], to_z256(i16x8_extend_low_i8x16(c0)));
+        store(&mut out[8..16], to_z256(i16x8_extend_high_i8x16(c0)));
+        store(&mut out[16..24], to_z256(i16x8_extend_low_i8x16(c1)));
+        store(&mut out[24..32], to_z256(i16x8_extend_high_i8x16(c1)));
+    }
+    array_f
+}
+
+
+/// η = 3: each 3-byte group holds four coefficients as (x, y) pairs of 3-bit fields.
+fn cbd3(byte_array_b: &[u8]) -> [Z256; 256] {
+    let mut array_f = [Z256(0); 256];
+    let m249 = u32x4_splat(0x0024_9249);
+    let m7 = u32x4_splat(0x07);
+    let mut buf = [0u8; 16];
+    for (c, bytes) in byte_array_b.chunks_exact(12).enumerate() {
+        buf[0..12].copy_from_slice(bytes);
+        let v = load(&buf);
+        // Lane i holds bytes 3i, 3i + 1 and 3i + 2 (lane 16 of the second operand is zero)
+        let v = i8x16_shuffle::<0, 1, 2, 16, 3, 4, 5, 16, 6, 7, 8, 16, 9, 10, 11, 16>(
+            v,
+            u8x16_splat(0),
+        );
+        // Count the set bits in each 3-bit field
+        let t = i32x4_add(
+            i32x4_add(v128_and(v, m249), v128_and(u32x4_shr(v, 1), m249)),
+            v128_and(u32x4_shr(v, 2), m249),
+        );
+        // cj[i] is coefficient 4i + j
+        let cj = [0u32, 1, 2, 3].map(|j| {
+            let x = v128_and(u32x4_shr(t, 6 * j), m7);
+            let y = v128_and(u32x4_shr(t, 6 * j + 3), m7);
+            i32x4_sub(x, y)
+        });
+        // Transpose so that the four coefficients from each lane are adjacent
+        let t0 = i32x4_shuffle::<0, 4, 1, 5>(cj[0], cj[1]);
+        let t1 = i32x4_shuffle::<2, 6, 3, 7>(cj[0], cj[1]);
+        let t2 = i32x4_shuffle::<0, 4, 1, 5>(cj[2], cj[3]);
+        let t3 = i32x4_shuffle::<2, 6, 3, 7>(cj[2], cj[3]);
+        let c0 = i16x8_narrow_i32x4(
+            i32x4_shuffle::<0, 1, 4, 5>(t0, t2),
+            i32x4_shuffle::<2, 3, 6, 7>(t0, t2),
+        );
+        let c1 = i16x8_narrow_i32x4(
+            i32x4_shuffle::<0, 1, 4, 5>(t1, t3),
+            i32x4_shuffle::<2, 3, 6, 7>(t1, t3),
+        );
+        store(&mut array_f[16 * c..16 * c + 8], to_z256(c0));
+        store(&mut array_f[16 * c + 8..16 * c + 16], to_z256(c1));
+    }
+    array_f
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, RngCore, SeedableRng};
+
+    use crate::ntt::mul_cache;
+    use crate::types::Z256;
+    use crate::{ntt, sampling, simd128};
+
+    fn random_poly(rng: &mut impl Rng) -> [Z256; 256] {
+        core::array::from_fn(|_| Z256(rng.gen_range(0..3329)))
+    }
+
+    fn same(a: &[Z256], b: &[Z256]) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(x, y)| x.get_u16() == y.get_u16())
+    }
+
+    #[test]
+    fn test_simd128_matches_scalar() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(203);
+        for _i in 0..100 {
+            let (f, g) = (random_poly(&mut rng), random_poly(&mut rng));
+            assert!(same(&simd128::ntt(&f), &ntt::ntt(&f)));
+            assert!(same(&simd128::ntt_inv(&f), &ntt::ntt_inv(&f)));
+
+            let (mut acc_simd, mut acc_scalar) = ([0u32; 256], [0u32; 256]);
+            simd128::multiply_ntts_acc(&mut acc_simd, &f, &g, &mul_cache(&g));
+            ntt::multiply_ntts_acc(&mut acc_scalar, &f, &g, &mul_cache(&g));
+            assert_eq!(acc_simd, acc_scalar);
+
+            for eta in [2, 3] {
+                let mut bytes = [0u8; 192];
+                rng.fill_bytes(&mut bytes);
+                let bytes = &bytes[0..64 * eta as usize];
+                assert!(same(
+                    &simd128::sample_poly_cbd(eta, bytes).unwrap(),
+                    &sampling::sample_poly_cbd(eta, bytes).unwrap()
+                ));
+            }
+        }
+    }
+}
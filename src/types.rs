@@ -1,3 +1,4 @@
+use crate::helpers::count;
 use crate::Q;
 
 // While Z256 is nice, simple and correct, the performance is atrocious.
@@ -5,6 +6,7 @@ use crate::Q;
 
 /// Stored as u16, but arithmetic as u32 (so we can multiply/reduce/etc)
 #[derive(Clone, Copy)]
+#[repr(transparent)] // The SIMD backends load and store coefficients as u16 lanes
 pub struct Z256(pub u16);
 
 #[allow(clippy::inline_always)]
@@ -32,8 +34,22 @@ impl Z256 {
         Self(result)
     }
 
+    /// Barrett reduction of a wide (unreduced) sum of products, such as those accumulated by the
+    /// fused multiply-accumulate kernels in `ntt.rs`.
+    #[inline(always)]
+    pub fn reduce_u32(wide: u32) -> Self {
+        count!(reductions, 1);
+        let wide = u64::from(wide);
+        let quot = (wide * Self::M) >> 32;
+        let rem = wide - quot * Self::Q64;
+        let (diff, borrow) = rem.overflowing_sub(Self::Q64);
+        let result = if borrow { rem } else { diff }; // Not quite CT
+        Self(u16::try_from(result).unwrap())
+    }
+
     #[inline(always)]
     pub fn mul(self, other: Self) -> Self {
+        count!(reductions, 1);
         let prod = u64::from(self.0) * u64::from(other.0);
         let quot = prod * Self::M;
         let quot = quot >> (32);
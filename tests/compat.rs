@@ -0,0 +1,140 @@
+// Tests for the `compat` feature: the `_derand` entry points reproduce the NIST vectors from
+// their seeds, the randomized ones round trip, and both naming schemes return the same codes.
+#![cfg(feature = "compat")]
+
+use std::fs;
+
+use fips203::compat::{ml_kem_1024, ml_kem_512, ml_kem_768, OQS_ERROR, OQS_SUCCESS};
+use regex::Regex;
+
+fn field(filename: &str, name: &str) -> Vec<u8> {
+    let data = fs::read_to_string(filename).expect("Unable to read file");
+    let regex = Regex::new(&format!(r"{name}: ([0-9a-fA-F]+)")).unwrap();
+    hex::decode(regex.captures(&data).unwrap().get(1).unwrap().as_str()).unwrap()
+}
+
+macro_rules! compat_vectors {
+    ($module:ident, $name:literal, $keypair_derand:ident, $encaps:ident, $test_name:ident) => {
+        #[test]
+        fn $test_name() {
+            use $module::*;
+            let dir = "./tests/nist_vectors";
+
+            // Key generation from d ∥ z
+            let file = format!("{dir}/Key Generation -- {}.txt", $name);
+            let coins: [u8; 64] = [field(&file, "d"), field(&file, "z")]
+                .concat()
+                .try_into()
+                .unwrap();
+            let (mut pk, mut sk) = ([0u8; CRYPTO_PUBLICKEYBYTES], [0u8; CRYPTO_SECRETKEYBYTES]);
+            assert_eq!(crypto_kem_keypair_derand(&mut pk, &mut sk, &coins), 0);
+            assert_eq!(pk.to_vec(), field(&file, "ek"));
+            assert_eq!(sk.to_vec(), field(&file, "dk"));
+            let (mut pk2, mut sk2) = ([0u8; CRYPTO_PUBLICKEYBYTES], [0u8; CRYPTO_SECRETKEYBYTES]);
+            assert_eq!($keypair_derand(&mut pk2, &mut sk2, &coins), OQS_SUCCESS);
+            assert_eq!((pk, sk), (pk2, sk2));
+
+            // Encapsulation with m
+            let file = format!("{dir}/Encapsulation -- {}.txt", $name);
+            let pk: [u8; CRYPTO_PUBLICKEYBYTES] = field(&file, "ek").try_into().unwrap();
+            let coins: [u8; 32] = field(&file, "m").try_into().unwrap();
+            let (mut ct, mut ss) = ([0u8; CRYPTO_CIPHERTEXTBYTES], [0u8; CRYPTO_BYTES]);
+            assert_eq!(crypto_kem_enc_derand(&mut ct, &mut ss, &pk, &coins), 0);
+            assert_eq!(ct.to_vec(), field(&file, "c"));
+            assert_eq!(ss.to_vec(), field(&file, "K"));
+
+            // Decapsulation
+            let file = format!("{dir}/Decapsulation -- {}.txt", $name);
+            let sk: [u8; CRYPTO_SECRETKEYBYTES] = field(&file, "dk").try_into().unwrap();
+            let mut ct: [u8; CRYPTO_CIPHERTEXTBYTES] = field(&file, "c").try_into().unwrap();
+            assert_eq!(crypto_kem_dec(&mut ss, &ct, &sk), 0);
+            assert_eq!(ss.to_vec(), field(&file, "KPrime"));
+
+            // An encapsulation key with a coefficient not below q
+            let mut pk = pk;
+            pk[0] = 0xFF;
+            pk[1] |= 0x0F;
+            assert_eq!(crypto_kem_enc_derand(&mut ct, &mut ss, &pk, &coins), -1);
+            assert_eq!($encaps(&mut ct, &mut ss, &pk), OQS_ERROR);
+        }
+    };
+}
+
+compat_vectors!(
+    ml_kem_512,
+    "ML-KEM-512",
+    OQS_KEM_ml_kem_512_keypair_derand,
+    OQS_KEM_ml_kem_512_encaps,
+    test_vectors_512
+);
+compat_vectors!(
+    ml_kem_768,
+    "ML-KEM-768",
+    OQS_KEM_ml_kem_768_keypair_derand,
+    OQS_KEM_ml_kem_768_encaps,
+    test_vectors_768
+);
+compat_vectors!(
+    ml_kem_1024,
+    "ML-KEM-1024",
+    OQS_KEM_ml_kem_1024_keypair_derand,
+    OQS_KEM_ml_kem_1024_encaps,
+    test_vectors_1024
+);
+
+#[test]
+fn test_round_trip() {
+    use ml_kem_768::*;
+    assert_eq!(
+        (
+            CRYPTO_PUBLICKEYBYTES,
+            CRYPTO_SECRETKEYBYTES,
+            CRYPTO_CIPHERTEXTBYTES,
+            CRYPTO_BYTES
+        ),
+        (1184, 2400, 1088, 32)
+    );
+    assert_eq!(CRYPTO_ALGNAME, OQS_KEM_alg_ml_kem_768);
+    assert_eq!(OQS_KEM_ml_kem_768_length_public_key, CRYPTO_PUBLICKEYBYTES);
+
+    let (mut pk, mut sk) = ([0u8; CRYPTO_PUBLICKEYBYTES], [0u8; CRYPTO_SECRETKEYBYTES]);
+    assert_eq!(crypto_kem_keypair(&mut pk, &mut sk), 0);
+    let (mut ct, mut ss) = ([0u8; CRYPTO_CIPHERTEXTBYTES], [0u8; CRYPTO_BYTES]);
+    assert_eq!(crypto_kem_enc(&mut ct, &mut ss, &pk), 0);
+    let mut ss2 = [0u8; CRYPTO_BYTES];
+    assert_eq!(crypto_kem_dec(&mut ss2, &ct, &sk), 0);
+    assert_eq!(ss, ss2);
+
+    // The liboqs names interoperate with the PQClean ones
+    assert_eq!(OQS_KEM_ml_kem_768_keypair(&mut pk, &mut sk), OQS_SUCCESS);
+    assert_eq!(OQS_KEM_ml_kem_768_encaps(&mut ct, &mut ss, &pk), OQS_SUCCESS);
+    assert_eq!(crypto_kem_dec(&mut ss2, &ct, &sk), 0);
+    assert_eq!(ss, ss2);
+
+    // A modified ciphertext decapsulates to the implicit rejection secret, still returning 0
+    ct[0] ^= 1;
+    assert_eq!(OQS_KEM_ml_kem_768_decaps(&mut ss2, &ct, &sk), OQS_SUCCESS);
+    assert_ne!(ss, ss2);
+}
+
+#[test]
+fn test_coins() {
+    use fips203::compat::Coins;
+    use fips203::traits::{Encaps, KeyGen};
+    use rand_core::RngCore;
+
+    // The coins are handed out once, whole
+    let coins = [7u8; 32];
+    let mut rng = Coins::new(&coins);
+    let mut m = [0u8; 32];
+    assert!(rng.try_fill_bytes(&mut m).is_ok());
+    assert_eq!(m, coins);
+    assert!(rng.try_fill_bytes(&mut m).is_err());
+    assert!(Coins::new(&coins).try_fill_bytes(&mut [0u8; 33]).is_err());
+    assert!(Coins::new(&coins).try_fill_bytes(&mut [0u8; 16]).is_err());
+
+    // Encapsulation draws exactly m, and key generation (drawing z and d) is refused
+    let (ek, _dk) = fips203::ml_kem_768::KG::try_keygen_from_seed_vt(&[1u8; 64]).unwrap();
+    assert!(ek.try_encaps_with_rng_vt(&mut Coins::new(&coins)).is_ok());
+    assert!(fips203::ml_kem_768::KG::try_keygen_with_rng_vt(&mut Coins::new(&coins)).is_err());
+}
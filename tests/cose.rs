@@ -0,0 +1,235 @@
+// Tests for the `cose` feature: AKP COSE_Keys round trip and are checked on decode, and
+// COSE_Encrypt0/COSE_Encrypt messages with ML-KEM direct key agreement round trip and reject
+// tampering, other keys and other external AAD.
+#![cfg(feature = "cose")]
+
+use fips203::cose::{ContentAlgorithm, OVERHEAD};
+use fips203::traits::{KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+use rand_chacha::rand_core::SeedableRng;
+
+const ALGS: [ContentAlgorithm; 3] = [
+    ContentAlgorithm::A128Gcm,
+    ContentAlgorithm::A192Gcm,
+    ContentAlgorithm::A256Gcm,
+];
+
+macro_rules! round_trip {
+    ($module:ident, $ct_len:literal, $test_name:ident) => {
+        #[test]
+        fn $test_name() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+            let seed = [5u8; 64];
+            let (ek, dk) = $module::KG::try_keygen_from_seed_vt(&seed).unwrap();
+            let mut buf = [0u8; 3200];
+
+            // Public and private COSE_Keys
+            let len = ek.to_cose_key(&mut buf).unwrap();
+            let public_key = buf[..len].to_vec();
+            assert_eq!(public_key[0], 0xA3); // A map of kty, alg and pub
+            let decoded = $module::EncapsKey::try_from_cose_key(&public_key).unwrap();
+            assert_eq!(decoded.into_bytes(), ek.clone().into_bytes());
+            assert!($module::KG::try_keygen_from_cose_key(&public_key).is_err());
+
+            let len = $module::KG::seed_to_cose_key(&seed, &mut buf).unwrap();
+            let private_key = buf[..len].to_vec();
+            assert_eq!(private_key[0], 0xA4);
+            let (ek2, dk2) = $module::KG::try_keygen_from_cose_key(&private_key).unwrap();
+            assert_eq!(ek2.into_bytes(), ek.clone().into_bytes());
+            assert_eq!(dk2.into_bytes(), dk.clone().into_bytes());
+            let decoded = $module::EncapsKey::try_from_cose_key(&private_key).unwrap();
+            assert_eq!(decoded.into_bytes(), ek.clone().into_bytes());
+            assert!(ek.to_cose_key(&mut buf[..len - 100]).is_err());
+
+            // Both message structures, with and without external AAD
+            let mut plaintext = [0u8; 100];
+            for alg in ALGS {
+                for external_aad in [&b""[..], b"header"] {
+                    let len = ek
+                        .cose_encrypt0(&mut rng, alg, b"hello", external_aad, &mut buf)
+                        .unwrap();
+                    assert_eq!(buf[0], 0xD0); // Tag 16
+                    assert!(len <= $ct_len + 5 + OVERHEAD);
+                    let pt_len = dk
+                        .cose_decrypt0(&buf[..len], external_aad, &mut plaintext)
+                        .unwrap();
+                    assert_eq!(&plaintext[..pt_len], b"hello");
+                    assert!(dk
+                        .cose_decrypt(&buf[..len], external_aad, &mut plaintext)
+                        .is_err());
+
+                    let len = ek
+                        .cose_encrypt(&mut rng, alg, b"hello", external_aad, &mut buf)
+                        .unwrap();
+                    assert_eq!(buf[..2], [0xD8, 0x60]); // Tag 96
+                    assert!(len <= $ct_len + 5 + OVERHEAD);
+                    let pt_len = dk
+                        .cose_decrypt(&buf[..len], external_aad, &mut plaintext)
+                        .unwrap();
+                    assert_eq!(&plaintext[..pt_len], b"hello");
+                    assert!(dk
+                        .cose_decrypt0(&buf[..len], external_aad, &mut plaintext)
+                        .is_err());
+
+                    // The tag is optional
+                    let pt_len = dk
+                        .cose_decrypt(&buf[2..len], external_aad, &mut plaintext)
+                        .unwrap();
+                    assert_eq!(&plaintext[..pt_len], b"hello");
+                }
+            }
+            let len = ek
+                .cose_encrypt0(&mut rng, ALGS[0], b"", b"", &mut buf)
+                .unwrap();
+            assert_eq!(dk.cose_decrypt0(&buf[..len], b"", &mut []), Ok(0));
+        }
+    };
+}
+
+round_trip!(ml_kem_512, 768, test_round_trip_512);
+round_trip!(ml_kem_768, 1088, test_round_trip_768);
+round_trip!(ml_kem_1024, 1568, test_round_trip_1024);
+
+#[test]
+fn test_cose_key_rejects() {
+    let (ek, _dk) = ml_kem_768::KG::try_keygen_from_seed_vt(&[5u8; 64]).unwrap();
+    let mut buf = [0u8; 1300];
+    let len = ek.to_cose_key(&mut buf).unwrap();
+    let key = &buf[..len];
+
+    // Another parameter set, a truncated key or trailing data
+    assert!(ml_kem_512::EncapsKey::try_from_cose_key(key).is_err());
+    assert!(ml_kem_768::EncapsKey::try_from_cose_key(&key[..len - 1]).is_err());
+    assert!(ml_kem_768::EncapsKey::try_from_cose_key(&[key, &[0]].concat()).is_err());
+
+    // Another key type: {1: 1 (OKP), ...}
+    assert_eq!(key[1..3], [0x01, 0x07]);
+    let mut okp = key.to_vec();
+    okp[2] = 0x01;
+    assert!(ml_kem_768::EncapsKey::try_from_cose_key(&okp).is_err());
+
+    // A coefficient not below q, following the map head, two labels and values, pub label and
+    // byte string head (0x59 0x04 0xA0)
+    let mut bad = key.to_vec();
+    let pub_start = len - 1184;
+    assert_eq!(bad[pub_start - 3..pub_start], [0x59, 0x04, 0xA0]);
+    bad[pub_start] = 0xFF;
+    bad[pub_start + 1] |= 0x0F;
+    assert!(ml_kem_768::EncapsKey::try_from_cose_key(&bad).is_err());
+
+    // A private key whose pub is not derived from priv
+    let len = ml_kem_768::KG::seed_to_cose_key(&[6u8; 64], &mut buf).unwrap();
+    let mut private_key = buf[..len].to_vec();
+    let pub_start = private_key.len() - 1184 - 2 - 64;
+    private_key[pub_start..pub_start + 1184].copy_from_slice(&ek.into_bytes());
+    assert!(ml_kem_768::KG::try_keygen_from_cose_key(&private_key).is_err());
+}
+
+#[test]
+fn test_message_rejects() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (_ek, other_dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (ek_512, dk_512) = ml_kem_512::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let mut buf = [0u8; 1300];
+    let mut plaintext = [0u8; 16];
+
+    for encrypt0 in [true, false] {
+        let encrypt = if encrypt0 {
+            ml_kem_768::EncapsKey::cose_encrypt0
+        } else {
+            ml_kem_768::EncapsKey::cose_encrypt
+        };
+        let decrypt = if encrypt0 {
+            ml_kem_768::DecapsKey::cose_decrypt0
+        } else {
+            ml_kem_768::DecapsKey::cose_decrypt
+        };
+        let len = encrypt(&ek, &mut rng, ALGS[2], b"hello", b"aad", &mut buf).unwrap();
+        let message = buf[..len].to_vec();
+        assert_eq!(decrypt(&dk, &message, b"aad", &mut plaintext), Ok(5));
+
+        // Another key, other external AAD, a short output buffer or any bit flipped
+        assert!(decrypt(&other_dk, &message, b"aad", &mut plaintext).is_err());
+        assert!(decrypt(&dk, &message, b"aae", &mut plaintext).is_err());
+        assert!(decrypt(&dk, &message, b"aad", &mut plaintext[..4]).is_err());
+        for i in 0..len {
+            let mut tampered = message.clone();
+            tampered[i] ^= 0x01;
+            plaintext = [0xAA; 16];
+            if decrypt(&dk, &tampered, b"aad", &mut plaintext).is_ok() {
+                panic!("tampered byte {i} accepted");
+            }
+            assert!(plaintext[..5] == [0; 5] || plaintext[..5] == [0xAA; 5]);
+        }
+        assert!(decrypt(&dk, &message[..len - 1], b"aad", &mut plaintext).is_err());
+    }
+
+    // A message for another parameter set
+    let len = ek_512
+        .cose_encrypt(&mut rng, ALGS[0], b"hello", b"", &mut buf)
+        .unwrap();
+    assert!(dk.cose_decrypt(&buf[..len], b"", &mut plaintext).is_err());
+    assert!(dk_512
+        .cose_decrypt(&buf[..len], b"", &mut plaintext)
+        .is_ok());
+
+    // An output buffer too small for the message, or external AAD that is too long
+    assert!(ek
+        .cose_encrypt0(&mut rng, ALGS[0], b"hello", b"", &mut buf[..1100])
+        .is_err());
+    let external_aad = [0u8; fips203::cose::MAX_EXTERNAL_AAD_LEN + 1];
+    assert!(ek
+        .cose_encrypt0(&mut rng, ALGS[0], b"hello", &external_aad, &mut buf)
+        .is_err());
+    let len = ek
+        .cose_encrypt0(&mut rng, ALGS[0], b"hello", &external_aad[1..], &mut buf)
+        .unwrap();
+    assert_eq!(dk.cose_decrypt0(&buf[..len], &external_aad[1..], &mut plaintext), Ok(5));
+}
+
+#[test]
+fn test_header_rejects() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let mut buf = [0u8; 1300];
+    let mut plaintext = [0u8; 16];
+    let len = ek
+        .cose_encrypt0(&mut rng, ALGS[1], b"hello", b"", &mut buf)
+        .unwrap();
+
+    // 16([h'A10102', {5: iv, -4: ek}, ciphertext])
+    assert_eq!(buf[..7], [0xD0, 0x83, 0x43, 0xA1, 0x01, 0x02, 0xA2]);
+    let (head, rest) = buf[..len].split_at(7);
+
+    // A critical header parameter, a duplicate or the same label in both maps
+    for (protected, unprotected_extra) in [
+        (&[0x46, 0xA2, 0x01, 0x02, 0x02, 0x81, 0x01][..], &[][..]),
+        (&[0x45, 0xA2, 0x01, 0x02, 0x01, 0x02], &[]),
+        (&[0x43, 0xA1, 0x01, 0x02], &[0x01, 0x02]),
+    ] {
+        let mut message = vec![0xD0, 0x83];
+        message.extend_from_slice(protected);
+        message.push(if unprotected_extra.is_empty() {
+            0xA2
+        } else {
+            0xA3
+        });
+        message.extend_from_slice(unprotected_extra);
+        message.extend_from_slice(rest);
+        assert!(dk.cose_decrypt0(&message, b"", &mut plaintext).is_err());
+    }
+
+    // Unknown labels are skipped but still authenticated in the protected header
+    let mut message = vec![0xD0, 0x83];
+    message.extend_from_slice(&head[2..6]);
+    message.push(0xA3);
+    message.extend_from_slice(&[0x04, 0x42, 0x6B, 0x31]); // kid: h'6B31'
+    message.extend_from_slice(rest);
+    assert_eq!(dk.cose_decrypt0(&message, b"", &mut plaintext), Ok(5));
+    let mut message = vec![
+        0xD0, 0x83, 0x47, 0xA2, 0x01, 0x02, 0x04, 0x42, 0x6B, 0x31, 0xA2,
+    ];
+    message.extend_from_slice(rest);
+    assert!(dk.cose_decrypt0(&message, b"", &mut plaintext).is_err());
+}
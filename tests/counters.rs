@@ -0,0 +1,89 @@
+// Checks the operation counts recorded by the `counters` feature against those implied by the
+// spec (Algorithms 12-17). The counters are global, so this file holds a single test.
+
+#![cfg(feature = "counters")]
+
+use rand_chacha::rand_core::SeedableRng;
+
+use fips203::counters::{self, OpCounts};
+use fips203::traits::{Decaps, Encaps, KeyGen};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+
+// NTTs and reductions are fixed per parameter set; Keccak permutations depend on how many bytes
+// SampleNTT rejects, so only their lower bound is checked. Each NTT reduces 7 layers of 128
+// products, each inverse NTT also its 256 scaled outputs, each `b1 · γ` cache 128 products and
+// each accumulator 256 coefficients.
+fn check(
+    counts: OpCounts, ntt: usize, ntt_inv: usize, mul_caches: usize, reduce_accs: usize,
+    min_keccak_f: usize,
+) {
+    assert_eq!(counts.ntt, ntt);
+    assert_eq!(counts.ntt_inv, ntt_inv);
+    assert_eq!(
+        counts.reductions,
+        896 * ntt + 1152 * ntt_inv + 128 * mul_caches + 256 * reduce_accs
+    );
+    assert!(counts.keccak_f >= min_keccak_f, "{} < {min_keccak_f}", counts.keccak_f);
+    assert!(counts.keccak_f <= min_keccak_f + 8, "{} > {min_keccak_f} + 8", counts.keccak_f);
+}
+
+fn sum(counts: impl Iterator<Item = OpCounts>) -> OpCounts {
+    counts.fold(OpCounts::default(), |acc, c| OpCounts {
+        ntt: acc.ntt + c.ntt,
+        ntt_inv: acc.ntt_inv + c.ntt_inv,
+        keccak_f: acc.keccak_f + c.keccak_f,
+        reductions: acc.reductions + c.reductions,
+    })
+}
+
+macro_rules! check_set {
+    ($module:ident, $k:expr, $eta1:expr, $ek_len:expr, $ct_len:expr) => {{
+        let k: usize = $k;
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+        // H(ek) absorbs ek and J absorbs z || c in 136-byte blocks; PRF squeezes 64·η bytes
+        let (h_ek, j_zc) = ($ek_len / 136 + 1, (32 + $ct_len) / 136 + 1);
+        let (prf_eta1, prf_eta2) = ((64 * $eta1 + 135) / 136, 1);
+        // Each SampleNTT needs at least 3 SHAKE128 blocks
+        let gen_a_hat = 3 * k * k;
+
+        // G, A_hat, PRF for s and e, H(ek); the ŝ cache and one accumulator per row of t̂
+        let ((ek, dk), counts) =
+            counters::measure(|| $module::KG::try_keygen_with_rng_vt(&mut rng).unwrap());
+        check(counts, 2 * k, 0, k, k, 1 + gen_a_hat + 2 * k * prf_eta1 + h_ek);
+
+        // H(ek), G, A_hat, PRF for r, e1 and e2; the r̂ cache and an accumulator per row of u and v
+        let encrypt_keccak_f = gen_a_hat + k * prf_eta1 + (k + 1) * prf_eta2;
+        let ((_ssk, ct), counts) =
+            counters::measure(|| ek.try_encaps_with_rng_vt(&mut rng).unwrap());
+        check(counts, k, k + 1, k, k + 1, h_ek + 1 + encrypt_keccak_f);
+
+        // K-PKE.Decrypt (the ŝ cache and one accumulator), then G, the re-encryption and J
+        let (_ssk, counts) = counters::measure(|| dk.try_decaps_vt(&ct).unwrap());
+        check(counts, 2 * k, 1 + k + 1, 2 * k, k + 2, 1 + encrypt_keccak_f + j_zc);
+
+        // A batch of 5 (one group of 4 interleaved items and a single one) records exactly the
+        // counts of the single calls it replaces
+        let (eks, dks) = (vec![ek; 5], vec![dk; 5]);
+        let mut batch_rng = rng.clone();
+        let single =
+            sum(eks.iter().map(|ek| counters::measure(|| ek.try_encaps_with_rng_vt(&mut rng)).1));
+        let mut ssks = vec![fips203::SharedSecretKey::default(); 5];
+        let mut cts = vec![$module::CipherText::default(); 5];
+        let (_, batch) = counters::measure(|| {
+            $module::encaps_batch(&mut batch_rng, &eks, &mut ssks, &mut cts).unwrap()
+        });
+        assert_eq!(batch, single);
+        let single =
+            sum(dks.iter().zip(&cts).map(|(dk, ct)| counters::measure(|| dk.try_decaps_vt(ct)).1));
+        let (_, batch) =
+            counters::measure(|| $module::decaps_batch(&dks, &cts, &mut ssks).unwrap());
+        assert_eq!(batch, single);
+    }};
+}
+
+#[test]
+fn test_op_counts() {
+    check_set!(ml_kem_512, 2, 3, 800, 768);
+    check_set!(ml_kem_768, 3, 2, 1184, 1088);
+    check_set!(ml_kem_1024, 4, 2, 1568, 1568);
+}
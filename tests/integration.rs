@@ -86,3 +86,240 @@ fn test_expected_flow_1024() {
         assert_eq!(bob_ssk_bytes, alice_ssk_bytes)
     }
 }
+
+#[test]
+fn test_batch_matches_single_512() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(321);
+    let (eks, dks): (Vec<_>, Vec<_>) = (0..10)
+        .map(|_| ml_kem_512::KG::try_keygen_with_rng_vt(&mut rng).unwrap())
+        .unzip();
+
+    // Bob encapsulates against all 10 keys in one call, and then again one at a time
+    let mut batch_rng = rng.clone();
+    let mut batch_ssks = vec![fips203::SharedSecretKey::default(); 10];
+    let mut batch_cts = vec![ml_kem_512::CipherText::default(); 10];
+    ml_kem_512::encaps_batch(&mut batch_rng, &eks, &mut batch_ssks, &mut batch_cts).unwrap();
+    for i in 0..10 {
+        let (ssk, ct) = eks[i].try_encaps_with_rng_vt(&mut rng).unwrap();
+        assert_eq!(ssk, batch_ssks[i]);
+        assert_eq!(ct.into_bytes(), batch_cts[i].clone().into_bytes());
+    }
+
+    // Alice decapsulates all 10 ciphertexts (with one corrupted) in one call, and then one at a time
+    let mut bad_ct = batch_cts[7].clone().into_bytes();
+    bad_ct[0] ^= 1;
+    batch_cts[7] = ml_kem_512::CipherText::try_from_bytes(bad_ct).unwrap();
+    let mut decaps_ssks = vec![fips203::SharedSecretKey::default(); 10];
+    ml_kem_512::decaps_batch(&dks, &batch_cts, &mut decaps_ssks).unwrap();
+    for i in 0..10 {
+        assert_eq!(dks[i].try_decaps_vt(&batch_cts[i]).unwrap(), decaps_ssks[i]);
+        assert_eq!(i != 7, batch_ssks[i] == decaps_ssks[i]);
+    }
+}
+
+#[test]
+fn test_batch_matches_single_768() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(654);
+    let (eks, dks): (Vec<_>, Vec<_>) = (0..10)
+        .map(|_| ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap())
+        .unzip();
+
+    let mut batch_rng = rng.clone();
+    let mut batch_ssks = vec![fips203::SharedSecretKey::default(); 10];
+    let mut batch_cts = vec![ml_kem_768::CipherText::default(); 10];
+    ml_kem_768::encaps_batch(&mut batch_rng, &eks, &mut batch_ssks, &mut batch_cts).unwrap();
+    for i in 0..10 {
+        let (ssk, ct) = eks[i].try_encaps_with_rng_vt(&mut rng).unwrap();
+        assert_eq!(ssk, batch_ssks[i]);
+        assert_eq!(ct.into_bytes(), batch_cts[i].clone().into_bytes());
+    }
+
+    let mut decaps_ssks = vec![fips203::SharedSecretKey::default(); 10];
+    ml_kem_768::decaps_batch(&dks, &batch_cts, &mut decaps_ssks).unwrap();
+    assert_eq!(batch_ssks, decaps_ssks);
+
+    // Mismatched slice lengths are rejected
+    assert!(ml_kem_768::decaps_batch(&dks, &batch_cts[1..], &mut decaps_ssks[1..]).is_err());
+}
+
+#[test]
+fn test_batch_matches_single_1024() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(987);
+    let (eks, dks): (Vec<_>, Vec<_>) = (0..10)
+        .map(|_| ml_kem_1024::KG::try_keygen_with_rng_vt(&mut rng).unwrap())
+        .unzip();
+
+    let mut batch_rng = rng.clone();
+    let mut batch_ssks = vec![fips203::SharedSecretKey::default(); 10];
+    let mut batch_cts = vec![ml_kem_1024::CipherText::default(); 10];
+    ml_kem_1024::encaps_batch(&mut batch_rng, &eks, &mut batch_ssks, &mut batch_cts).unwrap();
+    for i in 0..10 {
+        let (ssk, ct) = eks[i].try_encaps_with_rng_vt(&mut rng).unwrap();
+        assert_eq!(ssk, batch_ssks[i]);
+        assert_eq!(ct.into_bytes(), batch_cts[i].clone().into_bytes());
+    }
+
+    let mut decaps_ssks = vec![fips203::SharedSecretKey::default(); 10];
+    ml_kem_1024::decaps_batch(&dks, &batch_cts, &mut decaps_ssks).unwrap();
+    assert_eq!(batch_ssks, decaps_ssks);
+
+    // A failing rng is reported rather than panicking; `FailingRng(n)` fails after `n` draws
+    struct FailingRng(usize);
+    impl rand_chacha::rand_core::RngCore for FailingRng {
+        fn next_u32(&mut self) -> u32 { unimplemented!() }
+
+        fn next_u64(&mut self) -> u64 { unimplemented!() }
+
+        fn fill_bytes(&mut self, _dest: &mut [u8]) { unimplemented!() }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_chacha::rand_core::Error> {
+            if self.0 == 0 {
+                return Err(rand_chacha::rand_core::Error::new("no entropy"));
+            }
+            self.0 -= 1;
+            dest.fill(0xa5);
+            Ok(())
+        }
+    }
+    impl rand_chacha::rand_core::CryptoRng for FailingRng {}
+    assert_eq!(
+        ml_kem_1024::encaps_batch(&mut FailingRng(0), &eks, &mut batch_ssks, &mut batch_cts),
+        Err("Alg16: random number generator failed")
+    );
+
+    // When it fails partway, the shared secrets and ciphertexts already written are wiped
+    ml_kem_1024::encaps_batch(&mut batch_rng, &eks, &mut batch_ssks, &mut batch_cts).unwrap();
+    assert!(
+        ml_kem_1024::encaps_batch(&mut FailingRng(9), &eks, &mut batch_ssks, &mut batch_cts)
+            .is_err()
+    );
+    for (ssk, ct) in batch_ssks.iter().zip(&batch_cts) {
+        assert_eq!(*ssk, fips203::SharedSecretKey::default());
+        assert_eq!(ct.clone().into_bytes(), ml_kem_1024::CipherText::default().into_bytes());
+    }
+}
+
+#[test]
+fn test_keygen_from_seed_matches_rng_order() {
+    // try_keygen_with_rng_vt() draws z and then d, whereas the seed is d ∥ z
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(321);
+    let mut z_d = [0u8; 64];
+    rand_chacha::rand_core::RngCore::fill_bytes(&mut rng, &mut z_d);
+    let mut seed = [0u8; 64];
+    seed[..32].copy_from_slice(&z_d[32..]);
+    seed[32..].copy_from_slice(&z_d[..32]);
+
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(321);
+    let (ek1, dk1) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (ek2, dk2) = ml_kem_768::KG::try_keygen_from_seed_vt(&seed).unwrap();
+    assert_eq!(ek1.into_bytes(), ek2.into_bytes());
+    assert_eq!(dk1.into_bytes(), dk2.into_bytes());
+}
+
+macro_rules! slice_conversions {
+    ($module:ident, $test_name:ident) => {
+        #[test]
+        fn $test_name() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+            let (ek, dk) = $module::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+            let (ssk, ct) = ek.try_encaps_with_rng_vt(&mut rng).unwrap();
+
+            // A frame holding all three, written and read back without fixed-size arrays
+            let mut frame = vec![
+                0u8;
+                1 + $module::EncapsKey::LEN
+                    + $module::DecapsKey::LEN
+                    + $module::CipherText::LEN
+            ];
+            let mut pos = 1;
+            pos += ek.write_to(&mut frame[pos..]).unwrap();
+            pos += dk.write_to(&mut frame[pos..]).unwrap();
+            pos += ct.write_to(&mut frame[pos..]).unwrap();
+            assert_eq!(pos, frame.len());
+
+            let (ek_bytes, rest) = frame[1..].split_at($module::EncapsKey::LEN);
+            let (dk_bytes, ct_bytes) = rest.split_at($module::DecapsKey::LEN);
+            assert_eq!(ek_bytes, ek.as_ref());
+            assert_eq!(dk_bytes, dk.as_ref());
+            assert_eq!(ct_bytes, ct.as_ref());
+            let ek2 = $module::EncapsKey::try_from(ek_bytes).unwrap();
+            let dk2 = $module::DecapsKey::try_from(dk_bytes).unwrap();
+            let ct2 = $module::CipherText::try_from(ct_bytes).unwrap();
+            assert_eq!(ek2.as_ref(), &ek.clone().into_bytes()[..]);
+            assert_eq!(dk2.try_decaps_vt(&ct2).unwrap(), ssk);
+
+            // Precise length errors, on input and output
+            assert_eq!(
+                $module::EncapsKey::try_from(&ek_bytes[1..]).err(),
+                Some("ek shorter than EK_LEN")
+            );
+            assert_eq!(
+                $module::EncapsKey::try_from(&frame[..ek_bytes.len() + 1]).err(),
+                Some("ek longer than EK_LEN")
+            );
+            assert_eq!(
+                $module::DecapsKey::try_from(&dk_bytes[1..]).err(),
+                Some("dk shorter than DK_LEN")
+            );
+            assert_eq!(
+                $module::CipherText::try_from(&frame[..]).err(),
+                Some("ct longer than CT_LEN")
+            );
+            assert!(ct
+                .write_to(&mut frame[..$module::CipherText::LEN - 1])
+                .is_err());
+
+            // The same validation as try_from_bytes()
+            let mut bad = ek.into_bytes();
+            bad[0] = 0xFF;
+            bad[1] |= 0x0F;
+            assert!($module::EncapsKey::try_from_bytes(bad).is_err());
+            assert!($module::EncapsKey::try_from(&bad[..]).is_err());
+        }
+    };
+}
+
+slice_conversions!(ml_kem_512, test_slice_conversions_512);
+slice_conversions!(ml_kem_768, test_slice_conversions_768);
+slice_conversions!(ml_kem_1024, test_slice_conversions_1024);
+
+#[test]
+fn test_fingerprints() {
+    use sha3::{Digest, Sha3_256};
+    use std::collections::HashMap;
+
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (other_ek, _dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+
+    // H(ek) is SHA3-256, and the decapsulation key holds the same digest
+    let fingerprint = ek.fingerprint();
+    let digest: [u8; 32] = Sha3_256::digest(ek.as_ref()).into();
+    assert_eq!(fingerprint.as_bytes(), &digest);
+    assert_eq!(dk.encaps_key_fingerprint(), fingerprint);
+    assert_ne!(other_ek.fingerprint(), fingerprint);
+
+    // Renderings
+    let hex = hex::encode(digest);
+    assert_eq!(format!("{fingerprint:x}"), hex);
+    let display = fingerprint.to_string();
+    assert_eq!(display.len(), 32 * 3 - 1);
+    assert_eq!(display.replace(':', ""), hex);
+    let short_id = fingerprint.short_id().to_string();
+    assert_eq!(
+        short_id,
+        format!("{}-{}-{}-{}", &hex[..4], &hex[4..8], &hex[8..12], &hex[12..16])
+    );
+
+    // Equality and hashing of encapsulation keys
+    let decoded = ml_kem_768::EncapsKey::try_from(ek.as_ref()).unwrap();
+    assert!(decoded == ek);
+    assert!(other_ek != ek);
+    let mut recipients = HashMap::new();
+    recipients.insert(ek.clone(), "alice");
+    recipients.insert(other_ek.clone(), "bob");
+    assert_eq!(recipients[&decoded], "alice");
+    let by_fingerprint: HashMap<_, _> =
+        [(ek.fingerprint(), "alice"), (other_ek.fingerprint(), "bob")].into();
+    assert_eq!(by_fingerprint[&dk.encaps_key_fingerprint()], "alice");
+}
@@ -0,0 +1,188 @@
+// Tests for the `jose` feature: AKP JWKs round trip and are checked on decode, and JWEs with
+// ML-KEM direct key agreement round trip in the compact and JSON serializations.
+#![cfg(feature = "jose")]
+
+use fips203::jose::{derive_cek, ContentEncryption};
+use fips203::traits::{KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768, SharedSecretKey};
+use rand_chacha::rand_core::SeedableRng;
+use serde_json::Value;
+
+const ENCS: [ContentEncryption; 3] = [
+    ContentEncryption::A128Gcm,
+    ContentEncryption::A192Gcm,
+    ContentEncryption::A256Gcm,
+];
+
+macro_rules! round_trip {
+    ($module:ident, $alg:literal, $test_name:ident) => {
+        #[test]
+        fn $test_name() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+            let seed = [5u8; 64];
+            let (ek, dk) = $module::KG::try_keygen_from_seed_vt(&seed).unwrap();
+
+            // Public and private JWKs
+            let jwk: Value = serde_json::from_str(&ek.to_jwk()).unwrap();
+            assert_eq!(jwk["kty"], "AKP");
+            assert_eq!(jwk["alg"], $alg);
+            assert!(jwk.get("priv").is_none());
+            let decoded = $module::EncapsKey::try_from_jwk(&ek.to_jwk()).unwrap();
+            assert_eq!(decoded.into_bytes(), ek.clone().into_bytes());
+
+            let private_jwk = $module::KG::seed_to_jwk(&seed).unwrap();
+            let (ek2, dk2) = $module::KG::try_keygen_from_jwk(&private_jwk).unwrap();
+            assert_eq!(ek2.into_bytes(), ek.clone().into_bytes());
+            assert_eq!(dk2.into_bytes(), dk.clone().into_bytes());
+            let decoded = $module::EncapsKey::try_from_jwk(&private_jwk).unwrap();
+            assert_eq!(decoded.into_bytes(), ek.clone().into_bytes());
+            assert!($module::KG::try_keygen_from_jwk(&ek.to_jwk()).is_err());
+
+            // JWEs in both serializations
+            for enc in ENCS {
+                let jwe = ek.jwe_encrypt_compact(&mut rng, enc, b"hello").unwrap();
+                assert_eq!(jwe.split('.').nth(1), Some(""));
+                assert_eq!(*dk.jwe_decrypt_compact(&jwe).unwrap(), b"hello");
+
+                let jwe = ek
+                    .jwe_encrypt_json(&mut rng, enc, b"hello", b"header")
+                    .unwrap();
+                assert_eq!(*dk.jwe_decrypt_json(&jwe).unwrap(), b"hello");
+                let jwe = ek.jwe_encrypt_json(&mut rng, enc, b"", b"").unwrap();
+                assert!(!jwe.contains("\"aad\""));
+                assert!(dk.jwe_decrypt_json(&jwe).unwrap().is_empty());
+            }
+
+            // The key establishment alone
+            let (cek, ct) = ek
+                .jwe_encaps(&mut rng, ContentEncryption::A256Gcm, b"Alice", b"Bob")
+                .unwrap();
+            assert_eq!(cek.len(), 32);
+            let cek2 = dk
+                .jwe_decaps(&ct, ContentEncryption::A256Gcm, b"Alice", b"Bob")
+                .unwrap();
+            assert_eq!(cek, cek2);
+            let cek3 = dk
+                .jwe_decaps(&ct, ContentEncryption::A256Gcm, b"Alice", b"")
+                .unwrap();
+            assert_ne!(cek, cek3);
+        }
+    };
+}
+
+round_trip!(ml_kem_512, "ML-KEM-512", test_round_trip_512);
+round_trip!(ml_kem_768, "ML-KEM-768", test_round_trip_768);
+round_trip!(ml_kem_1024, "ML-KEM-1024", test_round_trip_1024);
+
+#[test]
+fn test_concat_kdf() {
+    // The ECDH-ES example of RFC 7518 appendix C, whose Z happens to be 32 bytes
+    let z = [
+        158, 86, 217, 29, 129, 113, 53, 211, 114, 131, 66, 131, 191, 132, 38, 156, 251, 49, 110,
+        163, 218, 128, 106, 72, 246, 218, 167, 121, 140, 254, 144, 196,
+    ];
+    let ssk = SharedSecretKey::try_from_bytes(z).unwrap();
+    let cek = derive_cek(ContentEncryption::A128Gcm, &ssk, b"Alice", b"Bob");
+    assert_eq!(*cek, [86, 170, 141, 234, 248, 35, 109, 32, 92, 34, 40, 205, 113, 167, 16, 26]);
+}
+
+#[test]
+fn test_jwk_rejects() {
+    let (ek, _dk) = ml_kem_768::KG::try_keygen_from_seed_vt(&[5u8; 64]).unwrap();
+    let jwk = ek.to_jwk();
+
+    // Another parameter set, key type or a malformed document
+    assert!(ml_kem_512::EncapsKey::try_from_jwk(&jwk).is_err());
+    assert!(ml_kem_768::EncapsKey::try_from_jwk(&jwk.replace("AKP", "OKP")).is_err());
+    assert!(ml_kem_768::EncapsKey::try_from_jwk(&jwk[1..]).is_err());
+
+    // A coefficient not below q
+    let mut bytes = ek.clone().into_bytes();
+    bytes[0] = 0xFF;
+    bytes[1] |= 0x0F;
+    let mut jwk: Value = serde_json::from_str(&jwk).unwrap();
+    jwk["pub"] = Value::from(base64_url(&bytes));
+    assert!(ml_kem_768::EncapsKey::try_from_jwk(&jwk.to_string()).is_err());
+
+    // A private JWK whose pub is not derived from priv, or whose priv is not 64 bytes
+    let private_jwk = ml_kem_768::KG::seed_to_jwk(&[6u8; 64]).unwrap();
+    let mut private_jwk: Value = serde_json::from_str(&private_jwk).unwrap();
+    private_jwk["pub"] = Value::from(base64_url(&ek.into_bytes()));
+    assert!(ml_kem_768::KG::try_keygen_from_jwk(&private_jwk.to_string()).is_err());
+    private_jwk["priv"] = Value::from(base64_url(&[6u8; 63]));
+    assert!(ml_kem_768::KG::try_keygen_from_jwk(&private_jwk.to_string()).is_err());
+}
+
+#[test]
+fn test_jwe_rejects() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (_ek, other_dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (ek_512, _dk) = ml_kem_512::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+
+    let jwe = ek
+        .jwe_encrypt_compact(&mut rng, ContentEncryption::A256Gcm, b"hello")
+        .unwrap();
+    assert!(other_dk.jwe_decrypt_compact(&jwe).is_err());
+    for i in 0..jwe.len() {
+        let mut tampered = jwe.clone().into_bytes();
+        tampered[i] = if tampered[i] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert!(dk.jwe_decrypt_compact(&tampered).is_err());
+    }
+    let jwe_512 = ek_512
+        .jwe_encrypt_compact(&mut rng, ContentEncryption::A256Gcm, b"hello")
+        .unwrap();
+    assert!(dk.jwe_decrypt_compact(&jwe_512).is_err());
+
+    // The JSON AAD is authenticated
+    let jwe = ek
+        .jwe_encrypt_json(&mut rng, ContentEncryption::A128Gcm, b"hello", b"aad")
+        .unwrap();
+    let mut jwe: Value = serde_json::from_str(&jwe).unwrap();
+    jwe["aad"] = Value::from(base64_url(b"aae"));
+    assert!(dk.jwe_decrypt_json(&jwe.to_string()).is_err());
+}
+
+#[test]
+fn test_jwe_general_json() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+
+    let jwe = ek
+        .jwe_encrypt_json(&mut rng, ContentEncryption::A256Gcm, b"hello", b"")
+        .unwrap();
+    let flattened: Value = serde_json::from_str(&jwe).unwrap();
+    let general = serde_json::json!({
+        "recipients": [{"header": {"alg": "ML-KEM-768", "enc": "A256GCM"}}],
+        "protected": flattened["protected"],
+        "iv": flattened["iv"],
+        "ciphertext": flattened["ciphertext"],
+        "tag": flattened["tag"],
+    });
+    // Header parameters may not be repeated across the protected and recipient headers
+    assert!(dk.jwe_decrypt_json(&general.to_string()).is_err());
+
+    let mut two = general.clone();
+    two["recipients"] = serde_json::json!([{}, {}]);
+    assert!(dk.jwe_decrypt_json(&two.to_string()).is_err());
+
+    let mut one = general;
+    one["recipients"] = serde_json::json!([{"encrypted_key": ""}]);
+    assert_eq!(*dk.jwe_decrypt_json(&one.to_string()).unwrap(), b"hello");
+}
+
+fn base64_url(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut out = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+        }
+    }
+    out
+}
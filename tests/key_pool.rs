@@ -0,0 +1,125 @@
+// Tests for the `std` feature: the key pool must hand out valid, distinct key pairs in the order
+// its random number generator produces them, and surface a failing generator as an error.
+#![cfg(feature = "std")]
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand_chacha::rand_core::{CryptoRng, Error, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768, KeyPool};
+
+#[test]
+fn test_key_pool_matches_sequential_keygen_512() {
+    let pool =
+        KeyPool::<ml_kem_512::KG>::try_new_with_rng_factory(4, || ChaCha20Rng::seed_from_u64(123))
+            .unwrap();
+    let mut rng = ChaCha20Rng::seed_from_u64(123);
+    for _i in 0..10 {
+        let (ek1, dk1) = pool.take().unwrap();
+        let (ek2, dk2) = ml_kem_512::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+        assert_eq!(ek1.into_bytes(), ek2.into_bytes());
+        assert_eq!(dk1.into_bytes(), dk2.into_bytes());
+    }
+}
+
+#[test]
+fn test_key_pool_fills_to_capacity_768() {
+    let pool =
+        KeyPool::<ml_kem_768::KG>::try_new_with_rng_factory(3, || ChaCha20Rng::seed_from_u64(456))
+            .unwrap();
+    assert_eq!(pool.capacity(), 3);
+    let start = Instant::now();
+    while pool.len() < 3 {
+        assert!(start.elapsed() < Duration::from_secs(10), "pool did not fill");
+        thread::sleep(Duration::from_millis(1));
+    }
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(pool.len(), 3); // Never beyond capacity
+
+    let (ek, dk) = pool.take_ready().unwrap();
+    let (ssk, ct) = ek.try_encaps_vt().unwrap();
+    assert_eq!(ssk, dk.try_decaps_vt(&ct).unwrap());
+}
+
+#[test]
+fn test_key_pool_hands_out_each_pair_once_1024() {
+    let pool = Arc::new(KeyPool::<ml_kem_1024::KG>::try_new(2).unwrap());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = Arc::clone(&pool);
+            thread::spawn(move || {
+                (0..5)
+                    .map(|_| pool.take().unwrap().0.into_bytes().to_vec())
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut eks: Vec<_> = handles
+        .into_iter()
+        .flat_map(|h| h.join().unwrap())
+        .collect();
+    assert_eq!(eks.len(), 20);
+    eks.sort();
+    eks.dedup();
+    assert_eq!(eks.len(), 20);
+}
+
+#[test]
+fn test_key_pool_errors() {
+    assert!(KeyPool::<ml_kem_512::KG>::try_new(0).is_err());
+
+    // A generator that fails after its first 64 bytes (one key pair) stops the worker
+    struct FailingRng(usize);
+    impl RngCore for FailingRng {
+        fn next_u32(&mut self) -> u32 { unimplemented!() }
+
+        fn next_u64(&mut self) -> u64 { unimplemented!() }
+
+        fn fill_bytes(&mut self, _dest: &mut [u8]) { unimplemented!() }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            if self.0 < dest.len() {
+                return Err(Error::new("exhausted"));
+            }
+            self.0 -= dest.len();
+            dest.fill(7);
+            Ok(())
+        }
+    }
+    impl CryptoRng for FailingRng {}
+
+    let pool = KeyPool::<ml_kem_512::KG>::try_new_with_rng_factory(4, || FailingRng(64)).unwrap();
+    assert!(pool.take().is_ok());
+    assert!(pool.take().is_err());
+    assert!(pool.take_ready().is_none());
+}
+
+#[test]
+fn test_key_pool_worker_panics() {
+    // A panicking factory or generator is reported by `take()` rather than leaving it waiting
+    let pool = KeyPool::<ml_kem_512::KG>::try_new_with_rng_factory(4, || -> ChaCha20Rng {
+        panic!("no generator")
+    })
+    .unwrap();
+    assert_eq!(pool.take().err(), Some("KeyPool: worker thread panicked"));
+
+    struct PanickingRng;
+    impl RngCore for PanickingRng {
+        fn next_u32(&mut self) -> u32 { unimplemented!() }
+
+        fn next_u64(&mut self) -> u64 { unimplemented!() }
+
+        fn fill_bytes(&mut self, _dest: &mut [u8]) { unimplemented!() }
+
+        fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> Result<(), Error> { panic!("broken") }
+    }
+    impl CryptoRng for PanickingRng {}
+
+    let pool = KeyPool::<ml_kem_768::KG>::try_new_with_rng_factory(4, || PanickingRng).unwrap();
+    assert!(pool.take().is_err());
+    assert!(pool.take_ready().is_none());
+}
@@ -0,0 +1,56 @@
+// Tests for the `rayon` feature: parallel key generation must be deterministic given the master
+// seed (and match sequential key generation), and parallel decapsulation must match `try_decaps_vt`.
+#![cfg(feature = "rayon")]
+
+use rand_chacha::rand_core::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use fips203::traits::{Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+
+#[test]
+fn test_par_keygen_deterministic_512() {
+    let mut rng1 = ChaCha20Rng::seed_from_u64(123);
+    let mut rng2 = ChaCha20Rng::seed_from_u64(123);
+    let keys1 = ml_kem_512::KG::par_keygen(33, &mut rng1, ChaCha20Rng::from_seed).unwrap();
+    let keys2 = ml_kem_512::KG::par_keygen(33, &mut rng2, ChaCha20Rng::from_seed).unwrap();
+    assert_eq!(keys1.len(), 33);
+    for ((ek1, dk1), (ek2, dk2)) in keys1.into_iter().zip(keys2) {
+        assert_eq!(ek1.into_bytes(), ek2.into_bytes());
+        assert_eq!(dk1.into_bytes(), dk2.into_bytes());
+    }
+
+    // The same as drawing the per-key seeds by hand and generating each key sequentially
+    let mut rng3 = ChaCha20Rng::seed_from_u64(123);
+    let keys3 = ml_kem_512::KG::par_keygen(4, &mut rng3, ChaCha20Rng::from_seed).unwrap();
+    let mut rng4 = ChaCha20Rng::seed_from_u64(123);
+    for (ek3, dk3) in keys3 {
+        let mut seed = [0u8; 32];
+        rng4.fill_bytes(&mut seed);
+        let mut item_rng = ChaCha20Rng::from_seed(seed);
+        let (ek4, dk4) = ml_kem_512::KG::try_keygen_with_rng_vt(&mut item_rng).unwrap();
+        assert_eq!(ek3.into_bytes(), ek4.into_bytes());
+        assert_eq!(dk3.into_bytes(), dk4.into_bytes());
+    }
+}
+
+#[test]
+fn test_par_decaps_768() {
+    let mut rng = ChaCha20Rng::seed_from_u64(456);
+    let (ek, dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let (ssks, cts): (Vec<_>, Vec<_>) = (0..20)
+        .map(|_| ek.try_encaps_with_rng_vt(&mut rng).unwrap())
+        .unzip();
+    let par_ssks = dk.par_decaps(&cts).unwrap();
+    assert_eq!(ssks, par_ssks);
+}
+
+#[test]
+fn test_par_round_trip_1024() {
+    let mut rng = ChaCha20Rng::seed_from_u64(789);
+    let keys = ml_kem_1024::KG::par_keygen(8, &mut rng, ChaCha20Rng::from_seed).unwrap();
+    for (ek, dk) in &keys {
+        let (ssk, ct) = ek.try_encaps_with_rng_vt(&mut rng).unwrap();
+        assert_eq!(dk.par_decaps(&[ct]).unwrap(), [ssk]);
+    }
+}
@@ -0,0 +1,97 @@
+// Tests for the `serde` feature: every type round trips as hex through JSON and as bytes through
+// bincode and CBOR, and deserialization validates as `SerDes::try_from_bytes()` does.
+#![cfg(feature = "serde")]
+
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768, SharedSecretKey};
+use rand_chacha::rand_core::SeedableRng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn round_trip<T: Clone + Serialize + DeserializeOwned + SerDes>(value: &T, len: usize)
+where
+    T::ByteArray: AsRef<[u8]> + PartialEq + core::fmt::Debug,
+{
+    let bytes = value.clone().into_bytes();
+
+    let json = serde_json::to_string(value).unwrap();
+    assert_eq!(json, format!("\"{}\"", hex::encode(bytes.as_ref())));
+    let decoded: T = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.into_bytes(), bytes);
+    let upper: T = serde_json::from_str(&json.to_uppercase()).unwrap();
+    assert_eq!(upper.into_bytes(), bytes);
+
+    let bincode = bincode::serialize(value).unwrap();
+    assert_eq!(bincode.len(), 8 + len);
+    let decoded: T = bincode::deserialize(&bincode).unwrap();
+    assert_eq!(decoded.into_bytes(), bytes);
+
+    let mut cbor = Vec::new();
+    ciborium::into_writer(value, &mut cbor).unwrap();
+    assert_eq!(cbor[0] >> 5, 2); // A byte string
+    let decoded: T = ciborium::from_reader(&cbor[..]).unwrap();
+    assert_eq!(decoded.into_bytes(), bytes);
+
+    // The wrong length, in any format
+    let short = format!("\"{}\"", hex::encode(&bytes.as_ref()[1..]));
+    assert!(serde_json::from_str::<T>(&short).is_err());
+    assert!(bincode::deserialize::<T>(&bincode::serialize(&bytes.as_ref()[1..]).unwrap()).is_err());
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&serde_bytes(&bytes.as_ref()[1..]), &mut cbor).unwrap();
+    assert!(ciborium::from_reader::<T, _>(&cbor[..]).is_err());
+}
+
+// A CBOR byte string, as ciborium encodes `Vec<u8>` as an array
+fn serde_bytes(bytes: &[u8]) -> ciborium::Value { ciborium::Value::Bytes(bytes.to_vec()) }
+
+macro_rules! round_trips {
+    ($module:ident, $ek_len:literal, $dk_len:literal, $ct_len:literal, $test_name:ident) => {
+        #[test]
+        fn $test_name() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+            let (ek, dk) = $module::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+            let (ssk, ct) = ek.try_encaps_with_rng_vt(&mut rng).unwrap();
+            round_trip(&ek, $ek_len);
+            round_trip(&dk, $dk_len);
+            round_trip(&ct, $ct_len);
+            round_trip(&ssk, 32);
+
+            // Deserialized keys still work together
+            let json = serde_json::to_string(&(&ek, &dk)).unwrap();
+            let (ek, dk): ($module::EncapsKey, $module::DecapsKey) =
+                serde_json::from_str(&json).unwrap();
+            let (ssk, ct) = ek.try_encaps_with_rng_vt(&mut rng).unwrap();
+            assert_eq!(dk.try_decaps_vt(&ct).unwrap(), ssk);
+        }
+    };
+}
+
+round_trips!(ml_kem_512, 800, 1632, 768, test_round_trip_512);
+round_trips!(ml_kem_768, 1184, 2400, 1088, test_round_trip_768);
+round_trips!(ml_kem_1024, 1568, 3168, 1568, test_round_trip_1024);
+
+#[test]
+fn test_validation() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+    let (ek, _dk) = ml_kem_768::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+
+    // An encapsulation key with a coefficient not below q
+    let mut bytes = ek.into_bytes();
+    bytes[0] = 0xFF;
+    bytes[1] |= 0x0F;
+    assert!(ml_kem_768::EncapsKey::try_from_bytes(bytes).is_err());
+    let json = format!("\"{}\"", hex::encode(bytes));
+    assert!(serde_json::from_str::<ml_kem_768::EncapsKey>(&json).is_err());
+    assert!(bincode::deserialize::<ml_kem_768::EncapsKey>(
+        &bincode::serialize(&bytes[..]).unwrap()
+    )
+    .is_err());
+
+    // Non-hex digits are rejected without echoing the input
+    let json = format!("\"{}\"", "g".repeat(64));
+    let err = serde_json::from_str::<SharedSecretKey>(&json)
+        .unwrap_err()
+        .to_string();
+    assert!(!err.contains("ggg"), "{err}");
+    assert!(serde_json::from_str::<SharedSecretKey>("[0]").is_err());
+}
@@ -0,0 +1,64 @@
+// Tests for the `spki` feature: encapsulation keys round trip through SubjectPublicKeyInfo DER and
+// PEM, and documents for another parameter set or with out-of-range coefficients are rejected.
+#![cfg(feature = "spki")]
+
+use rand_chacha::rand_core::SeedableRng;
+
+use fips203::spki::{DecodePublicKey, EncodePublicKey};
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+
+macro_rules! round_trip {
+    ($module:ident, $test_name:ident, $oid_arc:literal) => {
+        #[test]
+        fn $test_name() {
+            let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(123);
+            let (ek, dk) = $module::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+            let der = ek.to_public_key_der().unwrap();
+            // SEQUENCE { SEQUENCE { OID 2.16.840.1.101.3.4.4.x }, BIT STRING { 0 unused bits, ek } }
+            let ek_bytes = ek.clone().into_bytes();
+            let prefix = [
+                0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x04, $oid_arc,
+            ];
+            assert_eq!(der.as_bytes()[6..17], prefix);
+            assert!(der.as_bytes().ends_with(&ek_bytes));
+
+            let decoded = $module::EncapsKey::from_public_key_der(der.as_bytes()).unwrap();
+            assert_eq!(decoded.into_bytes(), ek_bytes);
+
+            let pem = ek
+                .to_public_key_pem(fips203::spki::der::pem::LineEnding::LF)
+                .unwrap();
+            assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\n"));
+            let decoded = $module::EncapsKey::from_public_key_pem(&pem).unwrap();
+            let (ssk, ct) = decoded.try_encaps_with_rng_vt(&mut rng).unwrap();
+            assert_eq!(dk.try_decaps_vt(&ct).unwrap(), ssk);
+        }
+    };
+}
+
+round_trip!(ml_kem_512, test_round_trip_512, 0x01);
+round_trip!(ml_kem_768, test_round_trip_768, 0x02);
+round_trip!(ml_kem_1024, test_round_trip_1024, 0x03);
+
+#[test]
+fn test_rejects_invalid_documents() {
+    let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(456);
+    let (ek, _dk) = ml_kem_512::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+    let der = ek.to_public_key_der().unwrap().as_bytes().to_vec();
+    assert_eq!(der[..4], [0x30, 0x82, 0x03, 0x32]);
+
+    // The wrong parameter set, by length and by OID alone
+    assert!(ml_kem_768::EncapsKey::from_public_key_der(&der).is_err());
+    assert!(ml_kem_1024::EncapsKey::from_public_key_der(&der).is_err());
+    let mut relabeled = der.clone();
+    relabeled[16] = 0x02; // id-alg-ml-kem-768 on an ML-KEM-512 key
+    assert!(ml_kem_512::EncapsKey::from_public_key_der(&relabeled).is_err());
+
+    // A first coefficient of 0xFFF, which is not below q
+    let mut out_of_range = der.clone();
+    out_of_range[22] = 0xFF;
+    out_of_range[23] |= 0x0F;
+    assert!(ml_kem_512::EncapsKey::from_public_key_der(&out_of_range).is_err());
+    assert!(ml_kem_512::EncapsKey::from_public_key_der(&der).is_ok());
+}
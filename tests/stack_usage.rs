@@ -0,0 +1,110 @@
+// Measures the worst-case stack depth of KeyGen, Encaps and Decaps for each parameter set by
+// painting a region of a fresh thread's stack with a known pattern, running the operation, and
+// then finding the deepest word that was overwritten. Run with `--nocapture` to see the table,
+// and compare a default build against `--features small-stack` (release numbers are the
+// meaningful ones for embedded targets, and the only ones checked against the limits below).
+// Threads are unavailable on WASI, so this is skipped there.
+
+#![cfg(not(target_os = "wasi"))]
+
+use std::hint::black_box;
+use std::thread;
+
+use rand_chacha::rand_core::SeedableRng;
+
+use fips203::traits::{Decaps, Encaps, KeyGen};
+use fips203::{ml_kem_1024, ml_kem_512, ml_kem_768};
+
+const PATTERN: u64 = 0xA5C3_96E1_5A3C_691E;
+const PAINT_LEN: usize = 512 * 1024; // Painted region below the measuring frame
+const RED_ZONE: usize = 512; // Skipped below the measuring frame (covers the x86-64 red zone)
+const THREAD_STACK: usize = 1024 * 1024; // Must comfortably exceed PAINT_LEN
+
+
+// Returns the number of stack bytes used by `op` (beyond this function's own frame).
+#[inline(never)]
+fn stack_usage(op: impl FnOnce()) -> usize {
+    let marker = 0u8;
+    let top = (black_box(core::ptr::addr_of!(marker)) as usize - RED_ZONE) & !7;
+    let bottom = top - PAINT_LEN;
+    // Safety: the region lies within this thread's (otherwise unused) stack, which is sized
+    // well beyond PAINT_LEN, and is accessed only through volatile word-aligned operations.
+    for addr in (bottom..top).step_by(8) {
+        unsafe { (addr as *mut u64).write_volatile(PATTERN) };
+    }
+    op();
+    let mut addr = bottom;
+    while addr < top && unsafe { (addr as *const u64).read_volatile() } == PATTERN {
+        addr += 8;
+    }
+    assert!(addr > bottom, "painted region exhausted; increase PAINT_LEN");
+    top - addr + RED_ZONE
+}
+
+
+// Runs the measurement on a dedicated thread so the painted region is guaranteed to exist.
+fn measure(op: impl FnOnce() + Send + 'static) -> usize {
+    thread::Builder::new()
+        .stack_size(THREAD_STACK)
+        .spawn(move || stack_usage(op))
+        .unwrap()
+        .join()
+        .unwrap()
+}
+
+
+macro_rules! measure_param_set {
+    ($name:literal, $module:ident, $seed:literal) => {{
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64($seed);
+        let (ek, dk) = $module::KG::try_keygen_with_rng_vt(&mut rng).unwrap();
+        let (_ssk, ct) = ek.try_encaps_with_rng_vt(&mut rng).unwrap();
+
+        let mut rng_kg = rng.clone();
+        let keygen = measure(move || {
+            black_box($module::KG::try_keygen_with_rng_vt(&mut rng_kg).unwrap());
+        });
+        let ek_enc = ek.clone();
+        let encaps = measure(move || {
+            black_box(ek_enc.try_encaps_with_rng_vt(&mut rng).unwrap());
+        });
+        let decaps = measure(move || {
+            black_box(dk.try_decaps_vt(&ct).unwrap());
+        });
+        println!(
+            "{:<12} keygen {:>7} B   encaps {:>7} B   decaps {:>7} B",
+            $name, keygen, encaps, decaps
+        );
+        (keygen, encaps, decaps)
+    }};
+}
+
+
+// Upper bounds in bytes on any one operation for ML-KEM-512, -768 and -1024 in an x86-64 release
+// build, with some headroom over the measurements. Those for the streaming K-PKE (`small-stack` or
+// `small-code`) lie below what the default build uses, so a regression in either shows up here.
+const DEFAULT_LIMITS: [usize; 3] = [24 * 1024, 32 * 1024, 44 * 1024];
+const STREAMING_LIMITS: [usize; 3] = [15 * 1024, 20 * 1024, 24 * 1024];
+
+
+#[test]
+fn test_worst_case_stack() {
+    let usage = [
+        measure_param_set!("ML-KEM-512", ml_kem_512, 11),
+        measure_param_set!("ML-KEM-768", ml_kem_768, 22),
+        measure_param_set!("ML-KEM-1024", ml_kem_1024, 33),
+    ];
+    // Debug builds and other targets lay out frames differently, so only the painting is checked
+    if cfg!(debug_assertions) || !cfg!(target_arch = "x86_64") {
+        return;
+    }
+    let limits = if cfg!(any(feature = "small-stack", feature = "small-code")) {
+        STREAMING_LIMITS
+    } else {
+        DEFAULT_LIMITS
+    };
+    for ((keygen, encaps, decaps), limit) in usage.into_iter().zip(limits) {
+        assert!(keygen <= limit, "keygen used {keygen} B, over {limit} B");
+        assert!(encaps <= limit, "encaps used {encaps} B, over {limit} B");
+        assert!(decaps <= limit, "decaps used {decaps} B, over {limit} B");
+    }
+}